@@ -0,0 +1,73 @@
+//! Smoke tests of `weak-lists` on targets without `std`.
+//!
+//! This crate only depends on `core`, `alloc`, and `weak-lists` without its default
+//! features. Building it for a target without `std`, such as `thumbv7em-none-eabihf`,
+//! verifies that this configuration of `weak-lists` does not depend on `std`.
+//!
+//! The crate contains a small event bus as it might be used in firmware. Time is
+//! provided by a tick counter instead of the system clock.
+
+#![no_std]
+
+extern crate alloc;
+
+#[cfg(test)]
+mod tests;
+
+use {
+    alloc::rc::Rc,
+    core::cell::Cell,
+    weak_lists::{unsync::WeakListBuilder, WeakList, WeakListElement},
+};
+
+/// A listener for the events of a [Bus].
+pub trait Listener {
+    fn on_event(&self, event: u32);
+}
+
+/// An event bus that holds weak references to its listeners.
+pub struct Bus {
+    listeners: WeakList<dyn Listener>,
+    ticks: Rc<Cell<u64>>,
+}
+
+impl Bus {
+    /// Creates a bus with room for `capacity` listeners.
+    pub fn new(capacity: usize) -> Self {
+        let listeners = WeakListBuilder::default().capacity(capacity).build();
+        let ticks = Rc::new(Cell::new(0));
+        listeners.set_clock({
+            let ticks = ticks.clone();
+            move || ticks.get()
+        });
+        Self { listeners, ticks }
+    }
+
+    /// Subscribes a listener to the bus.
+    ///
+    /// The listener is unsubscribed when the element is dropped.
+    pub fn subscribe(&self, element: &WeakListElement<dyn Listener>) {
+        element.attach(&self.listeners);
+    }
+
+    /// Delivers an event to all listeners and returns the number of listeners that were
+    /// dropped without unsubscribing.
+    pub fn publish(&self, event: u32) -> usize {
+        self.listeners.for_each(|listener| listener.on_event(event))
+    }
+
+    /// Advances the clock of the bus by one tick.
+    pub fn tick(&self) {
+        self.ticks.set(self.ticks.get() + 1);
+    }
+
+    /// Returns the number of ticks since the oldest dropped listener was first observed.
+    pub fn max_dead_age(&self) -> u64 {
+        self.listeners.dead_age_stats().max_age
+    }
+
+    /// Returns the listeners of the bus.
+    pub fn listeners(&self) -> &WeakList<dyn Listener> {
+        &self.listeners
+    }
+}
@@ -0,0 +1,66 @@
+use {
+    crate::{Bus, Listener},
+    alloc::{
+        rc::{Rc, Weak},
+        vec::Vec,
+    },
+    core::cell::RefCell,
+    weak_lists::{assert_members, WeakListElement},
+};
+
+struct Recorder {
+    events: RefCell<Vec<u32>>,
+    element: WeakListElement<dyn Listener>,
+}
+
+impl Recorder {
+    fn new() -> Rc<Self> {
+        Rc::new_cyclic(|slf: &Weak<Self>| {
+            let slf: Weak<dyn Listener> = slf.clone();
+            Self {
+                events: Default::default(),
+                element: WeakListElement::new(slf),
+            }
+        })
+    }
+}
+
+impl Listener for Recorder {
+    fn on_event(&self, event: u32) {
+        self.events.borrow_mut().push(event);
+        if event == 2 {
+            self.element.detach();
+        }
+    }
+}
+
+#[test]
+fn publish() {
+    let bus = Bus::new(4);
+    let recorders = [Recorder::new(), Recorder::new()];
+    for recorder in &recorders {
+        bus.subscribe(&recorder.element);
+    }
+    assert_eq!(bus.publish(1), 0);
+    assert_eq!(bus.publish(2), 0);
+    assert_eq!(bus.publish(3), 0);
+    for recorder in &recorders {
+        assert_eq!(*recorder.events.borrow(), [1, 2]);
+    }
+    assert_members!(bus.listeners(), []);
+}
+
+#[test]
+fn dropped_listeners() {
+    let bus = Bus::new(0);
+    let listener: Rc<dyn Listener> = Recorder::new();
+    let element = WeakListElement::new(Rc::downgrade(&listener));
+    bus.subscribe(&element);
+    drop(listener);
+    assert_eq!(bus.publish(1), 1);
+    bus.tick();
+    bus.tick();
+    assert_eq!(bus.max_dead_age(), 2);
+    drop(element);
+    assert_eq!(bus.publish(1), 0);
+}
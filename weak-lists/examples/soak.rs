@@ -0,0 +1,124 @@
+//! Attaches, detaches, and iterates over a `SyncWeakList` from many threads.
+//!
+//! The entries attached before the threads start stay attached for the whole run. Every
+//! iteration must yield each of them exactly once. The internal consistency of the list is
+//! checked periodically with `check_invariants`.
+//!
+//! Usage:
+//!
+//! ```text
+//! cargo run --release --example soak --features std,test-util -- [SECONDS] [THREADS]
+//! ```
+
+use {
+    std::{
+        collections::HashSet,
+        env,
+        sync::{
+            atomic::{AtomicBool, Ordering::Relaxed},
+            Arc,
+        },
+        thread,
+        time::Duration,
+    },
+    weak_lists::{SyncWeakList, SyncWeakListElement},
+};
+
+const PINNED: usize = 16;
+const ENTRIES_PER_THREAD: usize = 64;
+
+struct Entry {
+    pinned: bool,
+    element: SyncWeakListElement<Entry>,
+}
+
+impl Entry {
+    fn new(pinned: bool) -> Arc<Self> {
+        Arc::new_cyclic(|slf| Self {
+            pinned,
+            element: SyncWeakListElement::new(slf.clone()),
+        })
+    }
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let mut arg = |name: &str, default: u64| match args.next() {
+        Some(arg) => arg
+            .parse()
+            .unwrap_or_else(|_| panic!("{name} must be a number")),
+        None => default,
+    };
+    let seconds = arg("SECONDS", 10);
+    let threads = arg("THREADS", 4);
+
+    let list = SyncWeakList::default();
+    let pinned: Vec<_> = (0..PINNED).map(|_| Entry::new(true)).collect();
+    for entry in &pinned {
+        entry.element.attach(&list);
+    }
+    let stop = AtomicBool::new(false);
+    let (list, stop) = (&list, &stop);
+    let (operations, iterations) = thread::scope(|s| {
+        let churners: Vec<_> = (0..threads)
+            .map(|seed| s.spawn(move || churn(list, stop, seed)))
+            .collect();
+        let iterators: Vec<_> = (0..threads)
+            .map(|_| s.spawn(move || iterate(list, stop)))
+            .collect();
+        thread::sleep(Duration::from_secs(seconds));
+        stop.store(true, Relaxed);
+        let sum = |handles: Vec<thread::ScopedJoinHandle<'_, u64>>| {
+            handles.into_iter().map(|h| h.join().unwrap()).sum::<u64>()
+        };
+        (sum(churners), sum(iterators))
+    });
+    list.check_invariants();
+    println!("{operations} modifications, {iterations} iterations, no violations");
+}
+
+/// Randomly attaches, detaches, drops, and replaces entries until `stop` is set.
+fn churn(list: &SyncWeakList<Entry>, stop: &AtomicBool, seed: u64) -> u64 {
+    let mut state = 2 * seed + 1;
+    let mut random = move || {
+        // xorshift64
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let mut entries: Vec<_> = (0..ENTRIES_PER_THREAD).map(|_| Entry::new(false)).collect();
+    let mut operations = 0;
+    while !stop.load(Relaxed) {
+        let r = random();
+        let entry = &mut entries[r as usize % ENTRIES_PER_THREAD];
+        match (r >> 32) % 8 {
+            0..=3 => entry.element.attach(list),
+            4 | 5 => entry.element.detach(),
+            // Dropping the entry detaches its element.
+            6 => *entry = Entry::new(false),
+            _ => list.check_invariants(),
+        }
+        operations += 1;
+    }
+    operations
+}
+
+/// Iterates over the list until `stop` is set and checks the pinned entries.
+fn iterate(list: &SyncWeakList<Entry>, stop: &AtomicBool) -> u64 {
+    let mut iterations = 0;
+    while !stop.load(Relaxed) {
+        let mut seen = HashSet::new();
+        for entry in list {
+            if entry.pinned {
+                assert!(
+                    seen.insert(Arc::as_ptr(&entry)),
+                    "a pinned entry was yielded twice",
+                );
+            }
+        }
+        assert_eq!(seen.len(), PINNED, "a pinned entry was not yielded");
+        iterations += 1;
+    }
+    iterations
+}
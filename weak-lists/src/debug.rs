@@ -0,0 +1,75 @@
+//! Helpers for formatting iterators.
+//!
+//! The `Debug` implementations of the list iterators only print the ids of the entries
+//! that the iterator has not yet yielded and whether their targets are alive. They
+//! neither upgrade the targets nor invoke their `Debug` implementations. This makes
+//! them safe to use even if the `Debug` implementations of the targets access the list.
+//! Use [DebugTargets] to print the targets instead.
+//!
+//! If the `debug-registry` feature is enabled, all live lists can be printed with
+//! [dump_all].
+
+#[cfg(feature = "debug-registry")]
+pub(crate) mod registry;
+
+use core::fmt::{Debug, Formatter};
+#[cfg(feature = "debug-registry")]
+pub use registry::dump_all;
+
+/// Formats the targets that an iterator would yield.
+///
+/// The targets are obtained by iterating over a clone of the iterator. For the list
+/// iterators, this upgrades the targets and accesses the list, as if by calling `next`.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use weak_lists::{debug::DebugTargets, WeakList, WeakListElement};
+///
+/// let list = WeakList::default();
+/// let entry = Rc::new(1);
+/// let element = WeakListElement::new(Rc::downgrade(&entry));
+/// element.attach(&list);
+/// let iter = list.iter();
+/// assert_eq!(format!("{:?}", iter), "[Entry { id: 0, alive: true }]");
+/// assert_eq!(format!("{:?}", DebugTargets::new(&iter)), "[1]");
+/// ```
+pub struct DebugTargets<'a, It> {
+    iter: &'a It,
+}
+
+impl<'a, It> DebugTargets<'a, It> {
+    /// Wraps the iterator.
+    pub fn new(iter: &'a It) -> Self {
+        Self { iter }
+    }
+}
+
+impl<It> Debug for DebugTargets<'_, It>
+where
+    It: Clone + Iterator,
+    It::Item: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter.clone()).finish()
+    }
+}
+
+/// An entry printed by the `Debug` implementations of the list iterators.
+pub(crate) struct Entry<I> {
+    pub(crate) id: I,
+    pub(crate) alive: bool,
+}
+
+impl<I> Debug for Entry<I>
+where
+    I: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Entry")
+            .field("id", &self.id)
+            .field("alive", &self.alive)
+            .finish()
+    }
+}
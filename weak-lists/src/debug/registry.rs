@@ -0,0 +1,173 @@
+#[cfg(feature = "sync")]
+use std::sync::{Mutex, MutexGuard};
+use {
+    alloc::{string::String, vec::Vec},
+    core::{cell::RefCell, fmt::Write},
+};
+
+/// Statistics about a registered list.
+pub(crate) struct ListStats {
+    /// The number of entries, including the entries whose targets have been dropped.
+    pub(crate) entries: usize,
+    /// The number of entries whose targets are alive.
+    pub(crate) alive: usize,
+    pub(crate) active_iterators: usize,
+    pub(crate) iterations: u64,
+}
+
+/// A list in a registry.
+struct Record {
+    /// The address of the data of the list. This identifies the list.
+    list: usize,
+    kind: &'static str,
+    name: Option<String>,
+    /// Computes the statistics of the list at the address.
+    ///
+    /// # Safety
+    ///
+    /// The list must still be registered.
+    stats: unsafe fn(usize) -> ListStats,
+}
+
+/// The registered thread-safe lists.
+#[cfg(feature = "sync")]
+static SYNC_LISTS: Mutex<Vec<Record>> = Mutex::new(Vec::new());
+
+std::thread_local! {
+    /// The registered single-threaded lists of this thread.
+    static UNSYNC_LISTS: RefCell<Vec<Record>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Registers a list.
+///
+/// # Safety
+///
+/// `stats` must be safe to call with `list` until the list is unregistered. The list must
+/// be unregistered before it is dropped. If `sync` is `false`, the list must be
+/// unregistered on this thread.
+pub(crate) unsafe fn register(
+    sync: bool,
+    list: usize,
+    kind: &'static str,
+    name: Option<String>,
+    stats: unsafe fn(usize) -> ListStats,
+) {
+    let record = Record {
+        list,
+        kind,
+        name,
+        stats,
+    };
+    if sync {
+        #[cfg(feature = "sync")]
+        lock_sync().push(record);
+    } else {
+        let _ = UNSYNC_LISTS.try_with(|lists| lists.borrow_mut().push(record));
+    }
+}
+
+/// Unregisters a list.
+pub(crate) fn unregister(sync: bool, list: usize) {
+    let remove = |lists: &mut Vec<Record>| {
+        if let Some(pos) = lists.iter().position(|r| r.list == list) {
+            lists.swap_remove(pos);
+        }
+    };
+    if sync {
+        #[cfg(feature = "sync")]
+        remove(&mut lock_sync());
+    } else {
+        // The registry might already have been destroyed if the list is dropped while
+        // the thread exits.
+        let _ = UNSYNC_LISTS.try_with(|lists| remove(&mut lists.borrow_mut()));
+    }
+}
+
+#[cfg(feature = "sync")]
+fn lock_sync() -> MutexGuard<'static, Vec<Record>> {
+    SYNC_LISTS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// The statistics of a registered list at the time of [dump_all].
+struct Snapshot {
+    list: usize,
+    kind: &'static str,
+    name: Option<String>,
+    stats: ListStats,
+}
+
+fn snapshot(lists: &[Record]) -> Vec<Snapshot> {
+    lists
+        .iter()
+        .map(|record| Snapshot {
+            list: record.list,
+            kind: record.kind,
+            name: record.name.clone(),
+            // SAFETY: The list is still registered.
+            stats: unsafe { (record.stats)(record.list) },
+        })
+        .collect()
+}
+
+fn dump(f: &mut dyn Write, lists: &[Snapshot]) -> core::fmt::Result {
+    for Snapshot {
+        list,
+        kind,
+        name,
+        stats,
+    } in lists
+    {
+        write!(f, "{kind} ")?;
+        match name {
+            Some(name) => write!(f, "{name:?}")?,
+            None => write!(f, "<unnamed>")?,
+        }
+        writeln!(
+            f,
+            " at {:#x}: {} entries, {} alive, {} active iterators, {} iterations",
+            list, stats.entries, stats.alive, stats.active_iterators, stats.iterations,
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes the names and statistics of all live lists.
+///
+/// This includes the [WeakList](crate::WeakList)s created on the current thread and the
+/// [SyncWeakList](crate::SyncWeakList)s created on any thread. Each line describes one
+/// list. Use [debug_name](crate::unsync::WeakListBuilder::debug_name) to give a list a
+/// name. This is useful to find lists that grow without bounds because their entries
+/// are never detached.
+///
+/// This function must not be called while the current thread holds the lock of a
+/// `SyncWeakList`, for example from the `Debug` implementation of a target that is
+/// formatted while the list is locked. The registry is not locked while `f` is written
+/// to. The writer may therefore create and drop lists.
+///
+/// This function is only available if the `debug-registry` feature is enabled.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use weak_lists::{unsync::WeakListBuilder, WeakListElement};
+///
+/// let list = WeakListBuilder::default().debug_name("callbacks").build();
+/// let entry = Rc::new(1);
+/// let element = WeakListElement::new(Rc::downgrade(&entry));
+/// element.attach(&list);
+/// let mut out = String::new();
+/// weak_lists::dump_all(&mut out).unwrap();
+/// assert!(out.contains("\"callbacks\""));
+/// assert!(out.contains("1 entries, 1 alive"));
+/// ```
+pub fn dump_all(f: &mut dyn Write) -> core::fmt::Result {
+    let unsync = UNSYNC_LISTS.with(|lists| snapshot(&lists.borrow()));
+    dump(f, &unsync)?;
+    #[cfg(feature = "sync")]
+    {
+        let sync = snapshot(&lock_sync());
+        dump(f, &sync)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use core::fmt::{Display, Formatter};
+
+/// The error returned by the fallible operations of the lists and their elements.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use weak_lists::{Error, WeakList, WeakListElement};
+///
+/// let list = WeakList::default();
+/// let entries = [Rc::new(0), Rc::new(1)];
+/// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+/// assert_eq!(elements[0].attach_keyed(&list, 7), Ok(()));
+/// assert_eq!(elements[1].attach_keyed(&list, 7), Err(Error::IdInUse));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The list has been closed with `SyncWeakList::close`.
+    Closed,
+    /// The target of the element has already been dropped.
+    TargetDropped,
+    /// The list already contains the maximum number of entries with the same target.
+    ///
+    /// See [max_entries_per_target](crate::unsync::WeakListBuilder::max_entries_per_target).
+    TargetLimitReached,
+    /// The list already contains an entry with the requested id.
+    IdInUse,
+    /// All ids of the id type of the list are in use.
+    ///
+    /// See [EntryId](crate::EntryId).
+    IdsExhausted,
+    /// The list does not contain an entry with the given id.
+    NotFound,
+    /// The element is not attached to a list.
+    NotAttached,
+    /// The element has no deferred attachment.
+    ///
+    /// See [defer_attach](crate::WeakListElement::defer_attach).
+    NotDeferred,
+    /// The number of iterators over the list has reached its limit.
+    ///
+    /// See [set_max_concurrent_iterations](crate::WeakList::set_max_concurrent_iterations).
+    TooManyIterators,
+    /// The list has no free slot.
+    ///
+    /// See [StaticWeakList](crate::unsync::StaticWeakList).
+    CapacityExceeded,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let msg = match self {
+            Error::Closed => "the list has been closed",
+            Error::TargetDropped => "the target of the element has been dropped",
+            Error::TargetLimitReached => "the list contains too many entries with this target",
+            Error::IdInUse => "the list already contains an entry with this id",
+            Error::IdsExhausted => "all ids of the list are in use",
+            Error::NotFound => "the list does not contain an entry with this id",
+            Error::NotAttached => "the element is not attached to a list",
+            Error::NotDeferred => "the element has no deferred attachment",
+            Error::TooManyIterators => "the list has too many iterators",
+            Error::CapacityExceeded => "the list is full",
+        };
+        f.write_str(msg)
+    }
+}
+
+impl core::error::Error for Error {}
@@ -0,0 +1,193 @@
+//! A C interface for the thread-safe list.
+//!
+//! This module exposes a [SyncWeakList] of [FfiCallback] trait objects to C code. C code
+//! creates a list with [weak_list_new], registers callbacks with [weak_list_register],
+//! and invokes all registered callbacks with [weak_list_dispatch]. Rust code can access
+//! the same list through [FfiList] and attach its own implementations of [FfiCallback].
+//!
+//! The corresponding C declarations are
+//!
+//! ```c
+//! typedef struct WeakList WeakList;
+//! typedef struct WeakListHandle WeakListHandle;
+//! typedef void (*WeakListCallback)(void *user_data, void *arg);
+//!
+//! WeakList *weak_list_new(void);
+//! void weak_list_free(WeakList *list);
+//! WeakListHandle *weak_list_register(const WeakList *list, WeakListCallback callback, void *user_data);
+//! void weak_list_unregister(WeakListHandle *handle);
+//! size_t weak_list_dispatch(const WeakList *list, void *arg);
+//! ```
+
+#[cfg(test)]
+mod tests;
+
+use {
+    crate::sync::{SyncWeakList, SyncWeakListElement},
+    alloc::{boxed::Box, sync::Arc},
+    core::{
+        ffi::c_void,
+        fmt::{Debug, Formatter},
+    },
+};
+
+/// A callback that can be attached to an [FfiList].
+pub trait FfiCallback: Send + Sync {
+    /// Invokes the callback.
+    ///
+    /// `arg` is the argument passed to [weak_list_dispatch].
+    fn invoke(&self, arg: *mut c_void);
+}
+
+/// The list type shared with C code.
+pub type FfiList = SyncWeakList<dyn FfiCallback>;
+
+/// The type of the callbacks registered by C code.
+///
+/// The first argument is the `user_data` passed to [weak_list_register]. The second
+/// argument is the `arg` passed to [weak_list_dispatch].
+pub type FfiCallbackFn = unsafe extern "C" fn(user_data: *mut c_void, arg: *mut c_void);
+
+/// A callback registered by C code.
+///
+/// This object is created by calling [weak_list_register]. It is destroyed by calling
+/// [weak_list_unregister].
+pub struct FfiHandle {
+    element: SyncWeakListElement<dyn FfiCallback>,
+    callback: Arc<ForeignCallback>,
+}
+
+struct ForeignCallback {
+    callback: FfiCallbackFn,
+    user_data: *mut c_void,
+}
+
+// SAFETY: The C code that registers the callback guarantees that the callback and its user
+//         data can be used from any thread. See weak_list_register.
+unsafe impl Send for ForeignCallback {}
+unsafe impl Sync for ForeignCallback {}
+
+impl FfiCallback for ForeignCallback {
+    fn invoke(&self, arg: *mut c_void) {
+        unsafe {
+            // SAFETY: This is guaranteed by the caller of weak_list_register.
+            (self.callback)(self.user_data, arg);
+        }
+    }
+}
+
+/// Creates a new list.
+///
+/// The list must be destroyed with [weak_list_free].
+#[no_mangle]
+pub extern "C" fn weak_list_new() -> *mut FfiList {
+    Box::into_raw(Box::default())
+}
+
+/// Destroys a list.
+///
+/// The callbacks registered with the list remain valid and must still be unregistered.
+/// If `list` is null, this function does nothing.
+///
+/// # Safety
+///
+/// `list` must be null or a pointer returned by [weak_list_new] that has not yet been
+/// destroyed. No other function may be using the list.
+#[no_mangle]
+pub unsafe extern "C" fn weak_list_free(list: *mut FfiList) {
+    if !list.is_null() {
+        drop(unsafe {
+            // SAFETY: This is guaranteed by the caller.
+            Box::from_raw(list)
+        });
+    }
+}
+
+/// Registers a callback with a list.
+///
+/// The returned handle keeps the callback registered until it is passed to
+/// [weak_list_unregister]. If `list` is null, the callback is not registered but a handle
+/// is still returned.
+///
+/// # Safety
+///
+/// - `list` must be null or a live list created by [weak_list_new].
+/// - Until the handle has been unregistered, it must be safe to invoke `callback` with
+///   `user_data` from any thread and concurrently with other invocations.
+#[no_mangle]
+pub unsafe extern "C" fn weak_list_register(
+    list: *const FfiList,
+    callback: FfiCallbackFn,
+    user_data: *mut c_void,
+) -> *mut FfiHandle {
+    let callback = Arc::new(ForeignCallback {
+        callback,
+        user_data,
+    });
+    let handle = FfiHandle {
+        element: SyncWeakListElement::new(Arc::downgrade(&callback) as _),
+        callback,
+    };
+    let list = unsafe {
+        // SAFETY: This is guaranteed by the caller.
+        list.as_ref()
+    };
+    if let Some(list) = list {
+        handle.element.attach(list);
+    }
+    Box::into_raw(Box::new(handle))
+}
+
+/// Unregisters a callback.
+///
+/// After this function returns, the callback is no longer invoked by subsequent calls to
+/// [weak_list_dispatch]. Invocations that are already running are not waited for. If
+/// `handle` is null, this function does nothing.
+///
+/// # Safety
+///
+/// `handle` must be null or a handle returned by [weak_list_register] that has not yet
+/// been unregistered.
+#[no_mangle]
+pub unsafe extern "C" fn weak_list_unregister(handle: *mut FfiHandle) {
+    if !handle.is_null() {
+        drop(unsafe {
+            // SAFETY: This is guaranteed by the caller.
+            Box::from_raw(handle)
+        });
+    }
+}
+
+/// Invokes all callbacks registered with a list.
+///
+/// The callbacks are invoked as if by [for_each](SyncWeakList::for_each). They may
+/// register and unregister callbacks. Returns the number of callbacks that were invoked.
+/// If `list` is null, this function returns 0.
+///
+/// # Safety
+///
+/// `list` must be null or a live list created by [weak_list_new].
+#[no_mangle]
+pub unsafe extern "C" fn weak_list_dispatch(list: *const FfiList, arg: *mut c_void) -> usize {
+    let list = unsafe {
+        // SAFETY: This is guaranteed by the caller.
+        list.as_ref()
+    };
+    let mut invoked = 0;
+    if let Some(list) = list {
+        list.for_each(|callback| {
+            callback.invoke(arg);
+            invoked += 1;
+        });
+    }
+    invoked
+}
+
+impl Debug for FfiHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("FfiHandle")
+            .field("element", &self.element)
+            .field("user_data", &self.callback.user_data)
+            .finish_non_exhaustive()
+    }
+}
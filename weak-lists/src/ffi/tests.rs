@@ -0,0 +1,63 @@
+use {
+    crate::{
+        ffi::{
+            weak_list_dispatch, weak_list_free, weak_list_new, weak_list_register,
+            weak_list_unregister, FfiCallback,
+        },
+        sync::SyncWeakListElement,
+    },
+    alloc::sync::Arc,
+    core::{
+        ffi::c_void,
+        ptr,
+        sync::atomic::{AtomicUsize, Ordering::Relaxed},
+    },
+};
+
+unsafe extern "C" fn add(user_data: *mut c_void, arg: *mut c_void) {
+    let counter = unsafe { &*(user_data as *const AtomicUsize) };
+    counter.fetch_add(arg as usize, Relaxed);
+}
+
+struct Native(AtomicUsize);
+
+impl FfiCallback for Native {
+    fn invoke(&self, arg: *mut c_void) {
+        self.0.fetch_add(arg as usize, Relaxed);
+    }
+}
+
+#[test]
+fn register_dispatch_unregister() {
+    let counters = [AtomicUsize::new(0), AtomicUsize::new(0)];
+    let user_data = counters.each_ref().map(|c| c as *const _ as *mut c_void);
+    unsafe {
+        let list = weak_list_new();
+        let first = weak_list_register(list, add, user_data[0]);
+        let second = weak_list_register(list, add, user_data[1]);
+        let native = Arc::new(Native(AtomicUsize::new(0)));
+        let element = SyncWeakListElement::new(Arc::downgrade(&native) as _);
+        element.attach(&*list);
+        assert_eq!(weak_list_dispatch(list, 2 as *mut c_void), 3);
+        weak_list_unregister(first);
+        assert_eq!(weak_list_dispatch(list, 3 as *mut c_void), 2);
+        assert_eq!(counters[0].load(Relaxed), 2);
+        assert_eq!(counters[1].load(Relaxed), 5);
+        assert_eq!(native.0.load(Relaxed), 5);
+        weak_list_free(list);
+        weak_list_unregister(second);
+    }
+}
+
+#[test]
+fn null() {
+    let counter = AtomicUsize::new(0);
+    unsafe {
+        assert_eq!(weak_list_dispatch(ptr::null(), ptr::null_mut()), 0);
+        let handle = weak_list_register(ptr::null(), add, &counter as *const _ as *mut c_void);
+        weak_list_unregister(handle);
+        weak_list_unregister(ptr::null_mut());
+        weak_list_free(ptr::null_mut());
+    }
+    assert_eq!(counter.load(Relaxed), 0);
+}
@@ -0,0 +1,465 @@
+//! A list over custom weak handles.
+//!
+//! The lists in [unsync](crate::unsync) and [sync](crate::sync) hold [Weak]
+//! references. The list in this module instead holds arbitrary handles implementing
+//! [WeakHandle]. This allows handles that are not pointers, such as an index and a
+//! generation into an arena.
+//!
+//! If the handles are plain keys, such as the keys of a slot map, that cannot be upgraded
+//! on their own, use [iter_resolved](HandleList::iter_resolved) to resolve them with a
+//! closure instead of implementing [WeakHandle].
+
+#[cfg(test)]
+mod tests;
+
+use {
+    alloc::rc::{Rc, Weak},
+    core::{
+        cell::UnsafeCell,
+        fmt::{Debug, Formatter},
+        iter::FusedIterator,
+        mem,
+        ops::Range,
+    },
+    stable_map::StableMap,
+};
+
+/// A weak handle that can be upgraded to a strong handle.
+///
+/// Unlike [Weak], implementations are not required to be pointers. The
+/// list never compares handles or their targets.
+///
+/// # Examples
+///
+/// ```
+/// use {
+///     std::{cell::RefCell, rc::Rc},
+///     weak_lists::handle::WeakHandle,
+/// };
+///
+/// struct Arena {
+///     slots: RefCell<Vec<(u32, Option<Rc<str>>)>>,
+/// }
+///
+/// struct Handle {
+///     arena: Rc<Arena>,
+///     index: usize,
+///     generation: u32,
+/// }
+///
+/// impl WeakHandle for Handle {
+///     type Strong = Rc<str>;
+///
+///     fn upgrade(&self) -> Option<Self::Strong> {
+///         match &self.arena.slots.borrow()[self.index] {
+///             (generation, Some(value)) if *generation == self.generation => {
+///                 Some(value.clone())
+///             }
+///             _ => None,
+///         }
+///     }
+/// }
+/// ```
+pub trait WeakHandle {
+    /// The type of the strong handle.
+    type Strong;
+
+    /// Attempts to upgrade the handle.
+    ///
+    /// Returns `None` if the target is no longer alive.
+    fn upgrade(&self) -> Option<Self::Strong>;
+}
+
+impl<T> WeakHandle for Weak<T>
+where
+    T: ?Sized,
+{
+    type Strong = Rc<T>;
+
+    fn upgrade(&self) -> Option<Self::Strong> {
+        Weak::upgrade(self)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<T> WeakHandle for alloc::sync::Weak<T>
+where
+    T: ?Sized,
+{
+    type Strong = alloc::sync::Arc<T>;
+
+    fn upgrade(&self) -> Option<Self::Strong> {
+        alloc::sync::Weak::upgrade(self)
+    }
+}
+
+/// A list holding weak handles to its elements.
+///
+/// This list behaves like [WeakList](crate::WeakList) except that it holds [WeakHandle]s.
+///
+/// # Examples
+///
+/// ```
+/// use {
+///     std::rc::Rc,
+///     weak_lists::handle::{HandleList, HandleListElement},
+/// };
+///
+/// let list = HandleList::default();
+/// let entry = Rc::new(1);
+/// let element = HandleListElement::new(Rc::downgrade(&entry));
+/// element.attach(&list);
+/// assert_eq!(list.iter().next(), Some(entry));
+/// ```
+pub struct HandleList<W> {
+    data: Rc<UnsafeCell<HandleListData<W>>>,
+}
+
+struct HandleListData<W> {
+    next_id: u64,
+    active_iterators: usize,
+    members: StableMap<u64, Rc<W>>,
+}
+
+/// An element that can be inserted into a [HandleList].
+///
+/// This object behaves like [WeakListElement](crate::WeakListElement).
+pub struct HandleListElement<W> {
+    handle: Rc<W>,
+    data: UnsafeCell<HandleEntryData<W>>,
+}
+
+struct HandleEntryData<W> {
+    id: u64,
+    owner: Weak<UnsafeCell<HandleListData<W>>>,
+}
+
+/// An iterator over the elements of a [HandleList].
+///
+/// This object is created by calling [iter](HandleList::iter) or by using the
+/// [IntoIterator] implementation of `&HandleList`.
+pub struct HandleIter<'a, W> {
+    iter: Range<usize>,
+    data: &'a UnsafeCell<HandleListData<W>>,
+}
+
+/// An iterator over the elements of a [HandleList] that resolves the handles with a
+/// closure.
+///
+/// This object is created by calling [iter_resolved](HandleList::iter_resolved).
+pub struct ResolvedIter<'a, W, F> {
+    iter: HandleIter<'a, W>,
+    resolve: F,
+}
+
+// The handles are stored in Rcs so that the list never has to run user code while holding
+// a reference to its data. Handles are only cloned, upgraded, and dropped after all
+// references have been released.
+
+impl<W> HandleList<W> {
+    /// Removes all elements from the list.
+    pub fn clear(&self) {
+        let members = {
+            let data = unsafe {
+                // SAFETY:
+                // - While we hold this reference, we do not call any functions that might
+                //   create additional references to self.data. This applies to all code
+                //   that creates references to self.data.
+                // - Therefore, this is an exclusive reference to self.data.
+                &mut *self.data.get()
+            };
+            mem::take(&mut data.members)
+        };
+        drop(members);
+    }
+
+    /// Creates an iterator over the entries of the list.
+    ///
+    /// This iterator provides the same guarantees as [WeakList::iter](crate::WeakList::iter).
+    pub fn iter(&self) -> HandleIter<'_, W> {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *self.data.get()
+        };
+        if data.active_iterators == 0 {
+            data.members.compact();
+        }
+        data.active_iterators += 1;
+        HandleIter {
+            iter: 0..data.members.index_len(),
+            data: &self.data,
+        }
+    }
+
+    /// Creates an iterator that resolves the handles of the entries with a closure.
+    ///
+    /// The handles do not have to implement [WeakHandle]. Instead, `resolve` is invoked
+    /// with the handle of each entry and the entry is skipped if it returns `None`. This
+    /// allows the list to hold keys into an arena or a slot map, for example. The closure
+    /// is invoked while no references to the list are held and may therefore modify
+    /// the list.
+    ///
+    /// This iterator provides the same guarantees as [iter](Self::iter).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use weak_lists::handle::{HandleList, HandleListElement};
+    ///
+    /// #[derive(Copy, Clone)]
+    /// struct Key {
+    ///     index: usize,
+    ///     generation: u32,
+    /// }
+    ///
+    /// let mut arena = vec![(0, Some("a")), (0, Some("b"))];
+    /// let list = HandleList::default();
+    /// let elements = [0, 1].map(|index| HandleListElement::new(Key { index, generation: 0 }));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// arena[0] = (1, Some("c"));
+    /// let resolve = |key: &Key| match arena[key.index] {
+    ///     (generation, value) if generation == key.generation => value,
+    ///     _ => None,
+    /// };
+    /// assert_eq!(list.iter_resolved(resolve).collect::<Vec<_>>(), ["b"]);
+    /// ```
+    pub fn iter_resolved<F, T>(&self, resolve: F) -> ResolvedIter<'_, W, F>
+    where
+        F: FnMut(&W) -> Option<T>,
+    {
+        ResolvedIter {
+            iter: self.iter(),
+            resolve,
+        }
+    }
+}
+
+impl<W> HandleListElement<W> {
+    /// Creates a new list element.
+    pub fn new(handle: W) -> Self {
+        Self {
+            handle: Rc::new(handle),
+            data: UnsafeCell::new(HandleEntryData {
+                id: 0,
+                owner: Default::default(),
+            }),
+        }
+    }
+
+    /// Returns the handle of this element.
+    pub fn handle(&self) -> &W {
+        &self.handle
+    }
+
+    /// Attaches the element to a list.
+    ///
+    /// If this element was previously attached to a list, it is automatically detached
+    /// from that list.
+    pub fn attach(&self, to: &HandleList<W>) {
+        self.detach();
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            // - The same applies to the reference to to.data below.
+            // - The insert call never replaces an existing member since ids are unique.
+            &mut *self.data.get()
+        };
+        data.owner = Rc::downgrade(&to.data);
+        let list_data = unsafe {
+            // SAFETY: See the previous safety comment.
+            &mut *to.data.get()
+        };
+        data.id = list_data.next_id;
+        list_data.next_id += 1;
+        list_data.members.insert(data.id, self.handle.clone());
+    }
+
+    /// Detaches the element from its current list.
+    pub fn detach(&self) {
+        let member = {
+            let data = unsafe {
+                // SAFETY:
+                // - While we hold this reference, we do not call any functions that might
+                //   create additional references to self.data. This applies to all code
+                //   that creates references to self.data.
+                // - Therefore, this is an exclusive reference to self.data.
+                // - The same applies to the reference to the list data below.
+                // - The removed member is dropped after all references have been released.
+                &mut *self.data.get()
+            };
+            let Some(prev) = mem::take(&mut data.owner).upgrade() else {
+                return;
+            };
+            let list_data = unsafe {
+                // SAFETY: See the previous safety comment.
+                &mut *prev.get()
+            };
+            list_data.members.remove(&data.id)
+        };
+        drop(member);
+    }
+}
+
+impl<W> Drop for HandleListElement<W> {
+    fn drop(&mut self) {
+        self.detach();
+    }
+}
+
+impl<W> Debug for HandleListElement<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *self.data.get()
+        };
+        let owner = data.owner.upgrade();
+        let owner_id = owner.as_ref().map(Rc::as_ptr);
+        f.debug_struct("HandleListElement")
+            .field("list", &owner_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<W> Default for HandleList<W> {
+    fn default() -> Self {
+        Self {
+            data: Rc::new(UnsafeCell::new(HandleListData {
+                next_id: 0,
+                active_iterators: 0,
+                members: Default::default(),
+            })),
+        }
+    }
+}
+
+impl<'a, W> IntoIterator for &'a HandleList<W>
+where
+    W: WeakHandle,
+{
+    type Item = W::Strong;
+    type IntoIter = HandleIter<'a, W>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<W> Debug for HandleList<W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("HandleList")
+            .field("id", &Rc::as_ptr(&self.data))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<W> Drop for HandleIter<'_, W> {
+    fn drop(&mut self) {
+        let data = unsafe { &mut *self.data.get() };
+        data.active_iterators -= 1;
+    }
+}
+
+impl<W> HandleIter<'_, W> {
+    /// Returns the first handle whose target is returned by `upgrade`.
+    fn next_with<T>(&mut self, mut upgrade: impl FnMut(&W) -> Option<T>) -> Option<T> {
+        for idx in &mut self.iter {
+            let member = {
+                let data = unsafe {
+                    // SAFETY:
+                    // - While we hold this reference, we do not call any functions that
+                    //   might create additional references to self.data. This applies to
+                    //   all code that creates references to self.data.
+                    // - Therefore, this is an exclusive reference to self.data.
+                    // - The clone call only clones an Rc.
+                    &mut *self.data.get()
+                };
+                data.members.get_by_index(idx).cloned()
+            };
+            if let Some(t) = member.and_then(|m| upgrade(&m)) {
+                return Some(t);
+            }
+        }
+        None
+    }
+}
+
+impl<W> Iterator for HandleIter<'_, W>
+where
+    W: WeakHandle,
+{
+    type Item = W::Strong;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with(W::upgrade)
+    }
+}
+
+impl<W> Clone for HandleIter<'_, W> {
+    fn clone(&self) -> Self {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *self.data.get()
+        };
+        data.active_iterators += 1;
+        Self {
+            iter: self.iter.clone(),
+            data: self.data,
+        }
+    }
+}
+
+impl<W> Debug for HandleIter<'_, W> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("HandleIter").finish_non_exhaustive()
+    }
+}
+
+impl<W> FusedIterator for HandleIter<'_, W> where W: WeakHandle {}
+
+impl<W, F, T> Iterator for ResolvedIter<'_, W, F>
+where
+    F: FnMut(&W) -> Option<T>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next_with(&mut self.resolve)
+    }
+}
+
+impl<W, F> Clone for ResolvedIter<'_, W, F>
+where
+    F: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            resolve: self.resolve.clone(),
+        }
+    }
+}
+
+impl<W, F> Debug for ResolvedIter<'_, W, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ResolvedIter").finish_non_exhaustive()
+    }
+}
+
+impl<W, F, T> FusedIterator for ResolvedIter<'_, W, F> where F: FnMut(&W) -> Option<T> {}
@@ -0,0 +1,109 @@
+use {
+    crate::handle::{HandleList, HandleListElement, WeakHandle},
+    alloc::{rc::Rc, vec::Vec},
+    core::{array, cell::RefCell},
+};
+
+struct Arena {
+    slots: RefCell<Vec<(u32, Option<usize>)>>,
+}
+
+struct Handle<'a> {
+    arena: &'a Arena,
+    index: usize,
+    generation: u32,
+}
+
+impl WeakHandle for Handle<'_> {
+    type Strong = usize;
+
+    fn upgrade(&self) -> Option<Self::Strong> {
+        match self.arena.slots.borrow()[self.index] {
+            (generation, value) if generation == self.generation => value,
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn arena_handles() {
+    let arena = Arena {
+        slots: RefCell::new(alloc::vec![(0, Some(1)), (0, Some(2)), (0, Some(4))]),
+    };
+    let list = HandleList::default();
+    let elements: [_; 3] = array::from_fn(|index| {
+        HandleListElement::new(Handle {
+            arena: &arena,
+            index,
+            generation: 0,
+        })
+    });
+    for element in &elements {
+        element.attach(&list);
+    }
+    assert_eq!(list.iter().sum::<usize>(), 7);
+    arena.slots.borrow_mut()[1] = (1, Some(8));
+    assert_eq!(list.iter().sum::<usize>(), 5);
+    let sum: usize = list
+        .iter()
+        .inspect(|i| {
+            if *i == 1 {
+                elements[2].detach();
+            }
+        })
+        .sum();
+    assert_eq!(sum, 1);
+    drop(elements);
+    assert!(list.iter().next().is_none());
+}
+
+#[test]
+fn rc_handles() {
+    let list = HandleList::default();
+    let entry = Rc::new(1);
+    let element = HandleListElement::new(Rc::downgrade(&entry));
+    element.attach(&list);
+    assert_eq!(list.iter().next(), Some(entry.clone()));
+    list.clear();
+    assert!(list.iter().next().is_none());
+    element.attach(&list);
+    drop(entry);
+    assert!(list.iter().next().is_none());
+}
+
+#[test]
+fn resolved_keys() {
+    #[derive(Copy, Clone)]
+    struct Key {
+        index: usize,
+        generation: u32,
+    }
+
+    let arena = RefCell::new(alloc::vec![(0, Some(1)), (0, Some(2)), (0, Some(4))]);
+    let list = HandleList::default();
+    let elements: [_; 3] = array::from_fn(|index| {
+        HandleListElement::new(Key {
+            index,
+            generation: 0,
+        })
+    });
+    for element in &elements {
+        element.attach(&list);
+    }
+    let resolve = |key: &Key| match arena.borrow()[key.index] {
+        (generation, value) if generation == key.generation => value,
+        _ => None,
+    };
+    assert_eq!(list.iter_resolved(resolve).sum::<usize>(), 7);
+    arena.borrow_mut()[1] = (1, Some(8));
+    assert_eq!(list.iter_resolved(resolve).sum::<usize>(), 5);
+    let sum: usize = list
+        .iter_resolved(|key| {
+            if key.index == 0 {
+                elements[2].detach();
+            }
+            resolve(key)
+        })
+        .sum();
+    assert_eq!(sum, 1);
+}
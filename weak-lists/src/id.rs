@@ -0,0 +1,74 @@
+//! Types that can be used as entry ids.
+
+use core::{fmt::Debug, hash::Hash};
+
+/// The type of the ids of list entries.
+///
+/// Every entry of a list has an id that is unique within its list. The id type is a type
+/// parameter of the lists and defaults to `u64`. Smaller types reduce the memory used by
+/// lists with many entries.
+///
+/// Ids are allocated sequentially. When the ids of a type are exhausted, allocation starts
+/// over at 0 and skips the ids that are still in use. Therefore, ids of detached entries
+/// can be reused by later entries. With `u64` this never happens in practice.
+///
+/// This trait is sealed and implemented for `u16`, `u32`, `u64`, and `usize`.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use weak_lists::{unsync::WeakListBuilder, WeakList, WeakListElement};
+///
+/// let list: WeakList<u8, u32> = WeakListBuilder::default().build_with_id_type();
+/// let entry = Rc::new(1);
+/// let element = WeakListElement::new_with_id_type(Rc::downgrade(&entry));
+/// element.attach(&list);
+/// assert_eq!(element.id(), Some(0u32));
+/// ```
+pub trait EntryId: Copy + Ord + Hash + Debug + Send + Sync + 'static + sealed::Sealed {}
+
+pub(crate) mod sealed {
+    pub trait Sealed {
+        /// The first id that is allocated.
+        const FIRST: Self;
+
+        /// Returns the next id, wrapping around at the maximum.
+        fn wrapping_next(self) -> Self;
+
+        /// Converts the id, truncating it if it does not fit.
+        #[cfg(feature = "deterministic")]
+        fn from_u64(id: u64) -> Self;
+    }
+}
+
+macro_rules! entry_id {
+    ($($ty:ty),*) => {
+        $(
+            impl sealed::Sealed for $ty {
+                const FIRST: Self = 0;
+
+                fn wrapping_next(self) -> Self {
+                    self.wrapping_add(1)
+                }
+
+                #[cfg(feature = "deterministic")]
+                fn from_u64(id: u64) -> Self {
+                    id as Self
+                }
+            }
+
+            impl EntryId for $ty {}
+        )*
+    };
+}
+
+entry_id!(u16, u32, u64, usize);
+
+/// The id of a membership of a multi element.
+///
+/// These ids are returned by
+/// [MultiWeakListElement::attach](crate::unsync::MultiWeakListElement::attach) and
+/// `SyncMultiWeakListElement::attach` and are unique within their element.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MembershipId(pub(crate) u64);
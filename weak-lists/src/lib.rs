@@ -3,12 +3,44 @@
 //! outcomes.
 
 #![no_std]
+#![cfg_attr(feature = "unsize", feature(unsize))]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
+pub mod debug;
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod handle;
+pub mod id;
+#[cfg(feature = "listener")]
+pub mod listener;
+mod macros;
+pub mod observer;
+pub mod policy;
+pub mod prelude;
+pub mod registry;
 #[cfg(feature = "sync")]
 pub mod sync;
+#[cfg(feature = "test-util")]
+pub mod test_util;
 pub mod unsync;
+pub mod waker;
 
+#[cfg(feature = "debug-registry")]
+pub use debug::dump_all;
 #[cfg(feature = "sync")]
 pub use sync::{SyncWeakList, SyncWeakListElement};
-pub use unsync::{WeakList, WeakListElement};
+pub use {
+    error::Error,
+    id::EntryId,
+    unsync::{iter_chain, WeakList, WeakListElement},
+};
+
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::rc::Rc;
+    #[cfg(feature = "sync")]
+    pub use alloc::sync::Arc;
+}
@@ -0,0 +1,183 @@
+//! An event with a listen/notify interface.
+//!
+//! This module provides an interface similar to the one used by many asynchronous
+//! primitives: Tasks call [Event::listen] to obtain an [EventListener] and await it. Other
+//! tasks call [Event::notify] to wake up a number of listeners.
+//!
+//! The listeners are stored in a [SyncWeakList]. Dropping a listener removes it from the
+//! event.
+
+#[cfg(test)]
+mod tests;
+
+use {
+    crate::sync::{SyncWeakList, SyncWeakListElement},
+    alloc::sync::Arc,
+    core::{
+        fmt::{Debug, Formatter},
+        future::Future,
+        pin::Pin,
+        sync::atomic::{
+            AtomicBool,
+            Ordering::{Acquire, Release},
+        },
+        task::{Context, Poll, Waker},
+    },
+    parking_lot::Mutex,
+};
+
+/// An event that listeners can wait for.
+///
+/// # Examples
+///
+/// ```
+/// use {
+///     std::{
+///         future::Future,
+///         pin::pin,
+///         task::{Context, Poll, Waker},
+///     },
+///     weak_lists::listener::Event,
+/// };
+///
+/// let event = Event::default();
+/// let mut listener = pin!(event.listen());
+/// let mut cx = Context::from_waker(Waker::noop());
+/// assert!(listener.as_mut().poll(&mut cx).is_pending());
+/// assert_eq!(event.notify(1), 1);
+/// assert!(listener.as_mut().poll(&mut cx).is_ready());
+/// ```
+#[derive(Default)]
+pub struct Event {
+    list: SyncWeakList<ListenerState>,
+}
+
+/// A listener waiting for an [Event].
+///
+/// This object is created by calling [listen](Event::listen). It is a future that completes
+/// once the listener has been notified. When this object is dropped, it is removed from
+/// the event.
+pub struct EventListener {
+    state: Arc<ListenerState>,
+}
+
+struct ListenerState {
+    element: SyncWeakListElement<ListenerState>,
+    notified: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl Event {
+    /// Creates a new listener for this event.
+    ///
+    /// The listener is only notified by calls to [notify](Self::notify) that happen after
+    /// this function returns.
+    pub fn listen(&self) -> EventListener {
+        let state = Arc::new_cyclic(|slf| ListenerState {
+            element: SyncWeakListElement::new(slf.clone()),
+            notified: AtomicBool::new(false),
+            waker: Default::default(),
+        });
+        state.element.attach(&self.list);
+        EventListener { state }
+    }
+
+    /// Notifies up to `n` listeners.
+    ///
+    /// Notified listeners are removed from the event. The order in which listeners are
+    /// notified is unspecified. Returns the number of listeners that were notified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use weak_lists::listener::Event;
+    ///
+    /// let event = Event::default();
+    /// let listeners = [event.listen(), event.listen(), event.listen()];
+    /// assert_eq!(event.notify(2), 2);
+    /// assert_eq!(listeners.iter().filter(|l| l.is_notified()).count(), 2);
+    /// assert_eq!(event.notify(usize::MAX), 1);
+    /// ```
+    pub fn notify(&self, n: usize) -> usize {
+        let mut notified = 0;
+        for listener in &self.list {
+            if notified == n {
+                break;
+            }
+            if listener.notify() {
+                notified += 1;
+            }
+        }
+        notified
+    }
+
+    /// Notifies all listeners.
+    ///
+    /// Returns the number of listeners that were notified.
+    pub fn notify_all(&self) -> usize {
+        self.notify(usize::MAX)
+    }
+}
+
+impl ListenerState {
+    /// Marks the listener as notified and wakes its task.
+    ///
+    /// Returns `false` if the listener had already been notified.
+    fn notify(&self) -> bool {
+        if self.notified.swap(true, Release) {
+            return false;
+        }
+        self.element.detach();
+        let waker = self.waker.lock().take();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        true
+    }
+}
+
+impl EventListener {
+    /// Returns whether this listener has been notified.
+    pub fn is_notified(&self) -> bool {
+        self.state.notified.load(Acquire)
+    }
+}
+
+impl Future for EventListener {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.is_notified() {
+            return Poll::Ready(());
+        }
+        let prev = {
+            let waker = &mut *self.state.waker.lock();
+            match waker {
+                Some(w) if w.will_wake(cx.waker()) => None,
+                _ => waker.replace(cx.waker().clone()),
+            }
+        };
+        drop(prev);
+        // The listener might have been notified before the waker was stored.
+        if self.is_notified() {
+            return Poll::Ready(());
+        }
+        Poll::Pending
+    }
+}
+
+impl Debug for Event {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Event")
+            .field("list", &self.list)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Debug for EventListener {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("EventListener")
+            .field("notified", &self.is_notified())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,48 @@
+use {
+    crate::listener::Event,
+    alloc::{sync::Arc, task::Wake},
+    core::{
+        future::Future,
+        pin::pin,
+        sync::atomic::{AtomicUsize, Ordering::Relaxed},
+        task::{Context, Waker},
+    },
+};
+
+struct Counter(AtomicUsize);
+
+impl Wake for Counter {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Relaxed);
+    }
+}
+
+#[test]
+fn notify_wakes() {
+    let event = Event::default();
+    let counter = Arc::new(Counter(AtomicUsize::new(0)));
+    let waker = Waker::from(counter.clone());
+    let mut cx = Context::from_waker(&waker);
+    let mut listener = pin!(event.listen());
+    assert!(listener.as_mut().poll(&mut cx).is_pending());
+    assert!(listener.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(event.notify(1), 1);
+    assert_eq!(counter.0.load(Relaxed), 1);
+    assert!(listener.as_mut().poll(&mut cx).is_ready());
+    assert_eq!(event.notify(1), 0);
+    assert_eq!(counter.0.load(Relaxed), 1);
+}
+
+#[test]
+fn dropped_listeners() {
+    let event = Event::default();
+    let listener1 = event.listen();
+    let listener2 = event.listen();
+    drop(listener1);
+    assert_eq!(event.notify_all(), 1);
+    assert!(listener2.is_notified());
+    let listener3 = event.listen();
+    assert!(!listener3.is_notified());
+    assert_eq!(event.notify(0), 0);
+    assert_eq!(event.notify_all(), 1);
+}
@@ -0,0 +1,145 @@
+//! Macros that generate wrappers around the lists.
+
+#[cfg(test)]
+mod tests;
+
+/// Generates a registry type that wraps a list.
+///
+/// `weak_list_registry!(pub Name for Type)` generates a newtype `Name` around
+/// [WeakList](crate::WeakList)`<Type>`. `weak_list_registry!(pub sync Name for Type)`
+/// generates a newtype around [SyncWeakList](crate::SyncWeakList)`<Type>` instead. This
+/// requires the `sync` feature. Attributes, such as doc comments, placed before the
+/// visibility are applied to the generated type.
+///
+/// The generated type implements `Default` and `Debug` and has the following methods:
+///
+/// - `new()`: Creates an empty registry.
+/// - `register(&self, target: &Rc<Type>)`: Attaches the target to the registry and
+///   returns its element. See [attach_new](crate::WeakList::attach_new). The target is
+///   unregistered when the element is dropped.
+/// - `unregister(&self, id: u64) -> Result<(), Error>`: Unregisters the target with the
+///   given id. See [detach_key](crate::WeakList::detach_key).
+/// - `broadcast(&self, f: impl FnMut(Rc<Type>)) -> usize`: Invokes `f` with all
+///   registered targets and returns the number of invocations. See
+///   [for_each](crate::WeakList::for_each).
+/// - `list(&self)`: Returns the wrapped list.
+///
+/// In the `sync` variant, `Rc` is replaced by `Arc`.
+///
+/// # Examples
+///
+/// ```
+/// use std::{cell::Cell, rc::Rc};
+/// use weak_lists::weak_list_registry;
+///
+/// trait ClientCallback {
+///     fn notify(&self);
+/// }
+///
+/// struct Client {
+///     notified: Cell<bool>,
+/// }
+///
+/// impl ClientCallback for Client {
+///     fn notify(&self) {
+///         self.notified.set(true);
+///     }
+/// }
+///
+/// weak_list_registry! {
+///     /// The clients that are notified about changes.
+///     pub ClientRegistry for dyn ClientCallback
+/// }
+///
+/// let registry = ClientRegistry::new();
+/// let client = Rc::new(Client {
+///     notified: Cell::new(false),
+/// });
+/// let element = registry.register(&(client.clone() as _));
+/// assert_eq!(registry.broadcast(|c| c.notify()), 1);
+/// assert!(client.notified.get());
+/// assert_eq!(registry.unregister(element.id().unwrap()), Ok(()));
+/// assert_eq!(registry.broadcast(|c| c.notify()), 0);
+/// ```
+#[macro_export]
+macro_rules! weak_list_registry {
+    (
+        @impl
+        $(#[$attr:meta])*
+        $vis:vis $name:ident for $ty:ty,
+        $list:ident,
+        $element:ident,
+        $rc:ident
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug, Default)]
+        $vis struct $name {
+            list: $crate::$list<$ty>,
+        }
+
+        #[allow(dead_code)]
+        impl $name {
+            /// Creates an empty registry.
+            $vis fn new() -> Self {
+                ::core::default::Default::default()
+            }
+
+            /// Registers a target and returns its element.
+            ///
+            /// The target is unregistered when the element is dropped.
+            $vis fn register(
+                &self,
+                target: &$crate::__private::$rc<$ty>,
+            ) -> $crate::$element<$ty> {
+                self.list.attach_new(target)
+            }
+
+            /// Unregisters the target with the given id.
+            ///
+            /// Returns `Error::NotFound` if no target with this id is registered.
+            $vis fn unregister(&self, id: u64) -> ::core::result::Result<(), $crate::Error> {
+                self.list.detach_key(id)
+            }
+
+            /// Invokes `f` with all registered targets.
+            ///
+            /// Returns the number of targets for which `f` was invoked.
+            $vis fn broadcast(
+                &self,
+                mut f: impl FnMut($crate::__private::$rc<$ty>),
+            ) -> usize {
+                let mut invoked = 0;
+                self.list.for_each(|target| {
+                    invoked += 1;
+                    f(target);
+                });
+                invoked
+            }
+
+            /// Returns the wrapped list.
+            $vis fn list(&self) -> &$crate::$list<$ty> {
+                &self.list
+            }
+        }
+    };
+    ($(#[$attr:meta])* $vis:vis sync $name:ident for $ty:ty $(;)?) => {
+        $crate::weak_list_registry!(
+            @impl
+            $(#[$attr])*
+            $vis $name for $ty,
+            SyncWeakList,
+            SyncWeakListElement,
+            Arc
+        );
+    };
+    ($(#[$attr:meta])* $vis:vis $name:ident for $ty:ty $(;)?) => {
+        $crate::weak_list_registry!(
+            @impl
+            $(#[$attr])*
+            $vis $name for $ty,
+            WeakList,
+            WeakListElement,
+            Rc
+        );
+    };
+}
@@ -0,0 +1,80 @@
+use {
+    crate::Error,
+    alloc::{rc::Rc, vec::Vec},
+    core::cell::Cell,
+};
+#[cfg(feature = "sync")]
+use {
+    alloc::sync::Arc,
+    core::sync::atomic::{AtomicUsize, Ordering::Relaxed},
+};
+
+trait Callback {
+    fn call(&self) -> usize;
+}
+
+struct Counter(Cell<usize>);
+
+impl Callback for Counter {
+    fn call(&self) -> usize {
+        self.0.set(self.0.get() + 1);
+        self.0.get()
+    }
+}
+
+crate::weak_list_registry!(Registry for dyn Callback);
+
+#[test]
+fn unsync() {
+    let registry = Registry::new();
+    let counters = [
+        Rc::new(Counter(Cell::new(0))),
+        Rc::new(Counter(Cell::new(10))),
+    ];
+    let elements = counters
+        .each_ref()
+        .map(|c| registry.register(&(c.clone() as Rc<dyn Callback>)));
+    let mut results = Vec::new();
+    assert_eq!(registry.broadcast(|c| results.push(c.call())), 2);
+    assert_eq!(results, [1, 11]);
+    assert_eq!(registry.unregister(elements[0].id().unwrap()), Ok(()));
+    assert_eq!(
+        registry.unregister(elements[0].id().unwrap_or(0)),
+        Err(Error::NotFound)
+    );
+    assert_eq!(registry.broadcast(|c| assert_eq!(c.call(), 12)), 1);
+    drop(elements);
+    assert_eq!(registry.broadcast(|_| unreachable!()), 0);
+    assert!(registry.list().iter().next().is_none());
+}
+
+#[cfg(feature = "sync")]
+trait SyncCallback: Send + Sync {
+    fn call(&self);
+}
+
+#[cfg(feature = "sync")]
+impl SyncCallback for AtomicUsize {
+    fn call(&self) {
+        self.fetch_add(1, Relaxed);
+    }
+}
+
+#[cfg(feature = "sync")]
+crate::weak_list_registry! {
+    /// A registry of thread-safe callbacks.
+    sync SyncRegistry for dyn SyncCallback;
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn sync() {
+    let registry = SyncRegistry::default();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let element = registry.register(&(counter.clone() as Arc<dyn SyncCallback>));
+    assert_eq!(registry.broadcast(|c| c.call()), 1);
+    assert_eq!(counter.load(Relaxed), 1);
+    drop(element);
+    assert_eq!(registry.broadcast(|c| c.call()), 0);
+    assert_eq!(counter.load(Relaxed), 1);
+}
@@ -0,0 +1,206 @@
+//! An implementation of the observer pattern on top of weak lists.
+//!
+//! An [Observable] holds weak references to its observers. Subscribing returns a
+//! [Subscription] token. The observer stays subscribed until the token is dropped.
+//!
+//! [Observable] and [SyncObservable] both implement [Subject] so that code emitting
+//! events can be written independently of the flavor.
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "sync")]
+use {
+    crate::{SyncWeakList, SyncWeakListElement},
+    alloc::sync::{Arc, Weak as ArcWeak},
+};
+use {
+    crate::{WeakList, WeakListElement},
+    alloc::rc::{Rc, Weak},
+    core::fmt::{Debug, Formatter},
+};
+
+/// An observer of events of type `E`.
+pub trait Observer<E> {
+    /// Handles an event.
+    fn observe(&self, event: E);
+}
+
+/// A source of events of type `E`.
+pub trait Subject<E> {
+    /// Sends an event to all subscribed observers.
+    fn notify(&self, event: E);
+}
+
+/// An object that can be observed.
+///
+/// # Examples
+///
+/// ```
+/// use std::{cell::Cell, rc::Rc};
+/// use weak_lists::observer::{Observable, Observer, Subject};
+///
+/// #[derive(Clone)]
+/// enum Event {
+///     Connected,
+///     Disconnected,
+/// }
+///
+/// #[derive(Default)]
+/// struct Counter {
+///     connected: Cell<usize>,
+/// }
+///
+/// impl Observer<Event> for Counter {
+///     fn observe(&self, event: Event) {
+///         match event {
+///             Event::Connected => self.connected.set(self.connected.get() + 1),
+///             Event::Disconnected => self.connected.set(self.connected.get() - 1),
+///         }
+///     }
+/// }
+///
+/// let observable = Observable::default();
+/// let counter = Rc::new(Counter::default());
+/// let subscription = observable.subscribe(&counter);
+/// observable.notify(Event::Connected);
+/// observable.notify(Event::Connected);
+/// observable.notify(Event::Disconnected);
+/// assert_eq!(counter.connected.get(), 1);
+/// drop(subscription);
+/// observable.notify(Event::Connected);
+/// assert_eq!(counter.connected.get(), 1);
+/// ```
+pub struct Observable<E> {
+    observers: WeakList<dyn Observer<E>>,
+}
+
+/// A subscription to an [Observable].
+///
+/// When this object is dropped, the observer is unsubscribed.
+pub struct Subscription<E> {
+    element: WeakListElement<dyn Observer<E>>,
+}
+
+/// An object that can be observed from multiple threads.
+///
+/// See [Observable] for an example.
+#[cfg(feature = "sync")]
+pub struct SyncObservable<E> {
+    observers: SyncWeakList<dyn Observer<E> + Send + Sync>,
+}
+
+/// A subscription to a [SyncObservable].
+///
+/// When this object is dropped, the observer is unsubscribed.
+#[cfg(feature = "sync")]
+pub struct SyncSubscription<E> {
+    element: SyncWeakListElement<dyn Observer<E> + Send + Sync>,
+}
+
+impl<E> Observable<E> {
+    /// Subscribes an observer.
+    ///
+    /// The observer stays subscribed until the returned token is dropped. Only a weak
+    /// reference to the observer is retained.
+    pub fn subscribe<O>(&self, observer: &Rc<O>) -> Subscription<E>
+    where
+        O: Observer<E> + 'static,
+    {
+        let observer: Weak<O> = Rc::downgrade(observer);
+        let element = WeakListElement::<dyn Observer<E>>::new(observer);
+        element.attach(&self.observers);
+        Subscription { element }
+    }
+}
+
+impl<E> Subject<E> for Observable<E>
+where
+    E: Clone,
+{
+    fn notify(&self, event: E) {
+        for observer in &self.observers {
+            observer.observe(event.clone());
+        }
+    }
+}
+
+impl<E> Default for Observable<E> {
+    fn default() -> Self {
+        Self {
+            observers: Default::default(),
+        }
+    }
+}
+
+impl<E> Debug for Observable<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Observable")
+            .field("observers", &self.observers)
+            .finish()
+    }
+}
+
+impl<E> Debug for Subscription<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Subscription")
+            .field("element", &self.element)
+            .finish()
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<E> SyncObservable<E> {
+    /// Subscribes an observer.
+    ///
+    /// The observer stays subscribed until the returned token is dropped. Only a weak
+    /// reference to the observer is retained.
+    pub fn subscribe<O>(&self, observer: &Arc<O>) -> SyncSubscription<E>
+    where
+        O: Observer<E> + Send + Sync + 'static,
+    {
+        let observer: ArcWeak<O> = Arc::downgrade(observer);
+        let element = SyncWeakListElement::<dyn Observer<E> + Send + Sync>::new(observer);
+        element.attach(&self.observers);
+        SyncSubscription { element }
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<E> Subject<E> for SyncObservable<E>
+where
+    E: Clone,
+{
+    fn notify(&self, event: E) {
+        for observer in &self.observers {
+            observer.observe(event.clone());
+        }
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<E> Default for SyncObservable<E> {
+    fn default() -> Self {
+        Self {
+            observers: Default::default(),
+        }
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<E> Debug for SyncObservable<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SyncObservable")
+            .field("observers", &self.observers)
+            .finish()
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<E> Debug for SyncSubscription<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SyncSubscription")
+            .field("element", &self.element)
+            .finish()
+    }
+}
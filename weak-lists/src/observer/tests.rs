@@ -0,0 +1,91 @@
+#[cfg(feature = "sync")]
+use {
+    crate::observer::SyncObservable,
+    alloc::sync::Arc,
+    core::sync::atomic::{AtomicUsize, Ordering::Relaxed},
+};
+use {
+    crate::observer::{Observable, Observer, Subject},
+    alloc::rc::Rc,
+    core::cell::Cell,
+};
+
+#[derive(Copy, Clone)]
+enum Event {
+    Add(usize),
+    Reset,
+}
+
+#[derive(Default)]
+struct Sum {
+    sum: Cell<usize>,
+}
+
+impl Observer<Event> for Sum {
+    fn observe(&self, event: Event) {
+        match event {
+            Event::Add(n) => self.sum.set(self.sum.get() + n),
+            Event::Reset => self.sum.set(0),
+        }
+    }
+}
+
+fn emit(subject: &impl Subject<Event>) {
+    subject.notify(Event::Reset);
+    subject.notify(Event::Add(1));
+    subject.notify(Event::Add(2));
+}
+
+#[test]
+fn observable() {
+    let observable = Observable::default();
+    let sum1 = Rc::new(Sum::default());
+    let sum2 = Rc::new(Sum::default());
+    let subscription1 = observable.subscribe(&sum1);
+    let subscription2 = observable.subscribe(&sum2);
+    emit(&observable);
+    assert_eq!(sum1.sum.get(), 3);
+    assert_eq!(sum2.sum.get(), 3);
+    drop(subscription2);
+    emit(&observable);
+    observable.notify(Event::Add(1));
+    assert_eq!(sum1.sum.get(), 4);
+    assert_eq!(sum2.sum.get(), 3);
+    drop(sum1);
+    observable.notify(Event::Add(1));
+    drop(subscription1);
+}
+
+#[cfg(feature = "sync")]
+#[derive(Default)]
+struct SyncSum {
+    sum: AtomicUsize,
+}
+
+#[cfg(feature = "sync")]
+impl Observer<Event> for SyncSum {
+    fn observe(&self, event: Event) {
+        match event {
+            Event::Add(n) => self.sum.fetch_add(n, Relaxed),
+            Event::Reset => self.sum.swap(0, Relaxed),
+        };
+    }
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn sync_observable() {
+    let observable = SyncObservable::default();
+    let sum1 = Arc::new(SyncSum::default());
+    let sum2 = Arc::new(SyncSum::default());
+    let _subscription1 = observable.subscribe(&sum1);
+    let subscription2 = observable.subscribe(&sum2);
+    emit(&observable);
+    assert_eq!(sum1.sum.load(Relaxed), 3);
+    assert_eq!(sum2.sum.load(Relaxed), 3);
+    drop(subscription2);
+    emit(&observable);
+    observable.notify(Event::Add(1));
+    assert_eq!(sum1.sum.load(Relaxed), 4);
+    assert_eq!(sum2.sum.load(Relaxed), 3);
+}
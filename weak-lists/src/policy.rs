@@ -0,0 +1,66 @@
+//! Policies that decide whether the entries of a list are yielded.
+//!
+//! By default, an entry is yielded if its target can be upgraded. A [LivenessPolicy] adds
+//! further conditions, such as a flag in the target that marks it as ready. The policy is
+//! a type parameter of [PolicyWeakList](crate::unsync::PolicyWeakList) and
+//! [SyncPolicyWeakList](crate::sync::SyncPolicyWeakList). The iterators of these lists
+//! apply it.
+
+/// A policy that decides whether an entry whose target is alive is yielded.
+///
+/// # Examples
+///
+/// ```
+/// use {
+///     std::{
+///         rc::Rc,
+///         sync::atomic::{AtomicBool, Ordering::Relaxed},
+///     },
+///     weak_lists::{policy::LivenessPolicy, unsync::PolicyWeakList, WeakListElement},
+/// };
+///
+/// struct Task {
+///     ready: AtomicBool,
+/// }
+///
+/// struct Ready;
+///
+/// impl LivenessPolicy<Task> for Ready {
+///     fn is_live(target: &Task) -> bool {
+///         target.ready.load(Relaxed)
+///     }
+/// }
+///
+/// let list = PolicyWeakList::<Task, Ready>::default();
+/// let task = Rc::new(Task {
+///     ready: AtomicBool::new(false),
+/// });
+/// let element = WeakListElement::new(Rc::downgrade(&task));
+/// element.attach(list.list());
+/// assert!(list.iter().next().is_none());
+/// task.ready.store(true, Relaxed);
+/// assert_eq!(list.iter().count(), 1);
+/// ```
+pub trait LivenessPolicy<T>
+where
+    T: ?Sized,
+{
+    /// Returns whether the entry with this target is yielded.
+    ///
+    /// This function is called after the target has been upgraded. It must not attach
+    /// elements to or detach elements from the list that is being iterated.
+    fn is_live(target: &T) -> bool;
+}
+
+/// The default policy that yields all entries whose targets are alive.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Upgrade;
+
+impl<T> LivenessPolicy<T> for Upgrade
+where
+    T: ?Sized,
+{
+    fn is_live(_target: &T) -> bool {
+        true
+    }
+}
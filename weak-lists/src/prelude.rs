@@ -0,0 +1,22 @@
+//! Re-exports of the commonly used types and traits.
+//!
+//! # Examples
+//!
+//! ```
+//! use weak_lists::prelude::*;
+//!
+//! let list = WeakListBuilder::default().capacity(16).build::<u32>();
+//! assert!(list.iter().next().is_none());
+//! ```
+
+#[cfg(feature = "sync")]
+pub use crate::{
+    observer::SyncObservable,
+    registry::SyncWeakRegistry,
+    sync::{SyncWeakList, SyncWeakListBuilder, SyncWeakListElement},
+};
+pub use crate::{
+    observer::{Observable, Observer, Subject},
+    registry::WeakRegistry,
+    unsync::{WeakList, WeakListBuilder, WeakListElement},
+};
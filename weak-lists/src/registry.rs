@@ -0,0 +1,439 @@
+//! A registry of services identified by their type.
+//!
+//! The registries in this module hold weak references to the registered services. When a
+//! service is dropped, it is automatically removed from the registry.
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "sync")]
+use {
+    alloc::sync::{Arc, Weak as ArcWeak},
+    parking_lot::Mutex,
+};
+use {
+    alloc::{
+        boxed::Box,
+        collections::BTreeMap,
+        rc::{Rc, Weak},
+    },
+    core::{
+        any::{Any, TypeId},
+        cell::RefCell,
+        fmt::{Debug, Formatter},
+        marker::PhantomData,
+    },
+};
+
+/// A registry holding weak references to services identified by their type.
+///
+/// The type of a service is usually a trait object. At most one service can be
+/// registered per type.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use weak_lists::registry::WeakRegistry;
+///
+/// trait Logger {
+///     fn log(&self, msg: &str);
+/// }
+///
+/// struct StderrLogger;
+///
+/// impl Logger for StderrLogger {
+///     fn log(&self, msg: &str) {
+///         eprintln!("{msg}");
+///     }
+/// }
+///
+/// let registry = WeakRegistry::default();
+/// let logger = Rc::new(StderrLogger);
+/// registry.register::<dyn Logger>(logger.clone());
+/// registry.get::<dyn Logger>().unwrap().log("hello");
+/// drop(logger);
+/// assert!(registry.get::<dyn Logger>().is_none());
+/// ```
+#[derive(Default)]
+pub struct WeakRegistry {
+    services: RefCell<BTreeMap<TypeId, Box<dyn Any>>>,
+}
+
+/// A thread-safe registry holding weak references to services identified by their type.
+///
+/// The type of a service is usually a trait object. At most one service can be
+/// registered per type.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use weak_lists::registry::SyncWeakRegistry;
+///
+/// trait Logger: Send + Sync {
+///     fn log(&self, msg: &str);
+/// }
+///
+/// struct StderrLogger;
+///
+/// impl Logger for StderrLogger {
+///     fn log(&self, msg: &str) {
+///         eprintln!("{msg}");
+///     }
+/// }
+///
+/// let registry = SyncWeakRegistry::default();
+/// let logger = Arc::new(StderrLogger);
+/// registry.register::<dyn Logger>(logger.clone());
+/// registry.get::<dyn Logger>().unwrap().log("hello");
+/// drop(logger);
+/// assert!(registry.get::<dyn Logger>().is_none());
+/// ```
+#[cfg(feature = "sync")]
+#[derive(Default)]
+pub struct SyncWeakRegistry {
+    services: Mutex<BTreeMap<TypeId, Box<dyn Any + Send + Sync>>>,
+}
+
+/// An entry in a [WeakRegistry].
+///
+/// This object is created by calling [entry](WeakRegistry::entry).
+pub enum Entry<'a, I>
+where
+    I: ?Sized + 'static,
+{
+    /// The registry contains a live service for the type.
+    Occupied(Rc<I>),
+    /// The registry does not contain a live service for the type.
+    Vacant(VacantEntry<'a, I>),
+}
+
+/// A vacant entry in a [WeakRegistry].
+///
+/// The registry is not borrowed by this object. A service for the type might therefore
+/// be registered while this object exists.
+pub struct VacantEntry<'a, I>
+where
+    I: ?Sized + 'static,
+{
+    registry: &'a WeakRegistry,
+    _phantom: PhantomData<fn(Rc<I>)>,
+}
+
+/// An entry in a [SyncWeakRegistry].
+///
+/// This object is created by calling [entry](SyncWeakRegistry::entry).
+#[cfg(feature = "sync")]
+pub enum SyncEntry<'a, I>
+where
+    I: ?Sized + Send + Sync + 'static,
+{
+    /// The registry contains a live service for the type.
+    Occupied(Arc<I>),
+    /// The registry does not contain a live service for the type.
+    Vacant(SyncVacantEntry<'a, I>),
+}
+
+/// A vacant entry in a [SyncWeakRegistry].
+///
+/// The registry is not locked by this object. Another thread might therefore register a
+/// service for the type while this object exists.
+#[cfg(feature = "sync")]
+pub struct SyncVacantEntry<'a, I>
+where
+    I: ?Sized + Send + Sync + 'static,
+{
+    registry: &'a SyncWeakRegistry,
+    _phantom: PhantomData<fn(Arc<I>)>,
+}
+
+impl WeakRegistry {
+    /// Registers a service.
+    ///
+    /// The registry only retains a weak reference to the service. If a service was
+    /// previously registered for the type `I`, it is replaced.
+    pub fn register<I>(&self, service: Rc<I>)
+    where
+        I: ?Sized + 'static,
+    {
+        self.services
+            .borrow_mut()
+            .insert(TypeId::of::<I>(), Box::new(Rc::downgrade(&service)));
+    }
+
+    /// Unregisters the service registered for the type `I`.
+    pub fn unregister<I>(&self)
+    where
+        I: ?Sized + 'static,
+    {
+        self.services.borrow_mut().remove(&TypeId::of::<I>());
+    }
+
+    /// Returns the service registered for the type `I`.
+    ///
+    /// If the service has been dropped, it is removed from the registry and `None` is
+    /// returned.
+    pub fn get<I>(&self) -> Option<Rc<I>>
+    where
+        I: ?Sized + 'static,
+    {
+        let services = &mut *self.services.borrow_mut();
+        let id = TypeId::of::<I>();
+        let service = services.get(&id)?.downcast_ref::<Weak<I>>()?.upgrade();
+        if service.is_none() {
+            services.remove(&id);
+        }
+        service
+    }
+
+    /// Returns the entry for the type `I`.
+    ///
+    /// The entry does not borrow the registry. In particular, the function passed to
+    /// [or_register](Entry::or_register) can use the registry, for example, to look up
+    /// the services that the new service depends on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::registry::WeakRegistry;
+    ///
+    /// let registry = WeakRegistry::default();
+    /// let service = Rc::new(1);
+    /// assert_eq!(*registry.entry::<i32>().or_register(|| service.clone()), 1);
+    /// assert_eq!(*registry.entry::<i32>().or_register(|| Rc::new(2)), 1);
+    /// ```
+    pub fn entry<I>(&self) -> Entry<'_, I>
+    where
+        I: ?Sized + 'static,
+    {
+        match self.get() {
+            Some(service) => Entry::Occupied(service),
+            None => Entry::Vacant(VacantEntry {
+                registry: self,
+                _phantom: PhantomData,
+            }),
+        }
+    }
+}
+
+impl Debug for WeakRegistry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("WeakRegistry").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "sync")]
+impl SyncWeakRegistry {
+    /// Registers a service.
+    ///
+    /// The registry only retains a weak reference to the service. If a service was
+    /// previously registered for the type `I`, it is replaced.
+    pub fn register<I>(&self, service: Arc<I>)
+    where
+        I: ?Sized + Send + Sync + 'static,
+    {
+        self.services
+            .lock()
+            .insert(TypeId::of::<I>(), Box::new(Arc::downgrade(&service)));
+    }
+
+    /// Unregisters the service registered for the type `I`.
+    pub fn unregister<I>(&self)
+    where
+        I: ?Sized + Send + Sync + 'static,
+    {
+        self.services.lock().remove(&TypeId::of::<I>());
+    }
+
+    /// Returns the service registered for the type `I`.
+    ///
+    /// If the service has been dropped, it is removed from the registry and `None` is
+    /// returned.
+    pub fn get<I>(&self) -> Option<Arc<I>>
+    where
+        I: ?Sized + Send + Sync + 'static,
+    {
+        let services = &mut *self.services.lock();
+        let id = TypeId::of::<I>();
+        let service = services.get(&id)?.downcast_ref::<ArcWeak<I>>()?.upgrade();
+        if service.is_none() {
+            services.remove(&id);
+        }
+        service
+    }
+
+    /// Returns the entry for the type `I`.
+    ///
+    /// The entry does not hold the lock of the registry. In particular, the function
+    /// passed to [or_register](SyncEntry::or_register) can use the registry, for example,
+    /// to look up the services that the new service depends on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::registry::SyncWeakRegistry;
+    ///
+    /// let registry = SyncWeakRegistry::default();
+    /// let service = Arc::new(1);
+    /// assert_eq!(*registry.entry::<i32>().or_register(|| service.clone()), 1);
+    /// assert_eq!(*registry.entry::<i32>().or_register(|| Arc::new(2)), 1);
+    /// ```
+    pub fn entry<I>(&self) -> SyncEntry<'_, I>
+    where
+        I: ?Sized + Send + Sync + 'static,
+    {
+        match self.get() {
+            Some(service) => SyncEntry::Occupied(service),
+            None => SyncEntry::Vacant(SyncVacantEntry {
+                registry: self,
+                _phantom: PhantomData,
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "sync")]
+impl Debug for SyncWeakRegistry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SyncWeakRegistry").finish_non_exhaustive()
+    }
+}
+
+impl<'a, I> Entry<'a, I>
+where
+    I: ?Sized + 'static,
+{
+    /// Returns the registered service or registers the service returned by `f`.
+    ///
+    /// `f` is invoked while the registry is not borrowed. If `f` registers a service for
+    /// the type itself, that service is returned and the service returned by `f` is
+    /// dropped.
+    pub fn or_register(self, f: impl FnOnce() -> Rc<I>) -> Rc<I> {
+        match self {
+            Entry::Occupied(service) => service,
+            Entry::Vacant(entry) => entry.register(f()),
+        }
+    }
+}
+
+impl<I> VacantEntry<'_, I>
+where
+    I: ?Sized + 'static,
+{
+    /// Registers a service.
+    ///
+    /// The registry only retains a weak reference to the service. If a live service has
+    /// been registered for the type since the entry was created, that service is
+    /// returned instead and `service` is not registered.
+    pub fn register(self, service: Rc<I>) -> Rc<I> {
+        let registered = {
+            let services = &mut *self.registry.services.borrow_mut();
+            let id = TypeId::of::<I>();
+            let registered = services
+                .get(&id)
+                .and_then(|s| s.downcast_ref::<Weak<I>>())
+                .and_then(Weak::upgrade);
+            if registered.is_none() {
+                services.insert(id, Box::new(Rc::downgrade(&service)));
+            }
+            registered
+        };
+        // The unused service is dropped after the registry has been released since its
+        // drop impl might use the registry.
+        registered.unwrap_or(service)
+    }
+}
+
+impl<I> Debug for Entry<'_, I>
+where
+    I: ?Sized + 'static,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Entry::Occupied(_) => f.debug_tuple("Occupied").finish_non_exhaustive(),
+            Entry::Vacant(e) => f.debug_tuple("Vacant").field(e).finish(),
+        }
+    }
+}
+
+impl<I> Debug for VacantEntry<'_, I>
+where
+    I: ?Sized + 'static,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("VacantEntry").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<'a, I> SyncEntry<'a, I>
+where
+    I: ?Sized + Send + Sync + 'static,
+{
+    /// Returns the registered service or registers the service returned by `f`.
+    ///
+    /// `f` is invoked while the registry is not locked. If a service for the type is
+    /// registered while `f` runs, that service is returned and the service returned by
+    /// `f` is dropped.
+    pub fn or_register(self, f: impl FnOnce() -> Arc<I>) -> Arc<I> {
+        match self {
+            SyncEntry::Occupied(service) => service,
+            SyncEntry::Vacant(entry) => entry.register(f()),
+        }
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<I> SyncVacantEntry<'_, I>
+where
+    I: ?Sized + Send + Sync + 'static,
+{
+    /// Registers a service.
+    ///
+    /// The registry only retains a weak reference to the service. If a live service has
+    /// been registered for the type since the entry was created, that service is
+    /// returned instead and `service` is not registered.
+    pub fn register(self, service: Arc<I>) -> Arc<I> {
+        let registered = {
+            let services = &mut *self.registry.services.lock();
+            let id = TypeId::of::<I>();
+            let registered = services
+                .get(&id)
+                .and_then(|s| s.downcast_ref::<ArcWeak<I>>())
+                .and_then(ArcWeak::upgrade);
+            if registered.is_none() {
+                services.insert(id, Box::new(Arc::downgrade(&service)));
+            }
+            registered
+        };
+        // The unused service is dropped after the registry has been unlocked since its
+        // drop impl might use the registry.
+        registered.unwrap_or(service)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<I> Debug for SyncEntry<'_, I>
+where
+    I: ?Sized + Send + Sync + 'static,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SyncEntry::Occupied(_) => f.debug_tuple("Occupied").finish_non_exhaustive(),
+            SyncEntry::Vacant(e) => f.debug_tuple("Vacant").field(e).finish(),
+        }
+    }
+}
+
+#[cfg(feature = "sync")]
+impl<I> Debug for SyncVacantEntry<'_, I>
+where
+    I: ?Sized + Send + Sync + 'static,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SyncVacantEntry").finish_non_exhaustive()
+    }
+}
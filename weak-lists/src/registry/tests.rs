@@ -0,0 +1,154 @@
+use {
+    crate::registry::{Entry, WeakRegistry},
+    alloc::rc::Rc,
+};
+#[cfg(feature = "sync")]
+use {
+    crate::registry::{SyncEntry, SyncWeakRegistry},
+    alloc::sync::Arc,
+};
+
+trait Service {
+    fn id(&self) -> usize;
+}
+
+impl Service for usize {
+    fn id(&self) -> usize {
+        *self
+    }
+}
+
+#[test]
+fn register_get() {
+    let registry = WeakRegistry::default();
+    assert!(registry.get::<dyn Service>().is_none());
+    let service1 = Rc::new(1usize);
+    let service2 = Rc::new(2usize);
+    registry.register::<dyn Service>(service1.clone());
+    registry.register::<usize>(service2.clone());
+    assert_eq!(registry.get::<dyn Service>().unwrap().id(), 1);
+    assert_eq!(*registry.get::<usize>().unwrap(), 2);
+    registry.register::<dyn Service>(service2.clone());
+    assert_eq!(registry.get::<dyn Service>().unwrap().id(), 2);
+    drop(service2);
+    assert!(registry.get::<dyn Service>().is_none());
+    assert!(registry.get::<usize>().is_none());
+    registry.register::<dyn Service>(service1.clone());
+    registry.unregister::<dyn Service>();
+    assert!(registry.get::<dyn Service>().is_none());
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn sync_register_get() {
+    let registry = SyncWeakRegistry::default();
+    assert!(registry.get::<dyn Service + Send + Sync>().is_none());
+    let service1 = Arc::new(1usize);
+    let service2 = Arc::new(2usize);
+    registry.register::<dyn Service + Send + Sync>(service1.clone());
+    registry.register::<usize>(service2.clone());
+    assert_eq!(registry.get::<dyn Service + Send + Sync>().unwrap().id(), 1);
+    assert_eq!(*registry.get::<usize>().unwrap(), 2);
+    registry.register::<dyn Service + Send + Sync>(service2.clone());
+    assert_eq!(registry.get::<dyn Service + Send + Sync>().unwrap().id(), 2);
+    drop(service2);
+    assert!(registry.get::<dyn Service + Send + Sync>().is_none());
+    assert!(registry.get::<usize>().is_none());
+    registry.register::<dyn Service + Send + Sync>(service1.clone());
+    registry.unregister::<dyn Service + Send + Sync>();
+    assert!(registry.get::<dyn Service + Send + Sync>().is_none());
+}
+
+#[test]
+fn entry() {
+    let registry = WeakRegistry::default();
+    let service1 = Rc::new(1usize);
+    let service2 = Rc::new(2usize);
+    let Entry::Vacant(entry) = registry.entry::<dyn Service>() else {
+        panic!();
+    };
+    entry.register(service1.clone());
+    let Entry::Occupied(service) = registry.entry::<dyn Service>() else {
+        panic!();
+    };
+    assert_eq!(service.id(), 1);
+    drop(service);
+    let service = registry
+        .entry::<dyn Service>()
+        .or_register(|| service2.clone());
+    assert_eq!(service.id(), 1);
+    drop((service, service1));
+    let service = registry
+        .entry::<dyn Service>()
+        .or_register(|| service2.clone());
+    assert_eq!(service.id(), 2);
+}
+
+#[test]
+fn entry_or_register_uses_registry() {
+    let registry = WeakRegistry::default();
+    let dependency = Rc::new(1usize);
+    registry.register::<usize>(dependency.clone());
+    let service = registry
+        .entry::<dyn Service>()
+        .or_register(|| Rc::new(registry.get::<usize>().unwrap().id() + 1));
+    assert_eq!(service.id(), 2);
+    assert_eq!(registry.get::<dyn Service>().unwrap().id(), 2);
+    drop(service);
+    let registered = Rc::new(3usize);
+    let service = registry.entry::<dyn Service>().or_register(|| {
+        registry.register::<dyn Service>(registered.clone());
+        Rc::new(4usize)
+    });
+    assert_eq!(service.id(), 3);
+    assert_eq!(registry.get::<dyn Service>().unwrap().id(), 3);
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn sync_entry() {
+    let registry = SyncWeakRegistry::default();
+    let service1 = Arc::new(1usize);
+    let service2 = Arc::new(2usize);
+    let SyncEntry::Vacant(entry) = registry.entry::<dyn Service + Send + Sync>() else {
+        panic!();
+    };
+    entry.register(service1.clone());
+    let SyncEntry::Occupied(service) = registry.entry::<dyn Service + Send + Sync>() else {
+        panic!();
+    };
+    assert_eq!(service.id(), 1);
+    drop(service);
+    let service = registry
+        .entry::<dyn Service + Send + Sync>()
+        .or_register(|| service2.clone());
+    assert_eq!(service.id(), 1);
+    drop((service, service1));
+    let service = registry
+        .entry::<dyn Service + Send + Sync>()
+        .or_register(|| service2.clone());
+    assert_eq!(service.id(), 2);
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn sync_entry_or_register_uses_registry() {
+    let registry = SyncWeakRegistry::default();
+    let dependency = Arc::new(1usize);
+    registry.register::<usize>(dependency.clone());
+    let service = registry
+        .entry::<dyn Service + Send + Sync>()
+        .or_register(|| Arc::new(registry.get::<usize>().unwrap().id() + 1));
+    assert_eq!(service.id(), 2);
+    assert_eq!(registry.get::<dyn Service + Send + Sync>().unwrap().id(), 2);
+    drop(service);
+    let registered = Arc::new(3usize);
+    let service = registry
+        .entry::<dyn Service + Send + Sync>()
+        .or_register(|| {
+            registry.register::<dyn Service + Send + Sync>(registered.clone());
+            Arc::new(4usize)
+        });
+    assert_eq!(service.id(), 3);
+    assert_eq!(registry.get::<dyn Service + Send + Sync>().unwrap().id(), 3);
+}
@@ -1,14 +1,67 @@
 //! The thread-safe version of the list.
 
+mod aging;
+mod builder;
+mod cascade;
+#[cfg(feature = "std")]
+mod cells;
+mod clock;
+mod contention;
+mod deferred;
+mod detach;
+mod dispatch;
+mod drain;
 mod element;
+mod enabled;
+mod epoch;
+mod events;
+mod frozen;
+#[cfg(feature = "test-util")]
+mod invariants;
 mod iter;
+mod limited;
 mod list;
+mod multi;
+mod once;
+mod paired;
+mod pin;
+mod policy;
+pub mod raw;
+mod reattach;
+mod recency;
+mod retained;
+mod set;
+mod sharded;
+mod shutdown;
+mod targets;
 #[cfg(test)]
 mod tests;
+mod view;
+mod visibility;
+mod watch;
+
+pub use set::iter_chain;
 
 use {
-    alloc::sync::{Arc, Weak},
-    core::ops::Range,
+    crate::{
+        id::{EntryId, MembershipId},
+        policy::LivenessPolicy,
+    },
+    alloc::{
+        boxed::Box,
+        collections::BTreeMap,
+        sync::{Arc, Weak},
+        vec::{self, Vec},
+    },
+    contention::ListMutex,
+    core::{
+        any::Any,
+        iter::Flatten,
+        marker::PhantomData,
+        ops::Range,
+        slice,
+        sync::atomic::{AtomicPtr, AtomicUsize},
+    },
     parking_lot::Mutex,
     stable_map::StableMap,
 };
@@ -21,6 +74,8 @@ use {
 ///
 /// This list supports concurrent iteration and modification.
 ///
+/// The type parameter `I` is the type of the ids of the entries. See [EntryId].
+///
 /// # Examples
 ///
 /// ```
@@ -86,20 +141,131 @@ use {
 /// // Callback 0 invoked
 /// // Callback 2 invoked
 /// ```
-pub struct SyncWeakList<T>
+pub struct SyncWeakList<T, I = u64>
 where
     T: ?Sized,
+    I: EntryId,
 {
-    data: Arc<Mutex<WeakListData<T>>>,
+    data: Arc<ListMutex<T, I>>,
+}
+
+/// A builder for [SyncWeakList]s.
+///
+/// # Examples
+///
+/// ```
+/// use weak_lists::sync::{SyncWeakListBuilder, SyncWeakList};
+///
+/// let list: SyncWeakList<u32> = SyncWeakListBuilder::default().capacity(1024).build();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SyncWeakListBuilder {
+    capacity: usize,
+    #[cfg(feature = "deterministic")]
+    first_id: u64,
+    track_invocations: bool,
+    max_entries_per_target: Option<usize>,
+    fair: bool,
+    #[cfg(feature = "debug-registry")]
+    debug_name: Option<alloc::string::String>,
 }
 
-struct WeakListData<T>
+struct WeakListData<T, I>
 where
     T: ?Sized,
+    I: EntryId,
 {
-    next_id: u64,
+    next_id: I,
+    /// The sequence number of the next attachment. See
+    /// [attach_seq](SyncWeakListElement::attach_seq).
+    next_seq: u64,
     active_iterators: usize,
-    members: StableMap<u64, Weak<T>>,
+    members: StableMap<I, Member<T, I>>,
+    /// The index at which [dispatch_some](SyncWeakList::dispatch_some) resumes.
+    cursor: usize,
+    /// Whether the list compacts itself when iteration starts. See [raw].
+    auto_compact: bool,
+    /// See [compaction_epoch](SyncWeakList::compaction_epoch).
+    compaction_epoch: u64,
+    /// The number of iterations that have been started. See [dead_age_stats](SyncWeakList::dead_age_stats).
+    iterations: u64,
+    /// The number of members that an iterator has skipped because their targets had
+    /// been dropped. See [live_len_hint](SyncWeakList::live_len_hint).
+    known_dead: usize,
+    /// See [set_clock](SyncWeakList::set_clock).
+    clock: Option<Arc<dyn Fn() -> u64 + Send + Sync>>,
+    /// The members that were cleared while their elements had requested to be reattached.
+    /// See [reopen_pending](SyncWeakList::reopen_pending).
+    pending: Vec<Member<T, I>>,
+    /// The entries that were attached by [attach_visible_now](SyncWeakListElement::attach_visible_now)
+    /// while iterators existed, together with their indices.
+    late: Vec<(I, usize)>,
+    /// The keys that have been notified since the outermost active iteration started.
+    /// See [notify_coalesced](SyncWeakList::notify_coalesced).
+    coalesced: Vec<u64>,
+    /// Whether the dispatch helpers record invocations. See [track_invocations](SyncWeakListBuilder::track_invocations).
+    track_invocations: bool,
+    /// See [set_max_concurrent_iterations](SyncWeakList::set_max_concurrent_iterations).
+    max_concurrent_iterations: Option<usize>,
+    /// See [max_entries_per_target](SyncWeakListBuilder::max_entries_per_target).
+    max_entries_per_target: Option<usize>,
+    /// The lists that are cleared together with this list. See [add_child](SyncWeakList::add_child).
+    children: Vec<Weak<ListMutex<T, I>>>,
+    /// Whether the lock is released fairly. See [fair](SyncWeakListBuilder::fair).
+    fair: bool,
+    /// Whether elements can no longer be attached. See [close](SyncWeakList::close).
+    closed: bool,
+    #[cfg(feature = "std")]
+    generation: u64,
+    #[cfg(feature = "std")]
+    change: Arc<parking_lot::Condvar>,
+    /// See [attach_events](SyncWeakList::attach_events).
+    #[cfg(feature = "std")]
+    events: Option<std::sync::mpsc::SyncSender<AttachEvent<I>>>,
+    /// See [dropped_attach_events](SyncWeakList::dropped_attach_events).
+    #[cfg(feature = "std")]
+    dropped_events: u64,
+}
+
+struct Member<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    id: I,
+    t: Weak<T>,
+    entry: Arc<EntryCell<T, I>>,
+    /// The time at which the first iterator that failed to upgrade `t` was created.
+    dead_since: Option<u64>,
+    /// A copy of [EntryData::reattach].
+    reattach: bool,
+    /// A copy of [EntryData::enabled].
+    enabled: bool,
+    /// See [attach_seq](SyncWeakListElement::attach_seq).
+    seq: u64,
+    /// `None` if all fields of the extra state have their default values.
+    extra: Option<Box<MemberExtra>>,
+}
+
+/// The state of a [Member] that is only used by some features.
+///
+/// It is stored separately so that members that do not use these features stay small.
+#[derive(Clone, Default)]
+struct MemberExtra {
+    /// See [attach_with_group](SyncWeakListElement::attach_with_group).
+    group: Option<u32>,
+    /// See [with_keepalive](SyncWeakListElement::with_keepalive).
+    keepalive: Option<Weak<dyn Any + Send + Sync>>,
+    /// See [invocation_stats](SyncWeakList::invocation_stats).
+    invocations: InvocationStats,
+    /// The first iteration that may yield the member. See
+    /// [attach_visible_next](SyncWeakListElement::attach_visible_next).
+    visible_from: u64,
+    /// Whether the member is removed once it has been yielded. See
+    /// [attach_once](SyncWeakListElement::attach_once).
+    once: bool,
+    /// See [attach_with_epoch](SyncWeakListElement::attach_with_epoch).
+    epoch: u64,
 }
 
 /// An thread-safe element that can be inserted into a weak list.
@@ -108,30 +274,597 @@ where
 /// detaches itself from the previous list.
 ///
 /// When this object is dropped, it detaches itself from its current list.
-pub struct SyncWeakListElement<T>
+///
+/// If `T` is sized, this object has the size of four pointers. Additionally, each
+/// element allocates its shared state in a separate `Arc` whose size depends on the id
+/// type. On 64-bit targets with the default id type, this allocation has 48 bytes.
+/// Applications with many elements can use a smaller id type such as `u32` to reduce it.
+/// See [new_with_id_type](Self::new_with_id_type).
+///
+/// While the element is attached, the list stores a record of 64 bytes for it on 64-bit
+/// targets. Attaching with a group, a keepalive object, a visibility or epoch
+/// restriction, or [once](Self::attach_once), and tracking invocations, allocates
+/// another block of state for this record.
+pub struct SyncWeakListElement<T, I = u64>
 where
     T: ?Sized,
+    I: EntryId,
 {
     t: Weak<T>,
-    data: Mutex<EntryData<T>>,
+    /// See [with_keepalive](Self::with_keepalive).
+    keepalive: Option<Weak<dyn Any + Send + Sync>>,
+    data: Arc<EntryCell<T, I>>,
+}
+
+/// The state of an element that is shared with its entry in a list.
+struct EntryCell<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// A weak reference to the [ListMutex] of the list that the element is attached to,
+    /// converted with [Weak::into_raw], or null.
+    ///
+    /// The reference is only replaced while `data` is locked. When the list is dropped, the
+    /// lowest bit is set without locking `data`. See
+    /// [is_attached](SyncWeakListElement::is_attached).
+    owner: AtomicPtr<ListMutex<T, I>>,
+    data: Mutex<EntryData<I>>,
+}
+
+struct EntryData<I>
+where
+    I: EntryId,
+{
+    id: I,
+    /// See [set_reattach_on_clear](SyncWeakListElement::set_reattach_on_clear).
+    reattach: bool,
+    /// See [defer_attach](SyncWeakListElement::defer_attach).
+    deferred: bool,
+    /// See [set_enabled](SyncWeakListElement::set_enabled).
+    enabled: bool,
 }
 
-struct EntryData<T>
+/// A read-only view of a [SyncWeakList].
+///
+/// This object is created by calling [view](SyncWeakList::view). It provides the functions of
+/// the list that only observe the entries, such as [iter](Self::iter), but no way to
+/// attach elements to the list or to detach them. This object can be copied freely.
+pub struct SyncWeakListView<'a, T, I = u64>
 where
     T: ?Sized,
+    I: EntryId,
 {
-    id: u64,
-    owner: Weak<Mutex<WeakListData<T>>>,
+    list: &'a SyncWeakList<T, I>,
+}
+
+/// A handle that can detach a [SyncWeakListElement] but not attach it.
+///
+/// This object is created by calling [detach_handle](SyncWeakListElement::detach_handle). It does not
+/// keep the element alive.
+pub struct SyncDetachHandle<T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    data: Weak<EntryCell<T, I>>,
 }
 
 /// An iterator over list elements.
 ///
 /// This object is created by calling [iter](SyncWeakList::iter) or by using the
 /// [IntoIterator] implementation of `&SyncWeakList`.
-pub struct Iter<'a, T>
+pub struct Iter<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    iter: Range<usize>,
+    data: &'a ListMutex<T, I>,
+    skipped: usize,
+    peeked: Option<(usize, I, Arc<T>)>,
+    visibility: Visibility<I>,
+    /// See [skip_target](Self::skip_target).
+    excluded: Option<Weak<T>>,
+}
+
+/// An immutable snapshot of the elements of a list.
+///
+/// This object is created by calling [freeze](SyncWeakList::freeze). It holds weak
+/// references to the elements that were alive when it was created. Cloning this object is
+/// cheap and iterating over it does not acquire any locks.
+pub struct FrozenWeakList<T>
+where
+    T: ?Sized,
+{
+    members: Arc<[Weak<T>]>,
+}
+
+/// An iterator over the elements of a frozen list.
+///
+/// This object is created by calling [iter](FrozenWeakList::iter) or by using the
+/// [IntoIterator] implementation of `&FrozenWeakList`.
+pub struct FrozenIter<'a, T>
+where
+    T: ?Sized,
+{
+    iter: slice::Iter<'a, Weak<T>>,
+}
+
+/// An iterator over a snapshot of the elements of a list that can be sent to other
+/// threads.
+///
+/// This object is created by calling [send_iter](SyncWeakList::send_iter) or by using the
+/// [IntoIterator] implementation of [FrozenWeakList]. It owns the snapshot and therefore
+/// does not borrow the list. Elements that have been dropped since the snapshot was
+/// created are skipped.
+pub struct SendIter<T>
+where
+    T: ?Sized,
+{
+    members: Arc<[Weak<T>]>,
+    iter: Range<usize>,
+}
+
+/// An iterator over list elements and their slots.
+///
+/// This object is created by calling [iter_indexed](SyncWeakList::iter_indexed).
+pub struct IndexedIter<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    iter: Iter<'a, T, I>,
+}
+
+/// An iterator over a limited number of list elements.
+///
+/// This object is created by calling [iter_limited](SyncWeakList::iter_limited).
+pub struct LimitedIter<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    iter: Iter<'a, T, I>,
+    /// The index at which the iterator started.
+    start: usize,
+    /// Whether the iterator has wrapped around at the end of the list.
+    wrapped: bool,
+    /// The number of elements that the iterator may still yield.
+    remaining: usize,
+}
+
+/// An iterator over list elements that keeps the entries alive until they are yielded.
+///
+/// This object is created by calling [iter_retained](SyncWeakList::iter_retained).
+pub struct RetainedIter<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    iter: Iter<'a, T, I>,
+    /// The targets that have not yet been yielded, keyed by the ids of their members.
+    retained: BTreeMap<I, Arc<T>>,
+}
+
+/// An iterator over list elements that yields the most recently attached entries first.
+///
+/// This object is created by calling [iter_by_recency](SyncWeakList::iter_by_recency).
+pub struct RecencyIter<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    list: &'a SyncWeakList<T, I>,
+    /// The ids and sequence numbers of the entries that have not yet been yielded.
+    entries: vec::IntoIter<(I, u64)>,
+}
+
+/// A list whose iterators only yield the entries accepted by a [LivenessPolicy].
+///
+/// This list wraps a [SyncWeakList]. Elements are attached to the wrapped list, which can
+/// be accessed with [list](Self::list). See [policy](crate::policy) for details.
+pub struct SyncPolicyWeakList<T, P, I = u64>
+where
+    T: ?Sized,
+    P: LivenessPolicy<T>,
+    I: EntryId,
+{
+    list: SyncWeakList<T, I>,
+    policy: PhantomData<fn() -> P>,
+}
+
+/// An iterator over the entries of a [SyncPolicyWeakList].
+///
+/// This object is created by calling [iter](SyncPolicyWeakList::iter).
+pub struct PolicyIter<'a, T, P, I = u64>
 where
     T: ?Sized,
+    P: LivenessPolicy<T>,
+    I: EntryId,
+{
+    iter: Iter<'a, T, I>,
+    policy: PhantomData<fn() -> P>,
+}
+
+/// An iterator over list elements that does not borrow the list.
+///
+/// This object is created by calling [iter_owned](SyncWeakList::iter_owned).
+///
+/// If the list is dropped while this iterator exists, the iterator no longer yields any
+/// elements.
+pub struct OwnedIter<T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
 {
     iter: Range<usize>,
-    data: &'a Mutex<WeakListData<T>>,
+    data: Weak<ListMutex<T, I>>,
+    visibility: Visibility<I>,
+}
+
+/// An iterator over the list elements that are also elements of another list.
+///
+/// This object is created by calling [intersect_iter](SyncWeakList::intersect_iter).
+pub struct Intersection<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    iter: Iter<'a, T, I>,
+    other: TargetSet<T>,
+}
+
+/// An iterator over the list elements that are not elements of another list.
+///
+/// This object is created by calling [difference_iter](SyncWeakList::difference_iter).
+pub struct Difference<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    iter: Iter<'a, T, I>,
+    other: TargetSet<T>,
+}
+
+/// An iterator over the elements of two lists.
+///
+/// This object is created by calling [union_iter](SyncWeakList::union_iter).
+pub struct Union<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    first: Iter<'a, T, I>,
+    second: Iter<'a, T, I>,
+    seen: TargetSet<T>,
+}
+
+/// An iterator over the elements of multiple lists.
+///
+/// This object is created by calling [iter_chain].
+pub struct IterChain<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    iters: Vec<Iter<'a, T, I>>,
+    seen: TargetSet<T>,
+}
+
+/// An iterator that removes the yielded elements from the list.
+///
+/// This object is created by calling [drain](SyncWeakList::drain).
+pub struct Drain<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    list: &'a SyncWeakList<T, I>,
+    iter: Iter<'a, T, I>,
+}
+
+/// An iterator that removes the elements matching a predicate from the list.
+///
+/// This object is created by calling [extract_if](SyncWeakList::extract_if).
+pub struct ExtractIf<'a, T, F, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    list: &'a SyncWeakList<T, I>,
+    iter: Iter<'a, T, I>,
+    pred: F,
+}
+
+/// A list holding weak references to pinned elements.
+///
+/// This list behaves like [SyncWeakList] except that its elements can only be created from
+/// `Pin<Arc<T>>` and that its iterators yield `Pin<Arc<T>>`.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use weak_lists::sync::{SyncPinWeakList, SyncPinWeakListElement};
+///
+/// let list = SyncPinWeakList::default();
+/// let entry = Arc::pin(1);
+/// let element = SyncPinWeakListElement::new(&entry);
+/// element.attach(&list);
+/// assert_eq!(list.iter().next(), Some(entry.clone()));
+/// ```
+pub struct SyncPinWeakList<T>
+where
+    T: ?Sized,
+{
+    list: SyncWeakList<T>,
+}
+
+/// An element that can be inserted into a [SyncPinWeakList].
+///
+/// This object behaves like [SyncWeakListElement].
+pub struct SyncPinWeakListElement<T>
+where
+    T: ?Sized,
+{
+    element: SyncWeakListElement<T>,
+}
+
+/// An iterator over the elements of a [SyncPinWeakList].
+///
+/// This object is created by calling [iter](SyncPinWeakList::iter) or by using the [IntoIterator]
+/// implementation of `&SyncPinWeakList`.
+pub struct PinIter<'a, T>
+where
+    T: ?Sized,
+{
+    iter: Iter<'a, T>,
+}
+
+/// A list that distributes its elements over `N` internal lists.
+///
+/// Each internal list, called a shard, has its own lock. Attaching and detaching elements
+/// only locks the shard of the element. This reduces contention for lists that are
+/// modified by many threads at the same time. Iterators cover all shards.
+///
+/// `N` must not be 0.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use weak_lists::sync::{SyncWeakListElement, SyncWeakListSharded};
+///
+/// let list = SyncWeakListSharded::<_, 4>::default();
+/// let entries = [Arc::new(0), Arc::new(1), Arc::new(2)];
+/// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+/// for element in &elements {
+///     list.attach(element);
+/// }
+/// assert_eq!(list.shards()[1].iter().next(), Some(entries[1].clone()));
+/// let mut targets: Vec<_> = list.iter().map(|e| *e).collect();
+/// targets.sort();
+/// assert_eq!(targets, [0, 1, 2]);
+/// ```
+pub struct SyncWeakListSharded<T, const N: usize>
+where
+    T: ?Sized,
+{
+    shards: [SyncWeakList<T>; N],
+    next: AtomicUsize,
+}
+
+/// An iterator over the elements of a [SyncWeakListSharded].
+///
+/// This object is created by calling [iter](SyncWeakListSharded::iter) or by using the
+/// [IntoIterator] implementation of `&SyncWeakListSharded`.
+pub struct ShardedIter<'a, T>
+where
+    T: ?Sized,
+{
+    iter: Flatten<slice::Iter<'a, SyncWeakList<T>>>,
+}
+
+/// An element that is a member of two lists at the same time.
+///
+/// This is useful if an object has to be registered in a global list and in a list of its
+/// parent. The element is always attached to both lists or to neither of them.
+///
+/// The element is locked while it is attached to or detached from the lists. Other threads
+/// never observe it in only one of the lists as a result of these operations.
+///
+/// When this object is dropped, it detaches itself from both lists.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use weak_lists::sync::{SyncWeakList, SyncPairedWeakListElement};
+///
+/// let global = SyncWeakList::default();
+/// let parent = SyncWeakList::default();
+/// let entry = Arc::new(1);
+/// let element = SyncPairedWeakListElement::new(Arc::downgrade(&entry));
+/// element.attach(&global, &parent);
+/// assert_eq!(global.iter().next(), Some(entry.clone()));
+/// assert_eq!(parent.iter().next(), Some(entry.clone()));
+/// element.detach();
+/// assert!(global.iter().next().is_none());
+/// assert!(parent.iter().next().is_none());
+/// ```
+pub struct SyncPairedWeakListElement<T>
+where
+    T: ?Sized,
+{
+    global: SyncWeakListElement<T>,
+    parent: SyncWeakListElement<T>,
+}
+
+/// An element that is a member of any number of lists at the same time.
+///
+/// Each call to [attach](Self::attach) adds a membership and returns its id. The
+/// memberships are independent of each other and can be removed individually with
+/// [detach](Self::detach) or all at once with [detach_all](Self::detach_all). A list can
+/// contain the element multiple times.
+///
+/// When this object is dropped, it detaches itself from all lists.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use weak_lists::sync::{SyncMultiWeakListElement, SyncWeakList};
+///
+/// let topics = [SyncWeakList::default(), SyncWeakList::default()];
+/// let entry = Arc::new(1);
+/// let element = SyncMultiWeakListElement::new(Arc::downgrade(&entry));
+/// let ids = topics.each_ref().map(|t| element.attach(t));
+/// assert!(element.detach(ids[0]));
+/// assert!(topics[0].iter().next().is_none());
+/// assert_eq!(topics[1].iter().next(), Some(entry.clone()));
+/// element.detach_all();
+/// assert!(topics[1].iter().next().is_none());
+/// ```
+pub struct SyncMultiWeakListElement<T>
+where
+    T: ?Sized,
+{
+    t: Weak<T>,
+    memberships: Mutex<Memberships<T>>,
+}
+
+/// The memberships of a [SyncMultiWeakListElement].
+struct Memberships<T>
+where
+    T: ?Sized,
+{
+    next_id: u64,
+    elements: Vec<(MembershipId, SyncWeakListElement<T>)>,
+}
+
+/// The state that an iterator uses to yield the entries attached by
+/// [attach_visible_now](SyncWeakListElement::attach_visible_now) while it runs.
+#[derive(Clone)]
+struct Visibility<I>
+where
+    I: EntryId,
+{
+    /// The iteration started by the iterator.
+    iteration: u64,
+    /// The number of elements of [WeakListData::late] that the iterator has processed.
+    late: usize,
+    /// The late entries that the iterator has not yet reached and would otherwise miss.
+    extra: Vec<(I, usize)>,
+    /// The time at which the iteration was started. See [set_clock](SyncWeakList::set_clock).
+    now: u64,
+    /// The largest epoch of the entries that the iterator yields. See
+    /// [iter_epoch_at_most](SyncWeakList::iter_epoch_at_most).
+    max_epoch: u64,
+}
+
+struct TargetSet<T>
+where
+    T: ?Sized,
+{
+    targets: BTreeMap<usize, Weak<T>>,
+}
+
+/// Statistics about the entries of a list whose targets have been dropped.
+///
+/// Ages are measured in ticks of the clock of the list. By default, the clock counts
+/// iterations. Every iterator created for the list starts a new iteration. A different
+/// clock can be set with [set_clock](SyncWeakList::set_clock). The age of an entry is the
+/// time that has passed since the creation of the first iterator that skipped the entry.
+///
+/// This object is returned by [dead_age_stats](SyncWeakList::dead_age_stats).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DeadAgeStats {
+    /// The number of entries whose targets have been dropped.
+    pub dead: usize,
+    /// The number of those entries that have not yet been skipped by any iterator.
+    pub unobserved: usize,
+    /// The sum of the ages of the entries that have been skipped.
+    pub total_age: u64,
+    /// The largest age of the entries that have been skipped.
+    pub max_age: u64,
+}
+
+/// Statistics about the contention of the lock of a [SyncWeakList].
+///
+/// This object is returned by [contention_stats](SyncWeakList::contention_stats).
+#[cfg(feature = "metrics")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ContentionStats {
+    /// The number of times the lock has been acquired.
+    pub acquisitions: u64,
+    /// The number of those acquisitions that had to wait for another thread.
+    pub contended: u64,
+    /// The number of contended acquisitions whose wait time has been measured.
+    pub sampled: u64,
+    /// The sum of the wait times of the measured acquisitions.
+    pub sampled_wait: core::time::Duration,
+}
+
+/// Whether an [AttachEvent] reports an attachment or a detachment.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AttachEventKind {
+    /// An element was attached to the list.
+    Attached,
+    /// An element was detached from the list or the list was cleared.
+    Detached,
+}
+
+/// A change of the members of a [SyncWeakList].
+///
+/// These events are sent to the channel set with
+/// [attach_events](SyncWeakList::attach_events).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AttachEvent<I = u64> {
+    /// The id of the entry.
+    pub id: I,
+    /// Whether the entry was attached or detached.
+    pub kind: AttachEventKind,
+}
+
+/// How [for_each_mut](SyncWeakList::for_each_mut) handles targets that are locked by
+/// another thread.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum LockPolicy {
+    /// The target is skipped.
+    #[default]
+    Skip,
+    /// The function waits until the lock becomes available.
+    Block,
+}
+
+/// Statistics about the callbacks invoked for an entry by the dispatch helpers.
+///
+/// Iterations are counted as described in [DeadAgeStats].
+///
+/// This object is returned by [invocation_stats](SyncWeakList::invocation_stats).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct InvocationStats {
+    /// The number of times a callback has been invoked for the entry.
+    pub count: u64,
+    /// The iteration during which a callback was last invoked for the entry.
+    pub last_iteration: Option<u64>,
+}
+
+/// A panic caught while invoking a callback for a list element.
+///
+/// This object is returned by [for_each_catch_unwind](SyncWeakList::for_each_catch_unwind).
+#[cfg(feature = "std")]
+pub struct CaughtPanic<T>
+where
+    T: ?Sized,
+{
+    /// The element for which the callback panicked.
+    pub member: Arc<T>,
+    /// The panic payload.
+    pub payload: alloc::boxed::Box<dyn core::any::Any + Send>,
 }
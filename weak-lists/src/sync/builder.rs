@@ -0,0 +1,276 @@
+#[cfg(feature = "debug-registry")]
+use crate::debug::registry::{self, ListStats};
+use {
+    crate::{
+        id::EntryId,
+        sync::{ListMutex, SyncWeakList, SyncWeakListBuilder, WeakListData},
+    },
+    alloc::{
+        sync::{Arc, Weak},
+        vec::Vec,
+    },
+    stable_map::StableMap,
+};
+
+impl SyncWeakListBuilder {
+    /// Sets the number of elements the list can hold without reallocating.
+    ///
+    /// The default is 0.
+    pub fn capacity(&mut self, capacity: usize) -> &mut Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets the id of the first entry of the list.
+    ///
+    /// Subsequent entries receive the following ids. If the id does not fit into the id type
+    /// of the list, it is truncated. The default is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{sync::SyncWeakListBuilder, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakListBuilder::default().first_id(100).build();
+    /// let entry = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&entry));
+    /// element.attach(&list);
+    /// assert_eq!(element.id(), Some(100));
+    /// ```
+    #[cfg(feature = "deterministic")]
+    pub fn first_id(&mut self, id: u64) -> &mut Self {
+        self.first_id = id;
+        self
+    }
+
+    /// Sets whether the lock of the list is released fairly.
+    ///
+    /// By default, a thread that releases the lock of the list can immediately acquire it
+    /// again, even if other threads are waiting for it. Under heavy attach and detach churn,
+    /// iterators, which acquire the lock once per entry, can therefore be starved by
+    /// threads modifying the list.
+    ///
+    /// If this is enabled, the lock is handed over to a waiting thread whenever an entry
+    /// is attached, detached, or yielded by an iterator. This bounds the latency of
+    /// iteration at the cost of throughput. Alternatively, use
+    /// [freeze](SyncWeakList::freeze) to acquire the lock only once.
+    ///
+    /// The default is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use weak_lists::sync::{SyncWeakList, SyncWeakListBuilder};
+    ///
+    /// let list: SyncWeakList<u32> = SyncWeakListBuilder::default().fair(true).build();
+    /// ```
+    pub fn fair(&mut self, fair: bool) -> &mut Self {
+        self.fair = fair;
+        self
+    }
+
+    /// Sets whether the list records the callbacks invoked by the dispatch helpers.
+    ///
+    /// If this is enabled, [for_each](SyncWeakList::for_each), [dispatch_some](SyncWeakList::dispatch_some),
+    /// and `for_each_catch_unwind` record for each entry how often and when they invoked
+    /// the callback. The records can be retrieved with
+    /// [invocation_stats](SyncWeakList::invocation_stats). This is useful to find entries that
+    /// never receive callbacks.
+    ///
+    /// The default is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{sync::SyncWeakListBuilder, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakListBuilder::default().track_invocations(true).build();
+    /// let entry = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&entry));
+    /// element.attach(&list);
+    /// list.for_each(|_| ());
+    /// list.for_each(|_| ());
+    /// assert_eq!(list.invocation_stats(element.id().unwrap()).unwrap().count, 2);
+    /// ```
+    pub fn track_invocations(&mut self, track: bool) -> &mut Self {
+        self.track_invocations = track;
+        self
+    }
+
+    /// Sets the maximum number of entries of the list that may reference the same target.
+    ///
+    /// Targets are compared by identity. If an element is attached to the list while
+    /// this many entries with the same live target are attached, the element is left
+    /// detached. This protects against registering the same target repeatedly through
+    /// different elements. Checking the limit requires a scan of the list.
+    ///
+    /// The limit applies to the `attach` functions of
+    /// [SyncWeakListElement](crate::sync::SyncWeakListElement) and
+    /// [SyncPairedWeakListElement](crate::sync::SyncPairedWeakListElement). By default,
+    /// there is no limit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{sync::SyncWeakListBuilder, Error, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakListBuilder::default().max_entries_per_target(1).build();
+    /// let entry = Arc::new(1);
+    /// let elements = [(); 2].map(|_| SyncWeakListElement::new(Arc::downgrade(&entry)));
+    /// assert_eq!(elements[0].try_attach(&list), Ok(()));
+    /// assert_eq!(elements[1].try_attach(&list), Err(Error::TargetLimitReached));
+    /// assert_eq!(list.iter().count(), 1);
+    /// ```
+    pub fn max_entries_per_target(&mut self, max: usize) -> &mut Self {
+        self.max_entries_per_target = Some(max);
+        self
+    }
+
+    /// Sets the name of the list in the output of [dump_all](crate::dump_all).
+    ///
+    /// By default, the list has no name.
+    ///
+    /// This function is only available if the `debug-registry` feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use weak_lists::sync::{SyncWeakListBuilder, SyncWeakList};
+    ///
+    /// let list: SyncWeakList<u32> = SyncWeakListBuilder::default()
+    ///     .debug_name("callbacks")
+    ///     .build();
+    /// let mut out = String::new();
+    /// weak_lists::dump_all(&mut out).unwrap();
+    /// assert!(out.contains("SyncWeakList \"callbacks\""));
+    /// ```
+    #[cfg(feature = "debug-registry")]
+    pub fn debug_name(&mut self, name: &str) -> &mut Self {
+        self.debug_name = Some(name.into());
+        self
+    }
+
+    /// Creates a new list.
+    pub fn build<T>(&self) -> SyncWeakList<T>
+    where
+        T: ?Sized,
+    {
+        self.build_with_id_type()
+    }
+
+    /// Creates a new list with a custom id type.
+    ///
+    /// See [EntryId] for details.
+    pub fn build_with_id_type<T, I>(&self) -> SyncWeakList<T, I>
+    where
+        T: ?Sized,
+        I: EntryId,
+    {
+        let list = SyncWeakList {
+            data: Arc::new(ListMutex::new(WeakListData {
+                #[cfg(not(feature = "deterministic"))]
+                next_id: I::FIRST,
+                #[cfg(feature = "deterministic")]
+                next_id: I::from_u64(self.first_id),
+                next_seq: 0,
+                active_iterators: 0,
+                cursor: 0,
+                auto_compact: true,
+                compaction_epoch: 0,
+                iterations: 0,
+                known_dead: 0,
+                clock: None,
+                pending: Vec::new(),
+                late: Vec::new(),
+                coalesced: Vec::new(),
+                track_invocations: self.track_invocations,
+                max_concurrent_iterations: None,
+                max_entries_per_target: self.max_entries_per_target,
+                children: Vec::new(),
+                fair: self.fair,
+                closed: false,
+                members: StableMap::with_capacity(self.capacity),
+                #[cfg(feature = "std")]
+                generation: 0,
+                #[cfg(feature = "std")]
+                change: Default::default(),
+                #[cfg(feature = "std")]
+                events: None,
+                #[cfg(feature = "std")]
+                dropped_events: 0,
+            })),
+        };
+        #[cfg(feature = "debug-registry")]
+        // SAFETY: The list is unregistered when its data is dropped.
+        unsafe {
+            registry::register(
+                true,
+                Arc::as_ptr(&list.data) as usize,
+                "SyncWeakList",
+                self.debug_name.clone(),
+                debug_stats::<T, I>,
+            );
+        }
+        list
+    }
+}
+
+impl<T, I> WeakListData<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns the number of entries with the target that can be added without exceeding
+    /// the limit set with [max_entries_per_target](SyncWeakListBuilder::max_entries_per_target).
+    pub(super) fn target_budget(&self, t: &Weak<T>) -> usize {
+        let Some(max) = self.max_entries_per_target else {
+            return usize::MAX;
+        };
+        if t.strong_count() == 0 {
+            return usize::MAX;
+        }
+        let entries = self
+            .members
+            .values()
+            .filter(|member| Weak::ptr_eq(&member.t, t) && member.t.strong_count() > 0)
+            .count();
+        max.saturating_sub(entries)
+    }
+}
+
+#[cfg(feature = "debug-registry")]
+impl<T, I> WeakListData<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn debug_stats(&self) -> ListStats {
+        ListStats {
+            entries: self.members.len(),
+            alive: self
+                .members
+                .values()
+                .filter(|m| m.t.strong_count() > 0)
+                .count(),
+            active_iterators: self.active_iterators,
+            iterations: self.iterations,
+        }
+    }
+}
+
+/// # Safety
+///
+/// `list` must be the address of a live `ListMutex<T, I>`.
+#[cfg(feature = "debug-registry")]
+unsafe fn debug_stats<T, I>(list: usize) -> ListStats
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    // SAFETY: Requirement of this function.
+    let list = unsafe { &*(list as *const ListMutex<T, I>) };
+    list.lock().debug_stats()
+}
@@ -0,0 +1,112 @@
+use {
+    crate::{
+        id::EntryId,
+        sync::{ListMutex, Member, SyncWeakList},
+    },
+    alloc::{
+        sync::{Arc, Weak},
+        vec::Vec,
+    },
+    core::{mem, ptr},
+};
+
+impl<T, I> SyncWeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Makes `child` a dependent of this list.
+    ///
+    /// When this list is cleared or dropped, `child` is cleared as well. This applies
+    /// recursively to the dependents of `child`. The list only holds a weak reference to
+    /// `child`.
+    ///
+    /// The lists are cleared one after another. Other threads might observe a state in
+    /// which this list has already been cleared but `child` has not.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let server = SyncWeakList::default();
+    /// let connection = SyncWeakList::default();
+    /// server.add_child(&connection);
+    /// let entry = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&entry));
+    /// element.attach(&connection);
+    /// drop(server);
+    /// assert!(connection.iter().next().is_none());
+    /// ```
+    pub fn add_child(&self, child: &SyncWeakList<T, I>) {
+        let data = &mut *self.data.lock();
+        data.children.retain(|c| c.strong_count() > 0);
+        data.children.push(Arc::downgrade(&child.data));
+    }
+
+    /// Removes `child` from the dependents of this list.
+    ///
+    /// See [add_child](Self::add_child).
+    pub fn remove_child(&self, child: &SyncWeakList<T, I>) {
+        let data = &mut *self.data.lock();
+        data.children
+            .retain(|c| c.strong_count() > 0 && !ptr::eq(c.as_ptr(), Arc::as_ptr(&child.data)));
+    }
+}
+
+/// Clears the list and, recursively, its dependents.
+///
+/// Returns the members that were removed from this list. Their elements have been
+/// detached.
+pub(super) fn clear<T, I>(list: &ListMutex<T, I>) -> Vec<Member<T, I>>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    let (removed, children) = {
+        let data = &mut *list.lock();
+        let removed = data.clear_members();
+        data.cursor = 0;
+        data.changed();
+        (removed, mem::take(&mut data.children))
+    };
+    // The elements are locked before the list. Therefore, they can only be detached
+    // after the list has been unlocked.
+    for member in &removed {
+        member.detach_removed(list);
+    }
+    // The children are taken out of the list while they are being cleared. This
+    // terminates the recursion if the dependencies contain a cycle. No list is locked
+    // while the children are cleared.
+    clear_children(&children);
+    let data = &mut *list.lock();
+    // Other threads might have added children in the meantime.
+    let added = mem::replace(&mut data.children, children);
+    data.children.extend(added);
+    data.children.retain(|c| c.strong_count() > 0);
+    removed
+}
+
+fn clear_children<T, I>(children: &[Weak<ListMutex<T, I>>])
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    for child in children {
+        if let Some(child) = child.upgrade() {
+            clear(&child);
+        }
+    }
+}
+
+impl<T, I> Drop for SyncWeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn drop(&mut self) {
+        let children: Vec<_> = mem::take(&mut self.data.lock().children);
+        clear_children(&children);
+    }
+}
@@ -0,0 +1,59 @@
+use {
+    crate::{
+        id::EntryId,
+        sync::{LockPolicy, SyncWeakList},
+    },
+    std::sync::RwLock,
+};
+
+impl<T, I> SyncWeakList<RwLock<T>, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Invokes the callback with a write lock of each target of the list.
+    ///
+    /// This function behaves like [for_each](Self::for_each) but write-locks each target
+    /// for the duration of the callback. If a target is locked by another thread,
+    /// `policy` determines whether the target is skipped or whether this function waits
+    /// for the lock. Poisoned targets are always skipped.
+    ///
+    /// The list itself is not locked while the callback runs.
+    ///
+    /// Returns the number of targets that were skipped because they were locked or
+    /// poisoned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{Arc, RwLock};
+    /// use weak_lists::{sync::LockPolicy, SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(RwLock::new(0)), Arc::new(RwLock::new(1))];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let lock = entries[0].read().unwrap();
+    /// assert_eq!(list.for_each_mut(LockPolicy::Skip, |e| *e += 1), 1);
+    /// drop(lock);
+    /// assert_eq!(list.for_each_mut(LockPolicy::Block, |e| *e += 1), 0);
+    /// assert_eq!(*entries[0].read().unwrap(), 1);
+    /// assert_eq!(*entries[1].read().unwrap(), 3);
+    /// ```
+    pub fn for_each_mut(&self, policy: LockPolicy, mut f: impl FnMut(&mut T)) -> usize {
+        let mut skipped = 0;
+        self.for_each(|lock| {
+            let guard = match policy {
+                LockPolicy::Skip => lock.try_write().ok(),
+                LockPolicy::Block => lock.write().ok(),
+            };
+            match guard {
+                Some(mut t) => f(&mut t),
+                None => skipped += 1,
+            }
+        });
+        skipped
+    }
+}
@@ -0,0 +1,61 @@
+use {
+    crate::{
+        id::EntryId,
+        sync::{ListMutex, SyncWeakList},
+    },
+    alloc::sync::Arc,
+};
+
+impl<T, I> SyncWeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Sets the clock of the list.
+    ///
+    /// The clock returns the current time in arbitrary ticks. It is used by the time-based
+    /// features of the list such as [dead_age_stats](Self::dead_age_stats). By default,
+    /// the clock counts the iterations of the list. A custom clock can be used to measure
+    /// wall-clock time or to control time in tests. The list never calls the clock while
+    /// it is locked. The clock must not decrease.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{
+    ///     atomic::{AtomicU64, Ordering::Relaxed},
+    ///     Arc,
+    /// };
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let time = Arc::new(AtomicU64::new(100));
+    /// list.set_clock({
+    ///     let time = time.clone();
+    ///     move || time.load(Relaxed)
+    /// });
+    /// let entry = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&entry));
+    /// element.attach(&list);
+    /// drop(entry);
+    /// list.iter().for_each(drop);
+    /// time.store(150, Relaxed);
+    /// assert_eq!(list.dead_age_stats().max_age, 50);
+    /// ```
+    pub fn set_clock(&self, clock: impl Fn() -> u64 + Send + Sync + 'static) {
+        let old = self.data.lock().clock.replace(Arc::new(clock));
+        drop(old);
+    }
+}
+
+/// Returns the current time of the clock set with [set_clock](SyncWeakList::set_clock).
+///
+/// Returns `None` if no clock has been set.
+pub(super) fn read_clock<T, I>(data: &ListMutex<T, I>) -> Option<u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    let clock = data.lock().clock.clone()?;
+    Some(clock())
+}
@@ -0,0 +1,136 @@
+#[cfg(feature = "metrics")]
+use {
+    crate::sync::{ContentionStats, SyncWeakList},
+    core::{
+        sync::atomic::{AtomicU64, Ordering::Relaxed},
+        time::Duration,
+    },
+    std::time::Instant,
+};
+use {
+    crate::{id::EntryId, sync::WeakListData},
+    parking_lot::{Mutex, MutexGuard},
+};
+
+/// The wait time of every `SAMPLE_INTERVAL`th contended acquisition is measured.
+#[cfg(feature = "metrics")]
+const SAMPLE_INTERVAL: u64 = 8;
+
+/// The mutex protecting the data of a list.
+///
+/// If the `metrics` feature is enabled, this object records how often threads have to
+/// wait for the lock.
+pub(super) struct ListMutex<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    mutex: Mutex<WeakListData<T, I>>,
+    #[cfg(feature = "metrics")]
+    acquisitions: AtomicU64,
+    #[cfg(feature = "metrics")]
+    contended: AtomicU64,
+    #[cfg(feature = "metrics")]
+    sampled: AtomicU64,
+    #[cfg(feature = "metrics")]
+    sampled_wait_nanos: AtomicU64,
+}
+
+impl<T, I> ListMutex<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    pub(super) fn new(data: WeakListData<T, I>) -> Self {
+        Self {
+            mutex: Mutex::new(data),
+            #[cfg(feature = "metrics")]
+            acquisitions: AtomicU64::new(0),
+            #[cfg(feature = "metrics")]
+            contended: AtomicU64::new(0),
+            #[cfg(feature = "metrics")]
+            sampled: AtomicU64::new(0),
+            #[cfg(feature = "metrics")]
+            sampled_wait_nanos: AtomicU64::new(0),
+        }
+    }
+
+    #[cfg(not(feature = "metrics"))]
+    pub(super) fn lock(&self) -> MutexGuard<'_, WeakListData<T, I>> {
+        self.mutex.lock()
+    }
+
+    #[cfg(feature = "metrics")]
+    pub(super) fn lock(&self) -> MutexGuard<'_, WeakListData<T, I>> {
+        self.acquisitions.fetch_add(1, Relaxed);
+        if let Some(guard) = self.mutex.try_lock() {
+            return guard;
+        }
+        if !self
+            .contended
+            .fetch_add(1, Relaxed)
+            .is_multiple_of(SAMPLE_INTERVAL)
+        {
+            return self.mutex.lock();
+        }
+        let start = Instant::now();
+        let guard = self.mutex.lock();
+        let nanos = u64::try_from(start.elapsed().as_nanos()).unwrap_or(u64::MAX);
+        self.sampled_wait_nanos.fetch_add(nanos, Relaxed);
+        self.sampled.fetch_add(1, Relaxed);
+        guard
+    }
+}
+
+impl<T, I> Drop for ListMutex<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn drop(&mut self) {
+        let list = self as *const Self as usize;
+        #[cfg(feature = "debug-registry")]
+        crate::debug::registry::unregister(true, list);
+        self.mutex.get_mut().detach_all(list);
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl<T, I> SyncWeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns statistics about the contention of the lock of the list.
+    ///
+    /// A lock acquisition is contended if the lock is held by another thread at the time.
+    /// Only the wait time of a sample of the contended acquisitions is measured. These
+    /// statistics can be used to determine which lists are hot enough to require
+    /// sharding.
+    ///
+    /// This function is only available if the `metrics` feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entry = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&entry));
+    /// element.attach(&list);
+    /// let stats = list.contention_stats();
+    /// assert!(stats.acquisitions > 0);
+    /// assert_eq!(stats.contended, 0);
+    /// ```
+    pub fn contention_stats(&self) -> ContentionStats {
+        let lock = &*self.data;
+        ContentionStats {
+            acquisitions: lock.acquisitions.load(Relaxed),
+            contended: lock.contended.load(Relaxed),
+            sampled: lock.sampled.load(Relaxed),
+            sampled_wait: Duration::from_nanos(lock.sampled_wait_nanos.load(Relaxed)),
+        }
+    }
+}
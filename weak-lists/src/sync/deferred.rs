@@ -0,0 +1,75 @@
+use {
+    crate::{
+        error::Error,
+        id::EntryId,
+        sync::{element::AttachOptions, SyncWeakList, SyncWeakListElement},
+    },
+    core::mem,
+};
+
+impl<T, I> SyncWeakListElement<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Records that the element should be attached to a list that does not exist yet.
+    ///
+    /// The element is detached from its current list. Once the list exists,
+    /// [adopt_pending](SyncWeakList::adopt_pending) completes the attachment. This is
+    /// useful if elements are created before the list that they are registered with.
+    /// Attaching or detaching the element cancels the deferred attachment.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let entry = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&entry));
+    /// element.defer_attach();
+    /// assert!(element.is_attach_deferred());
+    /// let list = SyncWeakList::default();
+    /// assert_eq!(list.adopt_pending(&element), Ok(()));
+    /// assert!(!element.is_attach_deferred());
+    /// assert_eq!(list.iter().next(), Some(entry));
+    /// ```
+    pub fn defer_attach(&self) {
+        let data = &mut *self.data.lock();
+        self.data.detach(data);
+        data.deferred = true;
+    }
+
+    /// Returns whether the element has a deferred attachment.
+    ///
+    /// See [defer_attach](Self::defer_attach).
+    pub fn is_attach_deferred(&self) -> bool {
+        self.data.lock().deferred
+    }
+}
+
+impl<T, I> SyncWeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Completes the deferred attachment of an element.
+    ///
+    /// See [defer_attach](SyncWeakListElement::defer_attach). The element is attached to
+    /// this list as if by [attach](SyncWeakListElement::attach). If multiple threads try
+    /// to adopt the element at the same time, only one of them succeeds.
+    ///
+    /// Returns [Error::NotDeferred] if the element has no deferred attachment. If the
+    /// element cannot be attached, the error is returned and the attachment remains
+    /// deferred.
+    pub fn adopt_pending(&self, element: &SyncWeakListElement<T, I>) -> Result<(), Error> {
+        if !mem::take(&mut element.data.lock().deferred) {
+            return Err(Error::NotDeferred);
+        }
+        let res = element.attach_with_options(self, AttachOptions::default());
+        if res.is_err() {
+            element.data.lock().deferred = true;
+        }
+        res.map(drop)
+    }
+}
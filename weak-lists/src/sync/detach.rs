@@ -0,0 +1,78 @@
+use {
+    crate::{
+        id::EntryId,
+        sync::{SyncDetachHandle, SyncWeakListElement},
+    },
+    alloc::sync::Arc,
+    core::fmt::{Debug, Formatter},
+};
+
+impl<T, I> SyncWeakListElement<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns a handle that can detach this element but not attach it to a list.
+    ///
+    /// This can be handed out as a subscription token without allowing the holder to
+    /// attach the element to other lists.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entry = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&entry));
+    /// element.attach(&list);
+    /// let handle = element.detach_handle();
+    /// handle.detach();
+    /// assert!(list.iter().next().is_none());
+    /// ```
+    pub fn detach_handle(&self) -> SyncDetachHandle<T, I> {
+        SyncDetachHandle {
+            data: Arc::downgrade(&self.data),
+        }
+    }
+}
+
+impl<T, I> SyncDetachHandle<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Detaches the element from its current list.
+    ///
+    /// This has the same effect as [SyncWeakListElement::detach]. If the element has been
+    /// attached to a different list since this handle was created, it is detached from
+    /// that list. If the element has been dropped, this function does nothing.
+    pub fn detach(&self) {
+        if let Some(data) = self.data.upgrade() {
+            data.detach(&mut data.lock());
+        }
+    }
+}
+
+impl<T, I> Clone for SyncDetachHandle<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl<T, I> Debug for SyncDetachHandle<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SyncDetachHandle").finish_non_exhaustive()
+    }
+}
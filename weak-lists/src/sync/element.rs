@@ -1,11 +1,23 @@
 use {
-    crate::sync::{EntryData, SyncWeakList, SyncWeakListElement},
-    alloc::sync::{Arc, Weak},
+    crate::{
+        error::Error,
+        id::EntryId,
+        sync::{
+            visibility::Visible, EntryCell, EntryData, InvocationStats, ListMutex, Member,
+            MemberExtra, SyncWeakList, SyncWeakListElement, WeakListData,
+        },
+    },
+    alloc::{
+        boxed::Box,
+        sync::{Arc, Weak},
+    },
     core::{
         fmt::{Debug, Formatter},
-        mem,
+        mem::ManuallyDrop,
+        ptr,
+        sync::atomic::{AtomicPtr, Ordering::Relaxed},
     },
-    parking_lot::Mutex,
+    parking_lot::{Mutex, MutexGuard},
 };
 
 impl<T> SyncWeakListElement<T>
@@ -35,24 +47,160 @@ where
     /// Since only weak references are stored, this does not create any actual reference
     /// cycles.
     pub fn new(t: Weak<T>) -> Self {
+        Self::new_with_id_type(t)
+    }
+
+    /// Creates a new list element from a strong reference.
+    ///
+    /// This is a shorthand for `SyncWeakListElement::new(Arc::downgrade(t))` that additionally
+    /// performs an unsizing coercion. This is useful when `T` is a trait object and the
+    /// type of the target is only known to implement that trait.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// #![feature(unsize)]
+    ///
+    /// use std::{marker::Unsize, sync::Arc};
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// trait Callback {}
+    ///
+    /// fn register<U>(list: &SyncWeakList<dyn Callback>, t: &Arc<U>) -> SyncWeakListElement<dyn Callback>
+    /// where
+    ///     U: Unsize<dyn Callback>,
+    /// {
+    ///     let element = SyncWeakListElement::from_arc(t);
+    ///     element.attach(list);
+    ///     element
+    /// }
+    /// ```
+    #[cfg(feature = "unsize")]
+    pub fn from_arc<U>(t: &Arc<U>) -> Self
+    where
+        U: ?Sized + core::marker::Unsize<T>,
+    {
+        let t: Weak<U> = Arc::downgrade(t);
+        Self::new(t)
+    }
+
+    /// Creates two list elements for different trait objects from one strong reference.
+    ///
+    /// This is useful when the same object should be registered in lists of different
+    /// trait objects. Both elements hold a weak reference to the same allocation. Each
+    /// element can be attached to one list of its type.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// #![feature(unsize)]
+    ///
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// trait Draw {}
+    /// trait Input {}
+    ///
+    /// struct Button;
+    ///
+    /// impl Draw for Button {}
+    /// impl Input for Button {}
+    ///
+    /// let draw = SyncWeakList::<dyn Draw>::default();
+    /// let input = SyncWeakList::<dyn Input>::default();
+    /// let button = Arc::new(Button);
+    /// let (a, b) = SyncWeakListElement::<dyn Draw>::from_arc_pair::<_, dyn Input>(&button);
+    /// a.attach(&draw);
+    /// b.attach(&input);
+    /// assert_eq!(draw.iter().count(), 1);
+    /// assert_eq!(input.iter().count(), 1);
+    /// ```
+    #[cfg(feature = "unsize")]
+    pub fn from_arc_pair<U, V>(t: &Arc<U>) -> (Self, SyncWeakListElement<V>)
+    where
+        U: ?Sized + core::marker::Unsize<T> + core::marker::Unsize<V>,
+        V: ?Sized,
+    {
+        (Self::from_arc(t), SyncWeakListElement::from_arc(t))
+    }
+}
+
+impl<T, I> SyncWeakListElement<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Creates a new list element for a list with a custom id type.
+    ///
+    /// This function behaves like [new](SyncWeakListElement::new). See [EntryId] for
+    /// details.
+    pub fn new_with_id_type(t: Weak<T>) -> Self {
         Self {
             t,
-            data: Mutex::new(EntryData {
-                id: 0,
-                owner: Default::default(),
-            }),
+            keepalive: None,
+            data: Arc::new(EntryCell::new(EntryData {
+                id: I::FIRST,
+                reattach: false,
+                deferred: false,
+                enabled: true,
+            })),
         }
     }
 
+    /// Creates a new list element whose entry is only valid while another object is alive.
+    ///
+    /// This function behaves like [new](SyncWeakListElement::new). Additionally, iterators skip the
+    /// entry if `keepalive` cannot be upgraded. This is useful if the target of the element is
+    /// only valid while another object, such as a session, is alive.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let callback = Arc::new(1);
+    /// let session = Arc::new("session");
+    /// let element = SyncWeakListElement::with_keepalive(Arc::downgrade(&callback), Arc::downgrade(&session));
+    /// element.attach(&list);
+    /// assert_eq!(list.iter().next(), Some(callback.clone()));
+    /// drop(session);
+    /// assert!(list.iter().next().is_none());
+    /// ```
+    pub fn with_keepalive<K>(t: Weak<T>, keepalive: Weak<K>) -> Self
+    where
+        K: Send + Sync + 'static,
+    {
+        let mut element = Self::new_with_id_type(t);
+        element.keepalive = Some(keepalive as _);
+        element
+    }
+
     /// Attaches the list element to a list.
     ///
     /// If this object was previously attached to a list, it is automatically detached
-    /// from that list.
+    /// from that list. This also applies if the element is already attached to `to`. The
+    /// element then receives a new id and might move to a different position in the list.
+    /// Use [ensure_attached](Self::ensure_attached) to keep the existing entry instead.
     ///
     /// The list will only hold on a weak reference to this element and vice versa.
     ///
+    /// If the element cannot be attached, for example because all ids of the [EntryId]
+    /// type of the list are in use, it is left detached. Use [try_attach](Self::try_attach)
+    /// to detect this.
+    ///
     /// Any existing iterator over the list might or might not see this element, this is
-    /// unspecified.
+    /// unspecified. Use [will_yield](crate::sync::Iter::will_yield) to determine whether
+    /// a specific iterator will see this element.
+    /// Use [attach_visible_now](Self::attach_visible_now) or
+    /// [attach_visible_next](Self::attach_visible_next) to choose the behavior.
+    ///
+    /// If the list has been [closed](SyncWeakList::close), the element is left detached.
+    ///
+    /// If the target of this element is still being constructed by [Arc::new_cyclic],
+    /// iterators skip the element until the construction has completed. See
+    /// [adopt_on_init](SyncWeakList::adopt_on_init) for a way to attach the element afterwards.
     ///
     /// # Examples
     ///
@@ -81,14 +229,304 @@ where
     /// assert!(clients1.iter().next().is_none());
     /// assert!(clients2.iter().next().is_some());
     /// ```
-    pub fn attach(&self, to: &SyncWeakList<T>) {
+    pub fn attach(&self, to: &SyncWeakList<T, I>) {
+        let _ = self.attach_with_options(to, AttachOptions::default());
+    }
+
+    /// Attaches the list element to a list and returns the number of entries in the list.
+    ///
+    /// This function behaves like [attach](Self::attach). The number of entries is
+    /// determined while the list is still locked. It therefore includes this element and
+    /// is not affected by concurrent modifications of the list. This can be used to
+    /// detect the first element that is attached to a list. Entries whose targets have
+    /// been dropped are included in the count.
+    ///
+    /// Returns an error if the element was not attached, for example [Error::Closed] if
+    /// the list has been [closed](SyncWeakList::close).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// assert_eq!(elements[0].attach_then_len(&list), Ok(1));
+    /// assert_eq!(elements[1].attach_then_len(&list), Ok(2));
+    /// assert_eq!(elements[1].attach_then_len(&list), Ok(2));
+    /// ```
+    pub fn attach_then_len(&self, to: &SyncWeakList<T, I>) -> Result<usize, Error> {
+        self.attach_with_options(to, AttachOptions::default())
+    }
+
+    /// Attaches the element to a list as a member of a group.
+    ///
+    /// This function behaves like [attach](Self::attach). Additionally, the element can
+    /// be detached together with all other members of the group by calling
+    /// [detach_group](SyncWeakList::detach_group).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1), Arc::new(2)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// elements[0].attach_with_group(&list, 1);
+    /// elements[1].attach_with_group(&list, 2);
+    /// elements[2].attach_with_group(&list, 1);
+    /// list.detach_group(1);
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [1]);
+    /// ```
+    pub fn attach_with_group(&self, to: &SyncWeakList<T, I>, group: u32) {
+        let _ = self.attach_with_options(
+            to,
+            AttachOptions {
+                group: Some(group),
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Attaches the element to a list using a caller-chosen key as its id.
+    ///
+    /// This function behaves like [attach](Self::attach) except that the entry receives
+    /// the id `key` instead of an id allocated by the list. The entry can then be
+    /// accessed with [get_key](SyncWeakList::get_key) and [detach_key](SyncWeakList::detach_key).
+    ///
+    /// Returns [Error::IdInUse] if the list already contains an entry with this id and
+    /// [Error::Closed] if the list has been [closed](SyncWeakList::close). The element is
+    /// left detached in this case.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use weak_lists::{Error, SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// assert_eq!(elements[0].attach_keyed(&list, 7), Ok(()));
+    /// assert_eq!(elements[1].attach_keyed(&list, 7), Err(Error::IdInUse));
+    /// assert_eq!(elements[0].id(), Some(7));
+    /// assert_eq!(list.get_key(7), Some(entries[0].clone()));
+    /// ```
+    pub fn attach_keyed(&self, to: &SyncWeakList<T, I>, key: I) -> Result<(), Error> {
+        self.attach_with_options(
+            to,
+            AttachOptions {
+                key: Some(key),
+                ..Default::default()
+            },
+        )
+        .map(drop)
+    }
+
+    /// Attaches the element to a list.
+    ///
+    /// Returns the number of entries in the list after the element has been attached.
+    pub(super) fn attach_with_options(
+        &self,
+        to: &SyncWeakList<T, I>,
+        options: AttachOptions<I>,
+    ) -> Result<usize, Error> {
         self.detach();
+        self.attach_locked(&mut self.data.lock(), to, options)
+    }
+
+    /// Attaches the detached element to a list.
+    ///
+    /// `data` must be the locked data of this element.
+    fn attach_locked(
+        &self,
+        data: &mut EntryData<I>,
+        to: &SyncWeakList<T, I>,
+        options: AttachOptions<I>,
+    ) -> Result<usize, Error> {
+        let AttachOptions {
+            group,
+            key,
+            visible,
+            once,
+            epoch,
+        } = options;
+        let mut list_data = WeakListData::lock_for_insert(&to.data);
+        if list_data.closed {
+            return Err(Error::Closed);
+        }
+        if list_data.target_budget(&self.t) == 0 {
+            return Err(Error::TargetLimitReached);
+        }
+        data.id = match key {
+            Some(key) if list_data.members.contains_key(&key) => return Err(Error::IdInUse),
+            Some(key) => key,
+            None => list_data.allocate_id()?,
+        };
+        self.data.set_owner(data, &to.data);
+        let visible_from = list_data.visible_from(visible);
+        let seq = list_data.allocate_seq();
+        list_data.insert_member(Member {
+            id: data.id,
+            t: self.t.clone(),
+            entry: self.data.clone(),
+            dead_since: None,
+            reattach: data.reattach,
+            enabled: data.enabled,
+            seq,
+            extra: MemberExtra {
+                group,
+                keepalive: self.keepalive.clone(),
+                visible_from,
+                once,
+                epoch,
+                ..Default::default()
+            }
+            .boxed(),
+        });
+        if let Visible::Now = visible {
+            list_data.record_late(data.id);
+        }
+        let len = list_data.members.len();
+        list_data.changed();
+        WeakListData::unlock(list_data);
+        Ok(len)
+    }
+
+    /// Returns the id of the entry of this element in its current list.
+    ///
+    /// Returns `None` if the element is not attached to a list. The id can be used with
+    /// [replace](SyncWeakList::replace).
+    pub fn id(&self) -> Option<I> {
+        if !self.is_attached() {
+            return None;
+        }
+        let data = &*self.data.lock();
+        self.data.has_live_owner(data).then_some(data.id)
+    }
+
+    /// Returns whether the element is attached to a list.
+    ///
+    /// Unlike [id](Self::id), this function does not lock the element and therefore
+    /// never waits for concurrent calls to [attach](Self::attach) or
+    /// [detach](Self::detach). If the list is being dropped concurrently, this function
+    /// might briefly continue to return `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let target = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&target));
+    /// assert!(!element.is_attached());
+    /// element.attach(&list);
+    /// assert!(element.is_attached());
+    /// drop(list);
+    /// assert!(!element.is_attached());
+    /// ```
+    pub fn is_attached(&self) -> bool {
+        self.data.is_attached()
+    }
+
+    /// Returns whether the target of this element is still alive.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use weak_lists::SyncWeakListElement;
+    ///
+    /// let target = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&target));
+    /// assert!(element.is_target_alive());
+    /// drop(target);
+    /// assert!(!element.is_target_alive());
+    /// ```
+    pub fn is_target_alive(&self) -> bool {
+        self.t.strong_count() > 0
+    }
+
+    /// Attaches the element to a list if its target is still alive.
+    ///
+    /// If the target of this element has already been dropped, the element is left
+    /// unchanged and this function returns [Error::TargetDropped]. Otherwise this function
+    /// behaves like [attach](Self::attach) but returns an error if the element was not
+    /// attached, for example [Error::IdsExhausted] if all ids of the list are in use.
+    ///
+    /// Note that the target is not yet alive while it is being constructed by
+    /// [Arc::new_cyclic]. Use [attach](Self::attach) in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use weak_lists::{Error, SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let target = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&target));
+    /// assert_eq!(element.try_attach(&list), Ok(()));
+    /// drop(target);
+    /// element.detach();
+    /// assert_eq!(element.try_attach(&list), Err(Error::TargetDropped));
+    /// assert!(list.iter().next().is_none());
+    /// ```
+    pub fn try_attach(&self, to: &SyncWeakList<T, I>) -> Result<(), Error> {
+        if !self.is_target_alive() {
+            return Err(Error::TargetDropped);
+        }
+        self.attach_with_options(to, AttachOptions::default())
+            .map(drop)
+    }
+
+    /// Attaches the element to a list unless it is already attached to that list.
+    ///
+    /// If the element is already attached to `to`, this function does nothing and the
+    /// element keeps its id and its position in the list. Otherwise, this function
+    /// behaves like [try_attach](Self::try_attach) except that it does not check whether
+    /// the target is alive. The check and the attachment happen while the element is
+    /// locked. Concurrent calls therefore attach the element at most once.
+    ///
+    /// Returns whether the element was attached by this call.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let id = elements[0].id();
+    /// assert_eq!(elements[0].ensure_attached(&list), Ok(false));
+    /// assert_eq!(elements[0].id(), id);
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [0, 1]);
+    /// ```
+    pub fn ensure_attached(&self, to: &SyncWeakList<T, I>) -> Result<bool, Error> {
         let data = &mut *self.data.lock();
-        data.owner = Arc::downgrade(&to.data);
-        let list_data = &mut *to.data.lock();
-        data.id = list_data.next_id;
-        list_data.next_id += 1;
-        list_data.members.insert(data.id, self.t.clone());
+        if ptr::eq(self.data.owner_ptr(data), Arc::as_ptr(&to.data)) {
+            let list_data = to.data.lock();
+            if matches!(
+                list_data.members.get(&data.id),
+                Some(m) if ptr::eq(Arc::as_ptr(&m.entry), &*self.data),
+            ) {
+                return Ok(false);
+            }
+        }
+        self.data.detach(data);
+        self.attach_locked(data, to, AttachOptions::default())?;
+        Ok(true)
     }
 
     /// Detaches the element from its current list.
@@ -119,34 +557,336 @@ where
     /// assert!(clients.iter().next().is_none());
     /// ```
     pub fn detach(&self) {
-        let data = &mut *self.data.lock();
-        let prev = mem::take(&mut data.owner).upgrade();
-        if let Some(prev) = prev {
-            let list_data = &mut *prev.lock();
-            list_data.members.remove(&data.id);
+        self.data.detach(&mut self.data.lock());
+    }
+
+    /// Detaches the element from its current list and returns whether the list is empty
+    /// afterwards.
+    ///
+    /// This function behaves like [detach](Self::detach). Whether the list is empty is
+    /// determined while the list is still locked and is therefore not affected by
+    /// concurrent modifications of the list. This can be used to detect the last element
+    /// that is detached from a list. Entries whose targets have been dropped are
+    /// considered.
+    ///
+    /// Returns [Error::NotAttached] if the element was not attached to a list.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use weak_lists::{Error, SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// assert_eq!(elements[0].detach_and_is_empty(), Ok(false));
+    /// assert_eq!(elements[1].detach_and_is_empty(), Ok(true));
+    /// assert_eq!(elements[1].detach_and_is_empty(), Err(Error::NotAttached));
+    /// ```
+    pub fn detach_and_is_empty(&self) -> Result<bool, Error> {
+        self.data
+            .detach(&mut self.data.lock())
+            .ok_or(Error::NotAttached)
+    }
+}
+
+/// The bit of [EntryCell::owner] that is set when the list is dropped.
+const LIST_DROPPED: usize = 1;
+
+impl<T, I> EntryCell<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Creates a cell that is not attached to a list.
+    pub(super) fn new(data: EntryData<I>) -> Self {
+        Self {
+            owner: AtomicPtr::new(ptr::null_mut()),
+            data: Mutex::new(data),
+        }
+    }
+
+    /// Creates a cell that is attached to a list.
+    pub(super) fn new_attached(data: EntryData<I>, owner: &Arc<ListMutex<T, I>>) -> Self {
+        let owner = Weak::into_raw(Arc::downgrade(owner)).cast_mut();
+        Self {
+            owner: AtomicPtr::new(owner),
+            data: Mutex::new(data),
+        }
+    }
+
+    pub(super) fn lock(&self) -> MutexGuard<'_, EntryData<I>> {
+        self.data.lock()
+    }
+
+    /// Returns whether the element is attached to a list. See [EntryCell::owner].
+    pub(super) fn is_attached(&self) -> bool {
+        let owner = self.owner.load(Relaxed);
+        !owner.is_null() && owner.addr() & LIST_DROPPED == 0
+    }
+
+    /// Returns the address of the list that the element is attached to or null.
+    ///
+    /// `data` must be the locked data of this cell. The list might have been dropped.
+    pub(super) fn owner_ptr(&self, data: &EntryData<I>) -> *const ListMutex<T, I> {
+        let _ = data;
+        self.owner
+            .load(Relaxed)
+            .map_addr(|addr| addr & !LIST_DROPPED)
+    }
+
+    /// Returns the list that the element is attached to unless it has been dropped.
+    ///
+    /// `data` must be the locked data of this cell.
+    pub(super) fn owner(&self, data: &EntryData<I>) -> Option<Arc<ListMutex<T, I>>> {
+        self.map_owner(data, Weak::upgrade)
+    }
+
+    /// Returns whether the element is attached to a list that has not been dropped.
+    ///
+    /// `data` must be the locked data of this cell. Unlike [owner](Self::owner), this
+    /// function never drops the list.
+    pub(super) fn has_live_owner(&self, data: &EntryData<I>) -> bool {
+        self.map_owner(data, |owner| owner.strong_count() > 0)
+    }
+
+    fn map_owner<R>(&self, data: &EntryData<I>, f: impl FnOnce(&Weak<ListMutex<T, I>>) -> R) -> R {
+        let owner = self.owner_ptr(data);
+        if owner.is_null() {
+            return f(&Weak::new());
+        }
+        // SAFETY: A non-null owner was created by Weak::into_raw in set_owner. The weak
+        // reference is only released while the data of this cell is locked, which the
+        // caller has done.
+        let owner = ManuallyDrop::new(unsafe { Weak::from_raw(owner) });
+        f(&owner)
+    }
+
+    /// Sets the list that the element is attached to.
+    ///
+    /// `data` must be the locked data of this cell.
+    pub(super) fn set_owner(&self, data: &mut EntryData<I>, owner: &Arc<ListMutex<T, I>>) {
+        let owner = Weak::into_raw(Arc::downgrade(owner)).cast_mut();
+        let prev = self.owner.swap(owner, Relaxed);
+        drop(Self::weak_from_raw(data, prev));
+    }
+
+    /// Resets the list that the element is attached to and returns the previous list.
+    ///
+    /// `data` must be the locked data of this cell.
+    pub(super) fn take_owner(&self, data: &mut EntryData<I>) -> Weak<ListMutex<T, I>> {
+        let prev = self.owner.swap(ptr::null_mut(), Relaxed);
+        Self::weak_from_raw(data, prev)
+    }
+
+    /// Converts a pointer that has been removed from [EntryCell::owner] back into a weak
+    /// reference.
+    ///
+    /// `data` must be the locked data of this cell.
+    fn weak_from_raw(data: &EntryData<I>, owner: *mut ListMutex<T, I>) -> Weak<ListMutex<T, I>> {
+        let _ = data;
+        if owner.is_null() {
+            return Weak::new();
+        }
+        // SAFETY: The pointer was created by Weak::into_raw in set_owner and has been
+        // removed from the cell. Therefore, we now own the weak reference.
+        unsafe { Weak::from_raw(owner.map_addr(|addr| addr & !LIST_DROPPED)) }
+    }
+
+    /// Marks the element as detached if it is attached to the list at address `list`.
+    ///
+    /// This is called when the list is dropped. The data of this cell is not locked.
+    fn mark_list_dropped(&self, list: usize) {
+        let owner = self.owner.load(Relaxed);
+        if owner.addr() == list {
+            let _ = self.owner.compare_exchange(
+                owner,
+                owner.map_addr(|addr| addr | LIST_DROPPED),
+                Relaxed,
+                Relaxed,
+            );
+        }
+    }
+
+    /// Detaches the element from its current list.
+    ///
+    /// `data` must be the locked data of this cell. Returns whether the list is empty
+    /// afterwards or `None` if the element was not a member of a list.
+    pub(super) fn detach(&self, data: &mut EntryData<I>) -> Option<bool> {
+        data.deferred = false;
+        let prev = self.take_owner(data).upgrade()?;
+        let mut list_data = prev.lock();
+        // The list might have been cleared and the id reused by another entry.
+        let is_member = match list_data.members.get(&data.id) {
+            Some(member) => ptr::eq(Arc::as_ptr(&member.entry), self),
+            None => false,
+        };
+        if is_member {
+            list_data.remove_member(data.id);
         }
+        let is_empty = list_data.members.is_empty();
+        list_data.changed();
+        WeakListData::unlock(list_data);
+        is_member.then_some(is_empty)
     }
 }
 
-impl<T> Drop for SyncWeakListElement<T>
+impl<T, I> Drop for EntryCell<T, I>
 where
     T: ?Sized,
+    I: EntryId,
+{
+    fn drop(&mut self) {
+        let owner = *self.owner.get_mut();
+        drop(Self::weak_from_raw(self.data.get_mut(), owner));
+    }
+}
+
+impl<T, I> WeakListData<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Marks the elements that are attached to this list as detached.
+    ///
+    /// This is called when the list is dropped. `list` is the address of the
+    /// [ListMutex] of the list. The elements are not locked since the last reference to
+    /// the list might be dropped while an element is locked.
+    pub(super) fn detach_all(&self, list: usize) {
+        for member in self.members.values().chain(&self.pending) {
+            member.entry.mark_list_dropped(list);
+        }
+    }
+}
+
+impl<T, I> Member<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns the target of the member unless its keepalive object has been dropped.
+    pub(super) fn target(&self) -> Option<&Weak<T>> {
+        match self.extra.as_ref().and_then(|e| e.keepalive.as_ref()) {
+            Some(k) if k.strong_count() == 0 => None,
+            _ => Some(&self.t),
+        }
+    }
+
+    /// Returns the extra state of the member, allocating it if necessary.
+    pub(super) fn extra_mut(&mut self) -> &mut MemberExtra {
+        self.extra.get_or_insert_with(Default::default)
+    }
+
+    /// See [MemberExtra::group].
+    pub(super) fn group(&self) -> Option<u32> {
+        self.extra.as_ref().and_then(|e| e.group)
+    }
+
+    /// See [MemberExtra::invocations].
+    pub(super) fn invocations(&self) -> InvocationStats {
+        self.extra
+            .as_ref()
+            .map(|e| e.invocations)
+            .unwrap_or_default()
+    }
+
+    /// See [MemberExtra::once].
+    pub(super) fn once(&self) -> bool {
+        self.extra.as_ref().is_some_and(|e| e.once)
+    }
+
+    /// Returns whether the target and the keepalive object of the member are alive.
+    pub(super) fn is_alive(&self) -> bool {
+        self.target().is_some_and(|t| t.strong_count() > 0)
+    }
+
+    /// Upgrades the target of the member unless its keepalive object has been dropped.
+    pub(super) fn upgrade(&self) -> Option<Arc<T>> {
+        self.target()?.upgrade()
+    }
+
+    /// Detaches the element of a member that has been removed from `list`.
+    ///
+    /// The list must not be locked.
+    pub(super) fn detach_removed(&self, list: &ListMutex<T, I>) {
+        let entry = &mut *self.entry.lock();
+        if entry.id == self.id && ptr::eq(self.entry.owner_ptr(entry), list) {
+            self.entry.take_owner(entry);
+        }
+    }
+}
+
+impl MemberExtra {
+    /// Boxes the state unless all fields have their default values.
+    pub(super) fn boxed(self) -> Option<Box<Self>> {
+        let MemberExtra {
+            group,
+            keepalive,
+            invocations,
+            visible_from,
+            once,
+            epoch,
+        } = &self;
+        let is_default = group.is_none()
+            && keepalive.is_none()
+            && *invocations == InvocationStats::default()
+            && *visible_from == 0
+            && !once
+            && *epoch == 0;
+        (!is_default).then(|| Box::new(self))
+    }
+}
+
+impl<T, I> Drop for SyncWeakListElement<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
 {
     fn drop(&mut self) {
         self.detach();
     }
 }
 
-impl<T> Debug for SyncWeakListElement<T>
+impl<T, I> Debug for SyncWeakListElement<T, I>
 where
     T: ?Sized,
+    I: EntryId,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let data = self.data.lock();
-        let owner = data.owner.upgrade();
+        let owner = self.data.owner(&data);
         let owner_id = owner.as_ref().map(Arc::as_ptr);
         f.debug_struct("SyncWeakListElement")
             .field("list", &owner_id)
             .finish_non_exhaustive()
     }
 }
+
+/// The options of [attach_with_options](SyncWeakListElement::attach_with_options).
+pub(super) struct AttachOptions<I> {
+    /// See [attach_with_group](SyncWeakListElement::attach_with_group).
+    pub(super) group: Option<u32>,
+    /// See [attach_keyed](SyncWeakListElement::attach_keyed).
+    pub(super) key: Option<I>,
+    pub(super) visible: Visible,
+    /// See [attach_once](SyncWeakListElement::attach_once).
+    pub(super) once: bool,
+    /// See [attach_with_epoch](SyncWeakListElement::attach_with_epoch).
+    pub(super) epoch: u64,
+}
+
+impl<I> Default for AttachOptions<I> {
+    fn default() -> Self {
+        Self {
+            group: None,
+            key: None,
+            visible: Visible::Unspecified,
+            once: false,
+            epoch: 0,
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use {
+    crate::{id::EntryId, sync::SyncWeakListElement},
+    alloc::sync::Arc,
+};
+
+impl<T, I> SyncWeakListElement<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Sets whether iterators yield the entry of the element.
+    ///
+    /// A disabled element remains attached to its list and keeps its id and its position.
+    /// Iterators skip its entry until it is enabled again. This includes the iterators
+    /// used by the dispatch helpers such as [for_each](crate::SyncWeakList::for_each) and
+    /// the snapshots taken by [map_collect](crate::SyncWeakList::map_collect). The flag is
+    /// checked when an iterator reaches the entry. It is retained when the element
+    /// is attached to another list.
+    ///
+    /// Elements are enabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// elements[0].set_enabled(false);
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [1]);
+    /// elements[0].set_enabled(true);
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [0, 1]);
+    /// ```
+    pub fn set_enabled(&self, enabled: bool) {
+        let data = &mut *self.data.lock();
+        data.enabled = enabled;
+        if let Some(list) = self.data.owner(data) {
+            let list_data = &mut *list.lock();
+            if let Some(member) = list_data.members.get_mut(&data.id) {
+                if Arc::ptr_eq(&member.entry, &self.data) {
+                    member.enabled = enabled;
+                }
+            }
+        }
+    }
+
+    /// Returns whether iterators yield the entry of the element.
+    ///
+    /// See [set_enabled](Self::set_enabled).
+    pub fn is_enabled(&self) -> bool {
+        self.data.lock().enabled
+    }
+}
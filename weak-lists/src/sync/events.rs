@@ -0,0 +1,110 @@
+use crate::{
+    id::EntryId,
+    sync::{AttachEventKind, WeakListData},
+};
+#[cfg(feature = "std")]
+use {
+    crate::sync::{AttachEvent, SyncWeakList},
+    std::sync::mpsc::{SyncSender, TrySendError},
+};
+
+#[cfg(feature = "std")]
+impl<T, I> SyncWeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Sends an event to `tx` whenever an element is attached to or detached from the
+    /// list.
+    ///
+    /// The events are sent while the list is locked but the list never waits for the
+    /// channel. If the channel is full, the event is discarded and counted instead. See
+    /// [dropped_attach_events](Self::dropped_attach_events). If the receiver has been
+    /// dropped, the sender is removed from the list.
+    ///
+    /// Clearing the list produces a [Detached](AttachEventKind::Detached) event for each
+    /// member. Replacing the element of a member produces a `Detached` event followed by
+    /// an [Attached](AttachEventKind::Attached) event with the same id. Targets being
+    /// dropped do not produce events.
+    ///
+    /// This replaces the sender set by a previous call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{mpsc, Arc};
+    /// use weak_lists::{
+    ///     sync::{AttachEvent, AttachEventKind},
+    ///     SyncWeakList, SyncWeakListElement,
+    /// };
+    ///
+    /// let list = SyncWeakList::default();
+    /// let (tx, rx) = mpsc::sync_channel(16);
+    /// list.attach_events(tx);
+    /// let entry = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&entry));
+    /// element.attach(&list);
+    /// let id = element.id().unwrap();
+    /// element.detach();
+    /// let events: Vec<_> = rx.try_iter().collect();
+    /// assert_eq!(
+    ///     events,
+    ///     [
+    ///         AttachEvent { id, kind: AttachEventKind::Attached },
+    ///         AttachEvent { id, kind: AttachEventKind::Detached },
+    ///     ],
+    /// );
+    /// ```
+    pub fn attach_events(&self, tx: SyncSender<AttachEvent<I>>) {
+        self.data.lock().events = Some(tx);
+    }
+
+    /// Stops sending events to the sender set by [attach_events](Self::attach_events).
+    pub fn clear_attach_events(&self) {
+        self.data.lock().events = None;
+    }
+
+    /// Returns the number of events that were discarded because the channel set by
+    /// [attach_events](Self::attach_events) was full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{mpsc, Arc};
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let (tx, rx) = mpsc::sync_channel(1);
+    /// list.attach_events(tx);
+    /// let entry = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&entry));
+    /// element.attach(&list);
+    /// element.detach();
+    /// assert_eq!(list.dropped_attach_events(), 1);
+    /// assert_eq!(rx.try_iter().count(), 1);
+    /// ```
+    pub fn dropped_attach_events(&self) -> u64 {
+        self.data.lock().dropped_events
+    }
+}
+
+impl<T, I> WeakListData<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Sends an event to the channel set by
+    /// [attach_events](crate::sync::SyncWeakList::attach_events), if any.
+    pub(super) fn emit(&mut self, id: I, kind: AttachEventKind) {
+        #[cfg(feature = "std")]
+        if let Some(tx) = &self.events {
+            match tx.try_send(AttachEvent { id, kind }) {
+                Ok(()) => {}
+                Err(TrySendError::Full(_)) => self.dropped_events += 1,
+                Err(TrySendError::Disconnected(_)) => self.events = None,
+            }
+        }
+        #[cfg(not(feature = "std"))]
+        let _ = (id, kind);
+    }
+}
@@ -0,0 +1,151 @@
+use {
+    crate::sync::{FrozenIter, FrozenWeakList, SendIter},
+    alloc::sync::Arc,
+    core::{
+        fmt::{Debug, Formatter},
+        iter::FusedIterator,
+    },
+};
+
+impl<T> FrozenWeakList<T>
+where
+    T: ?Sized,
+{
+    /// Returns the number of elements in the snapshot.
+    ///
+    /// This includes elements that have been dropped since the snapshot was created.
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Returns whether the snapshot contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Creates an iterator over the elements of the snapshot.
+    ///
+    /// Elements that have been dropped since the snapshot was created are skipped.
+    pub fn iter(&self) -> FrozenIter<'_, T> {
+        FrozenIter {
+            iter: self.members.iter(),
+        }
+    }
+}
+
+impl<T> Clone for FrozenWeakList<T>
+where
+    T: ?Sized,
+{
+    fn clone(&self) -> Self {
+        Self {
+            members: self.members.clone(),
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a FrozenWeakList<T>
+where
+    T: ?Sized,
+{
+    type Item = Arc<T>;
+    type IntoIter = FrozenIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> IntoIterator for FrozenWeakList<T>
+where
+    T: ?Sized,
+{
+    type Item = Arc<T>;
+    type IntoIter = SendIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        SendIter {
+            iter: 0..self.members.len(),
+            members: self.members,
+        }
+    }
+}
+
+impl<T> Debug for FrozenWeakList<T>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("FrozenWeakList")
+            .field("len", &self.members.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Iterator for FrozenIter<'_, T>
+where
+    T: ?Sized,
+{
+    type Item = Arc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.by_ref().find_map(|member| member.upgrade())
+    }
+}
+
+impl<T> Clone for FrozenIter<'_, T>
+where
+    T: ?Sized,
+{
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<T> Debug for FrozenIter<'_, T>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<T> FusedIterator for FrozenIter<'_, T> where T: ?Sized {}
+
+impl<T> Iterator for SendIter<T>
+where
+    T: ?Sized,
+{
+    type Item = Arc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let members = &self.members;
+        self.iter.by_ref().find_map(|idx| members[idx].upgrade())
+    }
+}
+
+impl<T> Clone for SendIter<T>
+where
+    T: ?Sized,
+{
+    fn clone(&self) -> Self {
+        Self {
+            members: self.members.clone(),
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<T> Debug for SendIter<T>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<T> FusedIterator for SendIter<T> where T: ?Sized {}
@@ -0,0 +1,62 @@
+use {
+    crate::{id::EntryId, sync::SyncWeakList},
+    alloc::{collections::BTreeSet, sync::Arc},
+    core::ptr,
+};
+
+impl<T, I> SyncWeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Checks the internal consistency of the list.
+    ///
+    /// This function is intended for stress tests that modify the list from many threads.
+    /// It acquires the lock of the list once and checks that
+    ///
+    /// - every entry can be found by its id,
+    /// - no element has more than one entry,
+    /// - the number of entries matches the number of occupied slots.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of these conditions is violated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entry = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&entry));
+    /// element.attach(&list);
+    /// list.check_invariants();
+    /// ```
+    pub fn check_invariants(&self) {
+        let data = &*self.data.lock();
+        let mut entries = BTreeSet::new();
+        let mut occupied = 0;
+        for idx in 0..data.members.index_len() {
+            let Some(member) = data.members.get_by_index(idx) else {
+                continue;
+            };
+            occupied += 1;
+            let by_id = data.members.get(&member.id);
+            assert!(
+                by_id.is_some_and(|m| ptr::eq(m, member)),
+                "the entry at index {idx} cannot be found by its id",
+            );
+            assert!(
+                entries.insert(Arc::as_ptr(&member.entry) as usize),
+                "the element of the entry at index {idx} has multiple entries",
+            );
+        }
+        assert_eq!(
+            occupied,
+            data.members.len(),
+            "the number of entries does not match the number of occupied slots",
+        );
+    }
+}
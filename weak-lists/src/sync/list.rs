@@ -1,16 +1,42 @@
 use {
-    crate::sync::{Iter, SyncWeakList, WeakListData},
-    alloc::sync::Arc,
-    core::fmt::{Debug, Formatter},
-    parking_lot::Mutex,
+    crate::{
+        error::Error,
+        id::EntryId,
+        sync::{
+            cascade, clock::read_clock, visibility::Visible, AttachEventKind, Difference,
+            EntryCell, EntryData, FrozenWeakList, Intersection, Iter, ListMutex, Member,
+            MemberExtra, OwnedIter, SendIter, SyncWeakList, SyncWeakListBuilder,
+            SyncWeakListElement, TargetSet, Union, Visibility, WeakListData,
+        },
+    },
+    alloc::{
+        boxed::Box,
+        sync::{Arc, Weak},
+        vec::Vec,
+    },
+    core::{
+        fmt::{Debug, Formatter},
+        mem,
+        ops::Range,
+        ptr,
+    },
+    parking_lot::MutexGuard,
+    stable_map::StableMap,
 };
 
-impl<T> SyncWeakList<T>
+impl<T, I> SyncWeakList<T, I>
 where
     T: ?Sized,
+    I: EntryId,
 {
     /// Removes all elements from the list.
     ///
+    /// The elements are detached as if by [detach](SyncWeakListElement::detach). Elements that
+    /// have requested to be reattached are an exception, see
+    /// [set_reattach_on_clear](SyncWeakListElement::set_reattach_on_clear).
+    ///
+    /// The dependents of this list are cleared as well. See [add_child](Self::add_child).
+    ///
     /// # Examples
     ///
     /// ```
@@ -26,8 +52,263 @@ where
     /// assert!(list.iter().next().is_none());
     /// ```
     pub fn clear(&self) {
+        cascade::clear(&self.data);
+    }
+
+    /// Removes all elements from the list and invokes `f` with their targets.
+    ///
+    /// This function behaves like [clear](Self::clear). After the list has been cleared,
+    /// `f` is invoked for each detached element whose target is still alive. The list
+    /// can be modified from within `f`.
+    ///
+    /// `f` is not invoked for the dependents of this list or for elements that have
+    /// requested to be reattached.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entry = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&entry));
+    /// element.attach(&list);
+    /// let mut cleared = vec![];
+    /// list.clear_notify(|e| cleared.push(*e));
+    /// assert_eq!(cleared, [1]);
+    /// assert!(element.id().is_none());
+    /// ```
+    pub fn clear_notify(&self, mut f: impl FnMut(Arc<T>)) {
+        for member in cascade::clear(&self.data) {
+            if let Some(target) = member.upgrade() {
+                f(target);
+            }
+        }
+    }
+
+    /// Detaches all elements that were attached to this list as members of the group.
+    ///
+    /// See [attach_with_group](crate::sync::SyncWeakListElement::attach_with_group).
+    pub fn detach_group(&self, group: u32) {
+        let ids: Vec<_> = {
+            let data = &*self.data.lock();
+            (0..data.members.index_len())
+                .filter_map(|idx| data.members.get_by_index(idx))
+                .filter(|member| member.group() == Some(group))
+                .map(|member| member.id)
+                .collect()
+        };
+        for id in ids {
+            self.detach_member(id);
+        }
+    }
+
+    /// Creates a new `Arc` with [Arc::new_cyclic] and attaches its element to this list.
+    ///
+    /// Elements that are attached while their target is still being constructed by
+    /// [Arc::new_cyclic] are skipped by iterators until the construction has completed.
+    /// This function instead attaches the element returned by `element` once the `Arc`
+    /// has been fully constructed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{Arc, Weak};
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// struct Client {
+    ///     element: SyncWeakListElement<Client>,
+    /// }
+    ///
+    /// let clients = SyncWeakList::default();
+    /// let client = clients.adopt_on_init(
+    ///     |slf| Client {
+    ///         element: SyncWeakListElement::new(slf.clone()),
+    ///     },
+    ///     |client| &client.element,
+    /// );
+    /// assert!(clients.iter().next().is_some());
+    /// ```
+    pub fn adopt_on_init<U>(
+        &self,
+        data_fn: impl FnOnce(&Weak<U>) -> U,
+        element: impl FnOnce(&U) -> &SyncWeakListElement<T, I>,
+    ) -> Arc<U> {
+        let u = Arc::new_cyclic(data_fn);
+        element(&u).attach(self);
+        u
+    }
+
+    /// Creates a new element for the target of `t` and attaches it to this list.
+    ///
+    /// This is a shorthand for creating the element with
+    /// [new_with_id_type](SyncWeakListElement::new_with_id_type) and attaching it with
+    /// [attach](SyncWeakListElement::attach). The entry is removed from the list when the returned element
+    /// is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::SyncWeakList;
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entry = Arc::new(1);
+    /// let element = list.attach_new(&entry);
+    /// assert_eq!(element.id(), Some(0));
+    /// assert_eq!(list.iter().next(), Some(entry));
+    /// ```
+    pub fn attach_new(&self, t: &Arc<T>) -> SyncWeakListElement<T, I> {
+        let element = SyncWeakListElement::new_with_id_type(Arc::downgrade(t));
+        element.attach(self);
+        element
+    }
+
+    /// Replaces the element of an entry.
+    ///
+    /// The element is detached from its current list and takes over the position of the
+    /// entry with the given id. The element previously stored in the entry is detached.
+    ///
+    /// Only the id and the position of the entry carry over. Everything else is reset as
+    /// if the element had been attached with [attach](SyncWeakListElement::attach): the
+    /// entry is not one-shot, belongs to no group, has epoch 0, is visible to all
+    /// iterators, and its invocation statistics start from zero.
+    ///
+    /// Returns [Error::NotFound] if the list does not contain an entry with the given id.
+    /// The element is left detached in this case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1), Arc::new(2)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// elements[0].attach(&list);
+    /// elements[1].attach(&list);
+    /// let id = elements[0].id().unwrap();
+    /// assert_eq!(list.replace(id, &elements[2]), Ok(()));
+    /// assert_eq!(elements[0].id(), None);
+    /// assert_eq!(elements[2].id(), Some(id));
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [2, 1]);
+    /// ```
+    pub fn replace(&self, id: I, element: &SyncWeakListElement<T, I>) -> Result<(), Error> {
+        let old_entry = self.data.lock().members.get(&id).map(|m| m.entry.clone());
+        let Some(old_entry) = old_entry else {
+            element.detach();
+            return Err(Error::NotFound);
+        };
+        if Arc::ptr_eq(&old_entry, &element.data) {
+            return Ok(());
+        }
+        // Elements are always locked before lists. Two elements are locked in the order
+        // of their addresses.
+        let (mut old, mut new);
+        if Arc::as_ptr(&old_entry) < Arc::as_ptr(&element.data) {
+            old = old_entry.lock();
+            new = element.data.lock();
+        } else {
+            new = element.data.lock();
+            old = old_entry.lock();
+        }
+        element.data.detach(&mut new);
+        if old.id != id || !ptr::eq(old_entry.owner_ptr(&old), Arc::as_ptr(&self.data)) {
+            // The old element was detached while it was unlocked.
+            return Err(Error::NotFound);
+        }
         let data = &mut *self.data.lock();
-        data.members.clear();
+        let visible_from = data.visible_from(Visible::Unspecified);
+        let Some(member) = data.members.get_mut(&id) else {
+            return Err(Error::NotFound);
+        };
+        member.t = element.t.clone();
+        member.extra = MemberExtra {
+            keepalive: element.keepalive.clone(),
+            visible_from,
+            ..Default::default()
+        }
+        .boxed();
+        if member.dead_since.take().is_some() {
+            data.known_dead -= 1;
+        }
+        member.entry = element.data.clone();
+        member.reattach = new.reattach;
+        member.seq = data.next_seq;
+        data.next_seq += 1;
+        member.enabled = new.enabled;
+        old_entry.take_owner(&mut old);
+        element.data.set_owner(&mut new, &self.data);
+        new.id = id;
+        data.emit(id, AttachEventKind::Detached);
+        data.emit(id, AttachEventKind::Attached);
+        data.changed();
+        Ok(())
+    }
+
+    /// Returns the target of the entry with the given key.
+    ///
+    /// The key of an entry is its id. See [attach_keyed](SyncWeakListElement::attach_keyed).
+    ///
+    /// Returns `None` if the list does not contain an entry with this key or if the target
+    /// of the entry has been dropped.
+    pub fn get_key(&self, key: I) -> Option<Arc<T>> {
+        self.data.lock().members.get(&key)?.upgrade()
+    }
+
+    /// Returns whether the list contains an entry with the given id.
+    ///
+    /// This function does not check whether the target of the entry is still alive. Ids
+    /// are allocated in increasing order and are only reused once the id space has been
+    /// exhausted. An id obtained from [id](SyncWeakListElement::id) can therefore be used to check
+    /// whether the element is still attached without access to the element itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::SyncWeakList;
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entry = Arc::new(1);
+    /// let element = list.attach_new(&entry);
+    /// let id = element.id().unwrap();
+    /// assert!(list.contains_id(id));
+    /// drop(element);
+    /// assert!(!list.contains_id(id));
+    /// ```
+    pub fn contains_id(&self, id: I) -> bool {
+        self.data.lock().members.contains_key(&id)
+    }
+
+    /// Detaches the entry with the given key.
+    ///
+    /// The key of an entry is its id. See [attach_keyed](SyncWeakListElement::attach_keyed).
+    ///
+    /// Returns [Error::NotFound] if the list does not contain an entry with this key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{Error, SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entry = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&entry));
+    /// assert_eq!(element.attach_keyed(&list, 3), Ok(()));
+    /// assert_eq!(list.detach_key(3), Ok(()));
+    /// assert_eq!(list.detach_key(3), Err(Error::NotFound));
+    /// assert_eq!(element.id(), None);
+    /// assert_eq!(list.get_key(3), None);
+    /// ```
+    pub fn detach_key(&self, key: I) -> Result<(), Error> {
+        match self.detach_member(key) {
+            true => Ok(()),
+            false => Err(Error::NotFound),
+        }
     }
 
     /// Creates an iterator over the entries of the list.
@@ -36,15 +317,745 @@ where
     /// was part of the list when this iterator was created, and if the element was not
     /// removed during the iteration, then the element will be returned exactly once by
     /// this iterator.
-    pub fn iter(&self) -> Iter<'_, T> {
+    ///
+    /// The entries are yielded in the order of their slots. Entries that are attached
+    /// later might reuse the slots of detached entries and are therefore not necessarily
+    /// yielded after the existing entries. However, the relative order of the entries
+    /// that remain in the list never changes. In particular, it is preserved when the list
+    /// is compacted. See [raw](crate::sync::raw) for details.
+    ///
+    /// The iteration order is therefore not necessarily the order in which the entries were
+    /// attached. Unless the ids have wrapped around, the ids of the entries reflect that
+    /// order instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries: Vec<_> = (0..8).map(Arc::new).collect();
+    /// let elements: Vec<_> = entries
+    ///     .iter()
+    ///     .map(|e| SyncWeakListElement::new(Arc::downgrade(e)))
+    ///     .collect();
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// for idx in [0, 2, 4, 5, 6] {
+    ///     elements[idx].detach();
+    /// }
+    /// // Creating the iterator compacts the list.
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [1, 3, 7]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T, I> {
+        let (iter, visibility) = self
+            .start_iteration()
+            .unwrap_or_else(|| self.start_rejected_iteration());
+        Iter {
+            iter,
+            data: &self.data,
+            skipped: 0,
+            peeked: None,
+            visibility,
+            excluded: None,
+        }
+    }
+
+    /// Creates an iterator over the entries of the list unless too many iterators exist.
+    ///
+    /// Returns [Error::TooManyIterators] if the number of iterators over the list has
+    /// reached the limit set with
+    /// [set_max_concurrent_iterations](Self::set_max_concurrent_iterations).
+    /// Otherwise, this function behaves like [iter](Self::iter).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use weak_lists::SyncWeakList;
+    ///
+    /// let list = SyncWeakList::<u32>::default();
+    /// list.set_max_concurrent_iterations(Some(1));
+    /// let iter = list.try_iter();
+    /// assert!(iter.is_ok());
+    /// assert!(list.try_iter().is_err());
+    /// drop(iter);
+    /// assert!(list.try_iter().is_ok());
+    /// ```
+    pub fn try_iter(&self) -> Result<Iter<'_, T, I>, Error> {
+        let (iter, visibility) = self.start_iteration().ok_or(Error::TooManyIterators)?;
+        Ok(Iter {
+            iter,
+            data: &self.data,
+            skipped: 0,
+            peeked: None,
+            visibility,
+            excluded: None,
+        })
+    }
+
+    /// Creates a boxed iterator over the entries of the list.
+    ///
+    /// This function behaves like [iter](Self::iter). It is useful for trait objects that
+    /// cannot name the type of the iterator. The iterator can be sent to other threads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{sync::Arc, thread};
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entry = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&entry));
+    /// element.attach(&list);
+    /// let iter = list.boxed_iter();
+    /// let sum = thread::scope(|s| s.spawn(|| iter.map(|e| *e).sum::<i32>()).join().unwrap());
+    /// assert_eq!(sum, 1);
+    /// ```
+    pub fn boxed_iter(&self) -> Box<dyn Iterator<Item = Arc<T>> + Send + '_>
+    where
+        T: Send + Sync,
+    {
+        Box::new(self.iter())
+    }
+
+    /// Creates an immutable snapshot of the live entries of the list.
+    ///
+    /// The snapshot holds weak references to the entries. It is cheap to clone and can be
+    /// iterated over without acquiring any locks, for example, by sending it to worker
+    /// threads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{sync::Arc, thread};
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entry = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&entry));
+    /// element.attach(&list);
+    /// let frozen = list.freeze();
+    /// element.detach();
+    /// assert!(list.iter().next().is_none());
+    /// let sum = thread::spawn(move || frozen.iter().map(|e| *e).sum::<i32>());
+    /// assert_eq!(sum.join().unwrap(), 1);
+    /// ```
+    pub fn freeze(&self) -> FrozenWeakList<T> {
+        let data = &*self.data.lock();
+        let mut members = Vec::with_capacity(data.members.len());
+        for idx in 0..data.members.index_len() {
+            if let Some(member) = data.members.get_by_index(idx) {
+                if member.is_alive() {
+                    members.push(member.t.clone());
+                }
+            }
+        }
+        FrozenWeakList {
+            members: members.into(),
+        }
+    }
+
+    /// Creates an iterator over a snapshot of the live entries of the list.
+    ///
+    /// This is a shorthand for calling [freeze](Self::freeze) and converting the snapshot
+    /// into an iterator. The iterator owns the snapshot and can be sent to other threads
+    /// if `T` is `Send` and `Sync`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{sync::Arc, thread};
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(1), Arc::new(2)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let iter = list.send_iter();
+    /// let sum = thread::spawn(move || iter.map(|e| *e).sum::<i32>());
+    /// assert_eq!(sum.join().unwrap(), 3);
+    /// ```
+    pub fn send_iter(&self) -> SendIter<T> {
+        self.freeze().into_iter()
+    }
+
+    /// Returns whether an iterator over this list currently exists.
+    ///
+    /// This can be used by callbacks to detect that they are being invoked from within an
+    /// iteration over this list.
+    ///
+    /// Iterators created by other threads are taken into account as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use weak_lists::SyncWeakList;
+    ///
+    /// let list = SyncWeakList::<u32>::default();
+    /// assert!(!list.is_iterating());
+    /// let iter = list.iter();
+    /// assert!(list.is_iterating());
+    /// drop(iter);
+    /// assert!(!list.is_iterating());
+    /// ```
+    pub fn is_iterating(&self) -> bool {
+        let data = &*self.data.lock();
+        data.active_iterators > 0
+    }
+
+    /// Limits the number of iterators that can exist at the same time.
+    ///
+    /// Once `max` iterators over the list exist, [iter](Self::iter) and
+    /// [iter_owned](Self::iter_owned) return iterators that do not yield any entries and
+    /// [try_iter](Self::try_iter) returns [Error::TooManyIterators]. The iterators of the
+    /// dispatch helpers such as [for_each](Self::for_each) are included. This protects
+    /// against callbacks that recursively trigger the dispatch that invoked them.
+    /// Iterators that already exist are not affected, even if they are
+    /// [reset](crate::sync::Iter::reset).
+    ///
+    /// `None` removes the limit. The default is `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::SyncWeakList;
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entry = Arc::new(1);
+    /// let _element = list.attach_new(&entry);
+    /// list.set_max_concurrent_iterations(Some(2));
+    /// let mut depth = 0;
+    /// fn dispatch(list: &SyncWeakList<i32>, depth: &mut usize) {
+    ///     list.for_each(|_| {
+    ///         *depth += 1;
+    ///         dispatch(list, depth);
+    ///     });
+    /// }
+    /// dispatch(&list, &mut depth);
+    /// assert_eq!(depth, 2);
+    /// ```
+    pub fn set_max_concurrent_iterations(&self, max: Option<usize>) {
         let data = &mut *self.data.lock();
+        data.max_concurrent_iterations = max;
+    }
+
+    /// Returns an estimate of the number of bytes allocated by the list.
+    ///
+    /// The estimate is based on the capacity of the internal storage of the list and on
+    /// the state that entries allocate when they use features such as groups or
+    /// keepalive objects. It does not include the size of this object, the elements,
+    /// their targets, or the state of active iterators. This function visits every entry
+    /// of the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entry = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&entry));
+    /// element.attach(&list);
+    /// assert!(list.memory_usage() > 0);
+    /// ```
+    pub fn memory_usage(&self) -> usize {
+        self.data.lock().memory_usage()
+    }
+
+    /// Creates an iterator over the entries of the list that does not borrow the list.
+    ///
+    /// This iterator provides the same guarantees as [iter](Self::iter). If the list is
+    /// dropped while the iterator exists, the iterator no longer yields any entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let mut iter = list.iter_owned();
+    /// assert_eq!(iter.next(), Some(entries[0].clone()));
+    /// drop(list);
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter_owned(&self) -> OwnedIter<T, I> {
+        let (iter, visibility) = self
+            .start_iteration()
+            .unwrap_or_else(|| self.start_rejected_iteration());
+        OwnedIter {
+            iter,
+            visibility,
+            data: Arc::downgrade(&self.data),
+        }
+    }
+
+    /// Creates an iterator over the entries of the list that are also entries of `other`.
+    ///
+    /// Entries are compared by the address of their targets. The entries of `other` are
+    /// determined when this function is called. Otherwise, the iterator provides the same
+    /// guarantees as [iter](Self::iter).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let subscribed = SyncWeakList::default();
+    /// let authorized = SyncWeakList::default();
+    /// let a = Arc::new(1);
+    /// let b = Arc::new(2);
+    /// let a1 = SyncWeakListElement::new(Arc::downgrade(&a));
+    /// let b1 = SyncWeakListElement::new(Arc::downgrade(&b));
+    /// let b2 = SyncWeakListElement::new(Arc::downgrade(&b));
+    /// a1.attach(&subscribed);
+    /// b1.attach(&subscribed);
+    /// b2.attach(&authorized);
+    /// let both: Vec<_> = subscribed.intersect_iter(&authorized).collect();
+    /// assert_eq!(both, [b]);
+    /// ```
+    pub fn intersect_iter(&self, other: &SyncWeakList<T, I>) -> Intersection<'_, T, I> {
+        Intersection {
+            iter: self.iter(),
+            other: TargetSet::of(other),
+        }
+    }
+
+    /// Creates an iterator over the entries of the list that are not entries of `other`.
+    ///
+    /// Entries are compared by the address of their targets. The entries of `other` are
+    /// determined when this function is called. Otherwise, the iterator provides the same
+    /// guarantees as [iter](Self::iter).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let subscribed = SyncWeakList::default();
+    /// let banned = SyncWeakList::default();
+    /// let a = Arc::new(1);
+    /// let b = Arc::new(2);
+    /// let a1 = SyncWeakListElement::new(Arc::downgrade(&a));
+    /// let b1 = SyncWeakListElement::new(Arc::downgrade(&b));
+    /// let b2 = SyncWeakListElement::new(Arc::downgrade(&b));
+    /// a1.attach(&subscribed);
+    /// b1.attach(&subscribed);
+    /// b2.attach(&banned);
+    /// let allowed: Vec<_> = subscribed.difference_iter(&banned).collect();
+    /// assert_eq!(allowed, [a]);
+    /// ```
+    pub fn difference_iter(&self, other: &SyncWeakList<T, I>) -> Difference<'_, T, I> {
+        Difference {
+            iter: self.iter(),
+            other: TargetSet::of(other),
+        }
+    }
+
+    /// Creates an iterator over the entries of the list followed by the entries of
+    /// `other`.
+    ///
+    /// Entries are compared by the address of their targets and each target is returned
+    /// at most once. Otherwise, the iterator provides the same guarantees as
+    /// [iter](Self::iter) for both lists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let users = SyncWeakList::default();
+    /// let admins = SyncWeakList::default();
+    /// let a = Arc::new(1);
+    /// let b = Arc::new(2);
+    /// let a1 = SyncWeakListElement::new(Arc::downgrade(&a));
+    /// let b1 = SyncWeakListElement::new(Arc::downgrade(&b));
+    /// let b2 = SyncWeakListElement::new(Arc::downgrade(&b));
+    /// a1.attach(&users);
+    /// b1.attach(&users);
+    /// b2.attach(&admins);
+    /// let everyone: Vec<_> = users.union_iter(&admins).collect();
+    /// assert_eq!(everyone, [a, b]);
+    /// ```
+    pub fn union_iter<'a>(&'a self, other: &'a SyncWeakList<T, I>) -> Union<'a, T, I> {
+        Union {
+            first: self.iter(),
+            second: other.iter(),
+            seen: Default::default(),
+        }
+    }
+
+    /// Creates a new list containing the live entries of this list.
+    ///
+    /// The new list is independent of this list. Its entries have the same targets, ids,
+    /// groups, and order as the entries of this list, but they are not associated with any
+    /// [SyncWeakListElement]. Attaching and detaching elements does not affect the other list.
+    ///
+    /// Since there are no elements that detach them, the entries of the new list remain
+    /// in the list until they are removed by id, for example with
+    /// [detach_key](Self::detach_key), or with [drain](Self::drain) or
+    /// [clear](Self::clear).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let copy = list.duplicate();
+    /// elements[0].detach();
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [1]);
+    /// assert_eq!(copy.iter().map(|e| *e).collect::<Vec<_>>(), [0, 1]);
+    /// copy.detach_key(elements[1].id().unwrap());
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [1]);
+    /// assert_eq!(copy.iter().map(|e| *e).collect::<Vec<_>>(), [0]);
+    /// ```
+    pub fn duplicate(&self) -> SyncWeakList<T, I> {
+        let list = SyncWeakListBuilder::default().build_with_id_type();
+        let data = &*self.data.lock();
+        // The new list is not yet shared with any other thread.
+        let mut list_data = list.data.lock();
+        list_data.next_id = data.next_id;
+        list_data.next_seq = data.next_seq;
+        for idx in 0..data.members.index_len() {
+            let Some(member) = data.members.get_by_index(idx) else {
+                continue;
+            };
+            if !member.is_alive() {
+                continue;
+            }
+            list_data.members.insert(
+                member.id,
+                Member {
+                    id: member.id,
+                    t: member.t.clone(),
+                    entry: Arc::new(EntryCell::new_attached(
+                        EntryData {
+                            id: member.id,
+                            reattach: false,
+                            deferred: false,
+                            enabled: member.enabled,
+                        },
+                        &list.data,
+                    )),
+                    dead_since: None,
+                    reattach: false,
+                    enabled: member.enabled,
+                    seq: member.seq,
+                    extra: member.extra.as_deref().and_then(|e| {
+                        MemberExtra {
+                            group: e.group,
+                            keepalive: e.keepalive.clone(),
+                            once: e.once,
+                            epoch: e.epoch,
+                            ..Default::default()
+                        }
+                        .boxed()
+                    }),
+                },
+            );
+        }
+        drop(list_data);
+        list
+    }
+
+    /// Moves the entries for which `f` returns `true` into a new list.
+    ///
+    /// The elements of the moved entries are attached to the new list.
+    ///
+    /// `f` is invoked for the entries of the list as if by [iter](Self::iter). It may
+    /// modify the list.
+    ///
+    /// The new list has the same settings as this list, for example, its clock, its limits,
+    /// and whether it compacts itself automatically.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let clients = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1), Arc::new(2)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&clients);
+    /// }
+    /// let idle = clients.split_off(|e| **e % 2 == 0);
+    /// assert_eq!(clients.iter().map(|e| *e).collect::<Vec<_>>(), [1]);
+    /// assert_eq!(idle.iter().map(|e| *e).collect::<Vec<_>>(), [0, 2]);
+    /// elements[0].detach();
+    /// assert_eq!(idle.iter().map(|e| *e).collect::<Vec<_>>(), [2]);
+    /// ```
+    pub fn split_off(&self, mut f: impl FnMut(&Arc<T>) -> bool) -> SyncWeakList<T, I> {
+        let list = SyncWeakListBuilder::default().build_with_id_type();
+        // The new list is not yet shared with any other thread.
+        list.data.lock().copy_settings(&self.data.lock());
+        let mut iter = self.iter();
+        while let Some((id, t)) = iter.next_with_id() {
+            if f(&t) {
+                self.move_member(id, &list);
+            }
+        }
+        list
+    }
+
+    /// Detaches the member with the given id from this list.
+    ///
+    /// Returns `false` if the list does not contain such a member.
+    pub(super) fn detach_member(&self, id: I) -> bool {
+        let Some(cell) = self.data.lock().members.get(&id).map(|m| m.entry.clone()) else {
+            return false;
+        };
+        // Elements are always locked before lists.
+        let entry = &mut *cell.lock();
+        if entry.id != id || !ptr::eq(cell.owner_ptr(entry), Arc::as_ptr(&self.data)) {
+            // The element was detached while it was unlocked.
+            return false;
+        }
+        let data = &mut *self.data.lock();
+        data.remove_member(id);
+        cell.take_owner(entry);
+        data.changed();
+        true
+    }
+
+    /// Moves the member with the given id to another list.
+    ///
+    /// `to` must not be this list.
+    fn move_member(&self, id: I, to: &SyncWeakList<T, I>) {
+        let Some(cell) = self.data.lock().members.get(&id).map(|m| m.entry.clone()) else {
+            return;
+        };
+        // Elements are always locked before lists.
+        let entry = &mut *cell.lock();
+        if entry.id != id || !ptr::eq(cell.owner_ptr(entry), Arc::as_ptr(&self.data)) {
+            // The element was detached while it was unlocked.
+            return;
+        }
+        let data = &mut *self.data.lock();
+        let Some(mut member) = data.remove_member(id) else {
+            return;
+        };
+        let to_data = &mut *to.data.lock();
+        // The other list never contains more members than have been removed from this
+        // list. Therefore an id is always available.
+        let Ok(new_id) = to_data.allocate_id() else {
+            unreachable!();
+        };
+        member.id = new_id;
+        member.dead_since = None;
+        entry.id = member.id;
+        cell.set_owner(entry, &to.data);
+        to_data.insert_member(member);
+        data.changed();
+        to_data.changed();
+    }
+
+    /// Registers a new iterator and returns the range of indices it has to visit.
+    ///
+    /// Returns `None` without registering the iterator if the limit set with
+    /// [set_max_concurrent_iterations](Self::set_max_concurrent_iterations) has been
+    /// reached.
+    fn start_iteration(&self) -> Option<(Range<usize>, Visibility<I>)> {
+        let now = read_clock(&self.data);
+        let data = &mut *self.data.lock();
+        if let Some(max) = data.max_concurrent_iterations {
+            if data.active_iterators >= max {
+                return None;
+            }
+        }
         if data.active_iterators == 0 {
-            data.members.compact();
+            data.coalesced.clear();
+            if data.auto_compact {
+                data.compact();
+            }
         }
+        let visibility = data.start_visibility(now);
         data.active_iterators += 1;
-        Iter {
-            iter: 0..data.members.index_len(),
-            data: &self.data,
+        Some((0..data.members.index_len(), visibility))
+    }
+
+    /// Registers an iterator that was rejected by [start_iteration](Self::start_iteration).
+    ///
+    /// The iterator does not visit any indices.
+    fn start_rejected_iteration(&self) -> (Range<usize>, Visibility<I>) {
+        let data = &mut *self.data.lock();
+        data.active_iterators += 1;
+        (0..0, Visibility::finished())
+    }
+}
+
+impl<T, I> WeakListData<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns an estimate of the number of bytes allocated by this object.
+    pub(super) fn memory_usage(&self) -> usize {
+        let member = mem::size_of::<I>() + mem::size_of::<usize>() + mem::size_of::<Member<T, I>>();
+        let child = mem::size_of::<Weak<ListMutex<T, I>>>();
+        self.members.capacity() * member
+            + self.pending.capacity() * mem::size_of::<Member<T, I>>()
+            + self.late.capacity() * mem::size_of::<(I, usize)>()
+            + self.coalesced.capacity() * mem::size_of::<u64>()
+            + self.children.capacity() * child
+            + self.extra_count() * mem::size_of::<MemberExtra>()
+    }
+
+    /// Returns the number of members, including pending members, that have allocated
+    /// extra state.
+    fn extra_count(&self) -> usize {
+        let members =
+            (0..self.members.index_len()).filter_map(|idx| self.members.get_by_index(idx));
+        members
+            .chain(&self.pending)
+            .filter(|member| member.extra.is_some())
+            .count()
+    }
+
+    /// Copies the settings of another list into this list.
+    ///
+    /// This includes the settings of the builder and of the setters of the list, but not
+    /// the state of the list.
+    fn copy_settings(&mut self, other: &Self) {
+        self.auto_compact = other.auto_compact;
+        self.clock = other.clock.clone();
+        self.track_invocations = other.track_invocations;
+        self.max_concurrent_iterations = other.max_concurrent_iterations;
+        self.max_entries_per_target = other.max_entries_per_target;
+        self.fair = other.fair;
+    }
+
+    /// Releases the lock of the list, fairly if [fair](SyncWeakListBuilder::fair) is set.
+    pub(super) fn unlock(data: MutexGuard<'_, Self>) {
+        if data.fair {
+            MutexGuard::unlock_fair(data);
+        }
+    }
+
+    /// Locks the list and ensures that a member can be inserted without growing the member
+    /// map.
+    ///
+    /// Growing the map while the list is locked would stall all other threads, including
+    /// iterators, while the allocator runs. Instead, a larger map is allocated while the list
+    /// is unlocked and the members are moved into it once the list has been locked again.
+    /// The previous map is freed after the list has been unlocked. Since moving the members
+    /// compacts the map, this is only possible if the list may be compacted. Otherwise, the
+    /// map grows in place when the member is inserted.
+    pub(super) fn lock_for_insert(list: &ListMutex<T, I>) -> MutexGuard<'_, Self> {
+        let mut data = list.lock();
+        loop {
+            let capacity = data.members.capacity();
+            if data.members.len() < capacity || data.active_iterators > 0 || !data.auto_compact {
+                return data;
+            }
+            Self::unlock(data);
+            let mut members = StableMap::with_capacity((2 * capacity).max(4));
+            data = list.lock();
+            let unchanged = data.members.capacity() == capacity;
+            if unchanged && data.active_iterators == 0 && data.auto_compact {
+                data.move_members(&mut members);
+            }
+            Self::unlock(data);
+            drop(members);
+            data = list.lock();
+        }
+    }
+
+    /// Moves the members into another map and leaves the previous map in `members`.
+    ///
+    /// This compacts the member map and must only be called if there are no active
+    /// iterators.
+    fn move_members(&mut self, members: &mut StableMap<I, Member<T, I>>) {
+        let len = self.members.index_len();
+        let cursor = (0..self.cursor.min(len))
+            .filter(|&idx| self.members.get_by_index(idx).is_some())
+            .count();
+        mem::swap(&mut self.members, members);
+        for idx in 0..len {
+            let Some(id) = members.get_by_index(idx).map(|member| member.id) else {
+                continue;
+            };
+            if let Some(member) = members.remove(&id) {
+                self.members.insert(id, member);
+            }
+        }
+        if self.members.index_len() != len {
+            self.cursor = cursor;
+            self.compaction_epoch += 1;
+        }
+    }
+
+    /// Inserts a member and reports it to the event channel.
+    pub(super) fn insert_member(&mut self, member: Member<T, I>) {
+        let id = member.id;
+        self.members.insert(id, member);
+        self.emit(id, AttachEventKind::Attached);
+    }
+
+    /// Removes a member, updates the number of dead members, and reports it to the event
+    /// channel.
+    pub(super) fn remove_member(&mut self, id: I) -> Option<Member<T, I>> {
+        let member = self.members.remove(&id)?;
+        if member.dead_since.is_some() {
+            self.known_dead -= 1;
+        }
+        self.emit(id, AttachEventKind::Detached);
+        Some(member)
+    }
+
+    /// Allocates an id for a new member.
+    ///
+    /// The id is not used by any member of the list. Returns [Error::IdsExhausted] if
+    /// there is no such id.
+    pub(super) fn allocate_id(&mut self) -> Result<I, Error> {
+        let first = self.next_id;
+        loop {
+            let id = self.next_id;
+            self.next_id = id.wrapping_next();
+            if !self.members.contains_key(&id) {
+                return Ok(id);
+            }
+            if self.next_id == first {
+                return Err(Error::IdsExhausted);
+            }
+        }
+    }
+
+    /// Compacts the member map.
+    ///
+    /// This must only be called if there are no active iterators.
+    pub(super) fn compact(&mut self) {
+        let len = self.members.index_len();
+        let cursor = (0..self.cursor.min(len))
+            .filter(|&idx| self.members.get_by_index(idx).is_some())
+            .count();
+        self.members.compact();
+        if self.members.index_len() != len {
+            self.cursor = cursor;
+            self.compaction_epoch += 1;
         }
     }
 }
@@ -54,31 +1065,27 @@ where
     T: ?Sized,
 {
     fn default() -> Self {
-        Self {
-            data: Arc::new(Mutex::new(WeakListData {
-                next_id: 0,
-                active_iterators: 0,
-                members: Default::default(),
-            })),
-        }
+        SyncWeakListBuilder::default().build()
     }
 }
 
-impl<'a, T> IntoIterator for &'a SyncWeakList<T>
+impl<'a, T, I> IntoIterator for &'a SyncWeakList<T, I>
 where
     T: ?Sized,
+    I: EntryId,
 {
     type Item = Arc<T>;
-    type IntoIter = Iter<'a, T>;
+    type IntoIter = Iter<'a, T, I>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.iter()
     }
 }
 
-impl<T> Debug for SyncWeakList<T>
+impl<T, I> Debug for SyncWeakList<T, I>
 where
     T: ?Sized,
+    I: EntryId,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("SyncWeakList")
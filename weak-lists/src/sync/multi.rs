@@ -0,0 +1,94 @@
+use {
+    crate::{
+        id::MembershipId,
+        sync::{Memberships, SyncMultiWeakListElement, SyncWeakList, SyncWeakListElement},
+    },
+    alloc::{sync::Weak, vec::Vec},
+    core::{
+        fmt::{Debug, Formatter},
+        mem,
+    },
+    parking_lot::Mutex,
+};
+
+impl<T> SyncMultiWeakListElement<T>
+where
+    T: ?Sized,
+{
+    /// Creates a new list element.
+    ///
+    /// See [SyncWeakListElement::new].
+    pub fn new(t: Weak<T>) -> Self {
+        Self {
+            t,
+            memberships: Mutex::new(Memberships {
+                next_id: 0,
+                elements: Vec::new(),
+            }),
+        }
+    }
+
+    /// Attaches the element to a list and returns the id of the new membership.
+    ///
+    /// The existing memberships of the element are not affected. Memberships that have
+    /// ended because their list has been dropped or cleared are forgotten.
+    pub fn attach(&self, to: &SyncWeakList<T>) -> MembershipId {
+        let element = SyncWeakListElement::new(self.t.clone());
+        element.attach(to);
+        let (id, stale) = {
+            let memberships = &mut *self.memberships.lock();
+            let id = MembershipId(memberships.next_id);
+            memberships.next_id += 1;
+            let (live, stale): (Vec<_>, Vec<_>) = mem::take(&mut memberships.elements)
+                .into_iter()
+                .partition(|(_, e)| e.is_attached());
+            memberships.elements = live;
+            memberships.elements.push((id, element));
+            (id, stale)
+        };
+        drop(stale);
+        id
+    }
+
+    /// Detaches the element from the list of a membership.
+    ///
+    /// Returns whether the element had a membership with this id.
+    pub fn detach(&self, id: MembershipId) -> bool {
+        let element = {
+            let elements = &mut self.memberships.lock().elements;
+            let Some(idx) = elements.iter().position(|(m, _)| *m == id) else {
+                return false;
+            };
+            elements.swap_remove(idx)
+        };
+        drop(element);
+        true
+    }
+
+    /// Detaches the element from all of its lists.
+    pub fn detach_all(&self) {
+        let elements = mem::take(&mut self.memberships.lock().elements);
+        drop(elements);
+    }
+
+    /// Returns whether the element is attached to a list through the membership.
+    pub fn is_attached(&self, id: MembershipId) -> bool {
+        self.memberships
+            .lock()
+            .elements
+            .iter()
+            .any(|(m, e)| *m == id && e.is_attached())
+    }
+}
+
+impl<T> Debug for SyncMultiWeakListElement<T>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let memberships = self.memberships.lock();
+        f.debug_map()
+            .entries(memberships.elements.iter().map(|(id, e)| (id, e)))
+            .finish()
+    }
+}
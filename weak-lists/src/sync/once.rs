@@ -0,0 +1,44 @@
+use crate::{
+    id::EntryId,
+    sync::{element::AttachOptions, SyncWeakList, SyncWeakListElement},
+};
+
+impl<T, I> SyncWeakListElement<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Attaches the element to a list until its entry has been yielded once.
+    ///
+    /// This function behaves like [attach](Self::attach) except that the element is
+    /// detached as soon as an iterator over the list yields its entry. This includes the
+    /// iterators used by the dispatch helpers such as [for_each](SyncWeakList::for_each).
+    /// Even if multiple threads iterate over the list concurrently, the entry is yielded
+    /// by at most one iterator. Entries whose targets have been dropped are not yielded
+    /// and therefore remain attached.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// elements[0].attach_once(&list);
+    /// elements[1].attach(&list);
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [0, 1]);
+    /// assert_eq!(elements[0].id(), None);
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [1]);
+    /// ```
+    pub fn attach_once(&self, to: &SyncWeakList<T, I>) {
+        let _ = self.attach_with_options(
+            to,
+            AttachOptions {
+                once: true,
+                ..Default::default()
+            },
+        );
+    }
+}
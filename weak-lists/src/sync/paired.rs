@@ -0,0 +1,234 @@
+use {
+    crate::{
+        error::Error,
+        sync::{
+            visibility::Visible, EntryCell, EntryData, ListMutex, Member, MemberExtra,
+            SyncPairedWeakListElement, SyncWeakList, SyncWeakListElement, WeakListData,
+        },
+    },
+    alloc::sync::{Arc, Weak},
+    core::fmt::{Debug, Formatter},
+    parking_lot::MutexGuard,
+};
+
+impl<T> SyncPairedWeakListElement<T>
+where
+    T: ?Sized,
+{
+    /// Creates a new list element.
+    ///
+    /// See [SyncWeakListElement::new].
+    pub fn new(t: Weak<T>) -> Self {
+        Self {
+            global: SyncWeakListElement::new(t.clone()),
+            parent: SyncWeakListElement::new(t),
+        }
+    }
+
+    /// Attaches the element to two lists.
+    ///
+    /// If this element was previously attached to lists, it is automatically detached
+    /// from those lists. `global` and `parent` may be the same list, in which case the
+    /// element is contained in the list twice.
+    ///
+    /// If the element cannot be attached to one of the lists, for example because the
+    /// list has been [closed](SyncWeakList::close), it is attached to neither. See
+    /// [try_attach](Self::try_attach).
+    pub fn attach(&self, global: &SyncWeakList<T>, parent: &SyncWeakList<T>) {
+        let _ = self.try_attach(global, parent);
+    }
+
+    /// Attaches the element to two lists and returns whether it was attached.
+    ///
+    /// This function behaves like [attach](Self::attach). If the element cannot be
+    /// attached to one of the lists, it is left detached from both lists and an error is
+    /// returned:
+    ///
+    /// - [Error::Closed] if either list has been [closed](SyncWeakList::close).
+    /// - [Error::TargetLimitReached] if attaching the element would exceed the
+    ///   [max_entries_per_target](crate::sync::SyncWeakListBuilder::max_entries_per_target)
+    ///   limit of either list.
+    /// - [Error::IdsExhausted] if all ids of either list are in use.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{
+    ///     sync::{SyncPairedWeakListElement, SyncWeakListBuilder},
+    ///     Error, SyncWeakList,
+    /// };
+    ///
+    /// let global = SyncWeakList::default();
+    /// let parent = SyncWeakListBuilder::default().max_entries_per_target(1).build();
+    /// let entry = Arc::new(1);
+    /// let elements = [(); 2].map(|_| SyncPairedWeakListElement::new(Arc::downgrade(&entry)));
+    /// assert_eq!(elements[0].try_attach(&global, &parent), Ok(()));
+    /// let res = elements[1].try_attach(&global, &parent);
+    /// assert_eq!(res, Err(Error::TargetLimitReached));
+    /// assert_eq!(global.iter().count(), 1);
+    /// ```
+    pub fn try_attach(
+        &self,
+        global: &SyncWeakList<T>,
+        parent: &SyncWeakList<T>,
+    ) -> Result<(), Error> {
+        // Elements are always locked before lists.
+        let (global_entry, parent_entry) =
+            &mut lock_both(&*self.global.data, &*self.parent.data, EntryCell::lock);
+        self.global.data.detach(global_entry);
+        self.parent.data.detach(parent_entry);
+        let t = &self.global.t;
+        if Arc::ptr_eq(&global.data, &parent.data) {
+            let data = &mut *global.data.lock();
+            if data.closed {
+                return Err(Error::Closed);
+            }
+            if data.target_budget(t) < 2 {
+                return Err(Error::TargetLimitReached);
+            }
+            insert(&self.global, global_entry, global, data)?;
+            if let Err(e) = insert(&self.parent, parent_entry, parent, data) {
+                remove(&self.global, global_entry, data);
+                return Err(e);
+            }
+            data.changed();
+        } else {
+            let (global_data, parent_data) =
+                &mut lock_both(&*global.data, &*parent.data, ListMutex::lock);
+            if global_data.closed || parent_data.closed {
+                return Err(Error::Closed);
+            }
+            if global_data.target_budget(t) == 0 || parent_data.target_budget(t) == 0 {
+                return Err(Error::TargetLimitReached);
+            }
+            insert(&self.global, global_entry, global, global_data)?;
+            if let Err(e) = insert(&self.parent, parent_entry, parent, parent_data) {
+                remove(&self.global, global_entry, global_data);
+                return Err(e);
+            }
+            global_data.changed();
+            parent_data.changed();
+        }
+        Ok(())
+    }
+
+    /// Detaches the element from both of its lists.
+    pub fn detach(&self) {
+        // Elements are always locked before lists.
+        let (global_entry, parent_entry) =
+            &mut lock_both(&*self.global.data, &*self.parent.data, EntryCell::lock);
+        let global = self.global.data.take_owner(global_entry).upgrade();
+        let parent = self.parent.data.take_owner(parent_entry).upgrade();
+        match (global, parent) {
+            (Some(global), Some(parent)) if Arc::ptr_eq(&global, &parent) => {
+                let data = &mut *global.lock();
+                data.remove_member(global_entry.id);
+                data.remove_member(parent_entry.id);
+                data.changed();
+            }
+            (Some(global), Some(parent)) => {
+                let (global_data, parent_data) =
+                    &mut lock_both(&*global, &*parent, ListMutex::lock);
+                global_data.remove_member(global_entry.id);
+                parent_data.remove_member(parent_entry.id);
+                global_data.changed();
+                parent_data.changed();
+            }
+            // At most one of the lists is still alive.
+            (global, parent) => {
+                for (list, id) in [(global, global_entry.id), (parent, parent_entry.id)] {
+                    if let Some(list) = list {
+                        let data = &mut *list.lock();
+                        data.remove_member(id);
+                        data.changed();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Inserts an element into a list whose data and whose entry are both locked.
+///
+/// The caller must have checked that the list is open and that the limit of entries per
+/// target allows the element. The element is left detached if no id is available.
+fn insert<T>(
+    element: &SyncWeakListElement<T>,
+    entry: &mut EntryData<u64>,
+    list: &SyncWeakList<T>,
+    data: &mut WeakListData<T, u64>,
+) -> Result<(), Error>
+where
+    T: ?Sized,
+{
+    entry.id = data.allocate_id()?;
+    element.data.set_owner(entry, &list.data);
+    let visible_from = data.visible_from(Visible::Unspecified);
+    let seq = data.allocate_seq();
+    data.insert_member(Member {
+        id: entry.id,
+        t: element.t.clone(),
+        entry: element.data.clone(),
+        dead_since: None,
+        reattach: entry.reattach,
+        enabled: entry.enabled,
+        seq,
+        extra: MemberExtra {
+            keepalive: element.keepalive.clone(),
+            visible_from,
+            ..Default::default()
+        }
+        .boxed(),
+    });
+    Ok(())
+}
+
+/// Removes an element that was inserted with [insert] from a list whose data and whose
+/// entry are both locked.
+fn remove<T>(
+    element: &SyncWeakListElement<T>,
+    entry: &mut EntryData<u64>,
+    data: &mut WeakListData<T, u64>,
+) where
+    T: ?Sized,
+{
+    data.remove_member(entry.id);
+    element.data.take_owner(entry);
+}
+
+/// Locks two distinct mutexes in the order of their addresses.
+fn lock_both<'a, M, U>(
+    a: &'a M,
+    b: &'a M,
+    lock: impl Fn(&'a M) -> MutexGuard<'a, U>,
+) -> (MutexGuard<'a, U>, MutexGuard<'a, U>) {
+    if (a as *const M) < (b as *const M) {
+        let a = lock(a);
+        (a, lock(b))
+    } else {
+        let b = lock(b);
+        (lock(a), b)
+    }
+}
+
+impl<T> Drop for SyncPairedWeakListElement<T>
+where
+    T: ?Sized,
+{
+    fn drop(&mut self) {
+        self.detach();
+    }
+}
+
+impl<T> Debug for SyncPairedWeakListElement<T>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SyncPairedWeakListElement")
+            .field("global", &self.global)
+            .field("parent", &self.parent)
+            .finish_non_exhaustive()
+    }
+}
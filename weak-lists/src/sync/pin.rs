@@ -0,0 +1,141 @@
+use {
+    crate::sync::{PinIter, SyncPinWeakList, SyncPinWeakListElement, SyncWeakListElement},
+    alloc::sync::Arc,
+    core::{
+        fmt::{Debug, Formatter},
+        iter::FusedIterator,
+        pin::Pin,
+    },
+};
+
+impl<T> SyncPinWeakList<T>
+where
+    T: ?Sized,
+{
+    /// Removes all elements from the list.
+    pub fn clear(&self) {
+        self.list.clear();
+    }
+
+    /// Creates an iterator over the entries of the list.
+    ///
+    /// This iterator provides the same guarantees as [SyncWeakList::iter](crate::sync::SyncWeakList::iter).
+    pub fn iter(&self) -> PinIter<'_, T> {
+        PinIter {
+            iter: self.list.iter(),
+        }
+    }
+}
+
+impl<T> SyncPinWeakListElement<T>
+where
+    T: ?Sized,
+{
+    /// Creates a new list element.
+    pub fn new(t: &Pin<Arc<T>>) -> Self {
+        let t = unsafe {
+            // SAFETY: The Arc is only used to create a Weak. Since the Weak can only be
+            //         upgraded by the iterators of SyncPinWeakList, which pin the upgraded
+            //         Arc again, the pinning invariant is upheld.
+            Pin::into_inner_unchecked(t.clone())
+        };
+        Self {
+            element: SyncWeakListElement::new(Arc::downgrade(&t)),
+        }
+    }
+
+    /// Attaches the element to a list.
+    ///
+    /// See [SyncWeakListElement::attach].
+    pub fn attach(&self, to: &SyncPinWeakList<T>) {
+        self.element.attach(&to.list);
+    }
+
+    /// Detaches the element from its current list.
+    ///
+    /// See [SyncWeakListElement::detach].
+    pub fn detach(&self) {
+        self.element.detach();
+    }
+}
+
+impl<T> Default for SyncPinWeakList<T>
+where
+    T: ?Sized,
+{
+    fn default() -> Self {
+        Self {
+            list: Default::default(),
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SyncPinWeakList<T>
+where
+    T: ?Sized,
+{
+    type Item = Pin<Arc<T>>;
+    type IntoIter = PinIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> Debug for SyncPinWeakList<T>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SyncPinWeakList")
+            .field("list", &self.list)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Debug for SyncPinWeakListElement<T>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SyncPinWeakListElement")
+            .field("element", &self.element)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Iterator for PinIter<'_, T>
+where
+    T: ?Sized,
+{
+    type Item = Pin<Arc<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|t| unsafe {
+            // SAFETY: All elements of a SyncPinWeakList were created from pinned Arcs.
+            Pin::new_unchecked(t)
+        })
+    }
+}
+
+impl<T> Clone for PinIter<'_, T>
+where
+    T: ?Sized,
+{
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<T> Debug for PinIter<'_, T>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(&self.iter, f)
+    }
+}
+
+impl<T> FusedIterator for PinIter<'_, T> where T: ?Sized {}
@@ -0,0 +1,251 @@
+//! Low-level access to the entries of a [SyncWeakList].
+//!
+//! This module is intended for frameworks that want to implement their own iteration
+//! strategies. It exposes the storage of the list directly.
+//!
+//! The entries of a list are stored in slots. The following invariants hold:
+//!
+//! - Every entry has an id that is unique within its list. Ids allocated by the list are
+//!   only reused after the id type has been exhausted. See [EntryId].
+//!   Ids can also be chosen by the caller with [SyncWeakListElement::attach_keyed]. The id of an
+//!   element can be retrieved with [SyncWeakListElement::id].
+//! - The slot of an entry does not change until the list is compacted. Compaction removes
+//!   empty slots and moves the remaining entries to lower slots, preserving their order.
+//!   To amortize its cost, compaction does nothing if only few slots are empty.
+//! - When an entry is detached, its slot becomes empty. Empty slots might be reused by
+//!   entries that are attached later.
+//! - The list is never compacted while an iterator is active. Otherwise, the list compacts
+//!   itself when an iterator is created or [reset](crate::sync::Iter::reset) and when
+//!   attaching an element requires the storage to grow. This can be disabled with
+//!   [set_auto_compact](RawSyncWeakList::set_auto_compact).
+//!
+//! Each function locks the list separately. Other threads can modify the list between
+//! two calls.
+//!
+//! [SyncWeakListElement::id]: crate::sync::SyncWeakListElement::id
+//! [SyncWeakListElement::attach_keyed]: crate::sync::SyncWeakListElement::attach_keyed
+
+use {
+    crate::{
+        id::EntryId,
+        sync::{IndexedIter, SyncWeakList},
+    },
+    alloc::sync::{Arc, Weak},
+    core::{
+        fmt::{Debug, Formatter},
+        iter::FusedIterator,
+    },
+};
+
+/// Low-level access to the entries of a [SyncWeakList].
+///
+/// This object is created by calling [raw](SyncWeakList::raw). See the
+/// [module documentation](self) for the invariants of the storage.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use weak_lists::{SyncWeakList, SyncWeakListElement};
+///
+/// let list = SyncWeakList::default();
+/// let entries = [Arc::new(0), Arc::new(1), Arc::new(2)];
+/// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+/// for element in &elements {
+///     element.attach(&list);
+/// }
+/// let raw = list.raw();
+/// raw.set_auto_compact(false);
+/// elements[0].detach();
+/// elements[1].detach();
+/// assert_eq!(raw.slots(), 3);
+/// assert!(raw.slot(0).is_none());
+/// let (id, target) = raw.slot(2).unwrap();
+/// assert_eq!(Some(id), elements[2].id());
+/// assert_eq!(target.upgrade(), Some(entries[2].clone()));
+/// assert!(raw.compact());
+/// assert_eq!(raw.slots(), 1);
+/// ```
+pub struct RawSyncWeakList<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    list: &'a SyncWeakList<T, I>,
+}
+
+impl<T, I> SyncWeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns an object providing low-level access to the entries of the list.
+    ///
+    /// See [raw](crate::sync::raw) for details.
+    pub fn raw(&self) -> RawSyncWeakList<'_, T, I> {
+        RawSyncWeakList { list: self }
+    }
+
+    /// Creates an iterator over the entries of the list and their slots.
+    ///
+    /// This iterator behaves like [iter](Self::iter) but also yields the slot of each
+    /// entry. Since the list is never compacted while an iterator is active, the slots
+    /// remain valid until the iterator is dropped. Afterwards, they remain valid until
+    /// the [compaction_epoch](Self::compaction_epoch) changes. A slot can be reused by a
+    /// different entry after its entry has been detached.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let mut scratch = vec![0; list.raw().slots()];
+    /// for (slot, e) in list.iter_indexed() {
+    ///     scratch[slot] = *e + 1;
+    /// }
+    /// assert_eq!(scratch, [1, 2]);
+    /// ```
+    pub fn iter_indexed(&self) -> IndexedIter<'_, T, I> {
+        IndexedIter { iter: self.iter() }
+    }
+
+    /// Returns a counter that changes whenever the entries of the list move to different
+    /// slots.
+    ///
+    /// This happens when the list is compacted or cleared. See [raw](crate::sync::raw).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entry = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&entry));
+    /// element.attach(&list);
+    /// let epoch = list.compaction_epoch();
+    /// list.clear();
+    /// assert_ne!(list.compaction_epoch(), epoch);
+    /// ```
+    pub fn compaction_epoch(&self) -> u64 {
+        self.data.lock().compaction_epoch
+    }
+}
+
+impl<T, I> RawSyncWeakList<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns the number of slots.
+    ///
+    /// This includes empty slots.
+    pub fn slots(&self) -> usize {
+        self.list.data.lock().members.index_len()
+    }
+
+    /// Returns the id and the target of the entry in a slot.
+    ///
+    /// Returns `None` if the slot is empty or out of bounds.
+    pub fn slot(&self, slot: usize) -> Option<(I, Weak<T>)> {
+        let data = &*self.list.data.lock();
+        let member = data.members.get_by_index(slot)?;
+        Some((member.id, member.t.clone()))
+    }
+
+    /// Returns the target of the entry with the given id.
+    ///
+    /// Returns `None` if no entry with this id is attached to the list.
+    pub fn get(&self, id: I) -> Option<Weak<T>> {
+        let data = &*self.list.data.lock();
+        data.members.get(&id).map(|m| m.t.clone())
+    }
+
+    /// Compacts the list.
+    ///
+    /// Returns `false` and leaves the list unchanged if an iterator is active.
+    pub fn compact(&self) -> bool {
+        let data = &mut *self.list.data.lock();
+        if data.active_iterators > 0 {
+            return false;
+        }
+        data.compact();
+        true
+    }
+
+    /// Returns whether the list compacts itself automatically.
+    ///
+    /// The default is `true`.
+    pub fn auto_compact(&self) -> bool {
+        self.list.data.lock().auto_compact
+    }
+
+    /// Sets whether the list compacts itself automatically.
+    ///
+    /// If this is disabled, slots only change when [compact](Self::compact) is called,
+    /// when entries are attached or detached, and when the list is cleared.
+    pub fn set_auto_compact(&self, auto_compact: bool) {
+        self.list.data.lock().auto_compact = auto_compact;
+    }
+}
+
+impl<T, I> Debug for RawSyncWeakList<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RawSyncWeakList")
+            .field("list", self.list)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, I> Iterator for IndexedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    type Item = (usize, Arc<T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next_with_index().map(|(idx, _, t)| (idx, t))
+    }
+}
+
+impl<T, I> Clone for IndexedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<T, I> Debug for IndexedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(&self.iter, f)
+    }
+}
+
+impl<T, I> FusedIterator for IndexedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+}
@@ -0,0 +1,138 @@
+use {
+    crate::{
+        id::EntryId,
+        sync::{Member, SyncWeakList, SyncWeakListElement, WeakListData},
+    },
+    alloc::{sync::Arc, vec::Vec},
+    core::{mem, ptr},
+};
+
+impl<T, I> SyncWeakListElement<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Sets whether the element is reattached after its list has been cleared.
+    ///
+    /// If this is enabled and the list that the element is attached to is
+    /// [cleared](SyncWeakList::clear), the element is marked as pending. A later call to
+    /// [reopen_pending](SyncWeakList::reopen_pending) attaches it to the list again.
+    /// Attaching the element to another list, detaching it, or dropping it removes the
+    /// mark.
+    ///
+    /// This has no effect if the list is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// elements[0].set_reattach_on_clear(true);
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// list.clear();
+    /// assert!(list.iter().next().is_none());
+    /// assert_eq!(list.reopen_pending(), 1);
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [0]);
+    /// ```
+    pub fn set_reattach_on_clear(&self, reattach: bool) {
+        let data = &mut *self.data.lock();
+        data.reattach = reattach;
+        if let Some(list) = self.data.owner(data) {
+            let list_data = &mut *list.lock();
+            if let Some(member) = list_data.members.get_mut(&data.id) {
+                if Arc::ptr_eq(&member.entry, &self.data) {
+                    member.reattach = reattach;
+                }
+            }
+        }
+    }
+}
+
+impl<T, I> SyncWeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Reattaches the elements that were marked as pending when the list was cleared.
+    ///
+    /// See [set_reattach_on_clear](SyncWeakListElement::set_reattach_on_clear). Elements
+    /// that have been detached, attached to another list, or dropped since the list was
+    /// cleared are ignored. The reattached elements keep their ids unless an id has been
+    /// reused in the meantime. If no other id is available, the element is detached
+    /// instead.
+    ///
+    /// If the list has been [closed](Self::close), the elements remain pending.
+    ///
+    /// Returns the number of elements that were reattached.
+    pub fn reopen_pending(&self) -> usize {
+        let pending = mem::take(&mut self.data.lock().pending);
+        let mut reopened = 0;
+        for mut member in pending {
+            // Elements are always locked before lists.
+            let entry_data = member.entry.clone();
+            let entry = &mut *entry_data.lock();
+            let is_pending = entry.reattach
+                && entry.id == member.id
+                && ptr::eq(entry_data.owner_ptr(entry), Arc::as_ptr(&self.data));
+            if !is_pending {
+                continue;
+            }
+            let data = &mut *self.data.lock();
+            if data.closed {
+                data.pending.push(member);
+                continue;
+            }
+            match data.members.get(&member.id) {
+                Some(m) if Arc::ptr_eq(&m.entry, &member.entry) => continue,
+                Some(_) => {
+                    let Ok(id) = data.allocate_id() else {
+                        entry_data.take_owner(entry);
+                        continue;
+                    };
+                    member.id = id;
+                    entry.id = id;
+                }
+                None => {}
+            }
+            member.dead_since = None;
+            member.reattach = true;
+            member.enabled = entry.enabled;
+            data.insert_member(member);
+            data.changed();
+            reopened += 1;
+        }
+        reopened
+    }
+}
+
+impl<T, I> WeakListData<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Removes all members. The members whose elements have requested to be reattached
+    /// are moved to the pending members. The other members are returned.
+    pub(super) fn clear_members(&mut self) -> Vec<Member<T, I>> {
+        let mut removed = Vec::new();
+        for idx in 0..self.members.index_len() {
+            let Some(id) = self.members.get_by_index(idx).map(|m| m.id) else {
+                continue;
+            };
+            if let Some(member) = self.remove_member(id) {
+                match member.reattach {
+                    true => self.pending.push(member),
+                    false => removed.push(member),
+                }
+            }
+        }
+        self.members.clear();
+        self.compaction_epoch += 1;
+        removed
+    }
+}
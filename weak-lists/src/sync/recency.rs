@@ -0,0 +1,171 @@
+use {
+    crate::{
+        id::EntryId,
+        sync::{RecencyIter, SyncWeakList, SyncWeakListElement, WeakListData},
+    },
+    alloc::{sync::Arc, vec::Vec},
+    core::{
+        cmp::Reverse,
+        fmt::{Debug, Formatter},
+        iter::FusedIterator,
+    },
+};
+
+impl<T, I> SyncWeakListElement<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns the sequence number of the attachment of this element to its current list.
+    ///
+    /// Each list numbers the attachments of elements in the order in which they happen,
+    /// starting at 0. Replacing an entry with [replace](SyncWeakList::replace) counts as an
+    /// attachment of the new element. Elements that are reattached by
+    /// [reopen_pending](SyncWeakList::reopen_pending) keep their sequence numbers.
+    ///
+    /// Returns `None` if the element is not attached to a list.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entry = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&entry));
+    /// assert_eq!(element.attach_seq(), None);
+    /// element.attach(&list);
+    /// assert_eq!(element.attach_seq(), Some(0));
+    /// element.attach(&list);
+    /// assert_eq!(element.attach_seq(), Some(1));
+    /// ```
+    pub fn attach_seq(&self) -> Option<u64> {
+        let data = &*self.data.lock();
+        let list = self.data.owner(data)?;
+        let list_data = &*list.lock();
+        let member = list_data.members.get(&data.id)?;
+        Arc::ptr_eq(&member.entry, &self.data).then_some(member.seq)
+    }
+}
+
+impl<T, I> SyncWeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Creates an iterator that yields the most recently attached entries first.
+    ///
+    /// The entries are ordered by their [attach_seq](SyncWeakListElement::attach_seq). The
+    /// order is determined when the iterator is created. Entries that are attached
+    /// afterwards are not yielded. Entries that are detached before they are yielded are
+    /// skipped. Otherwise, this iterator provides the same guarantees as
+    /// [iter](Self::iter).
+    ///
+    /// This function has to sort the entries and is intended for diagnostics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1), Arc::new(2)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// elements[0].attach(&list);
+    /// let recent: Vec<_> = list.iter_by_recency().map(|e| *e).collect();
+    /// assert_eq!(recent, [0, 2, 1]);
+    /// ```
+    pub fn iter_by_recency(&self) -> RecencyIter<'_, T, I> {
+        let iter = self.iter();
+        let mut entries: Vec<_> = {
+            let data = &*self.data.lock();
+            (0..data.members.index_len())
+                .filter_map(|idx| data.members.get_by_index(idx))
+                .filter(|member| member.is_visible_to(&iter.visibility))
+                .map(|member| (member.id, member.seq))
+                .collect()
+        };
+        drop(iter);
+        entries.sort_unstable_by_key(|&(_, seq)| Reverse(seq));
+        RecencyIter {
+            list: self,
+            entries: entries.into_iter(),
+        }
+    }
+}
+
+impl<T, I> WeakListData<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns the sequence number of a new attachment.
+    pub(super) fn allocate_seq(&mut self) -> u64 {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        seq
+    }
+}
+
+impl<T, I> Iterator for RecencyIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    type Item = Arc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (id, seq) in self.entries.by_ref() {
+            let data = &*self.list.data.lock();
+            let t = match data.members.get(&id) {
+                Some(member) if member.seq == seq && member.enabled => member.upgrade(),
+                _ => None,
+            };
+            if t.is_some() {
+                return t;
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.entries.len()))
+    }
+}
+
+impl<T, I> Clone for RecencyIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn clone(&self) -> Self {
+        Self {
+            list: self.list,
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+impl<T, I> Debug for RecencyIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RecencyIter")
+            .field("remaining", &self.entries.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, I> FusedIterator for RecencyIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+}
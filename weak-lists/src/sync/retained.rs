@@ -0,0 +1,109 @@
+use {
+    crate::{
+        id::EntryId,
+        sync::{RetainedIter, SyncWeakList},
+    },
+    alloc::sync::Arc,
+    core::{
+        fmt::{Debug, Formatter},
+        iter::FusedIterator,
+    },
+};
+
+impl<T, I> SyncWeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Creates an iterator that keeps the targets of the entries alive until they are
+    /// yielded.
+    ///
+    /// The iterator upgrades the targets of all live entries when it is created and holds
+    /// the strong references until it yields the entries. Therefore, every entry that is
+    /// alive when the iterator is created is still alive when it is yielded. This is
+    /// useful if the entries must not be dropped during a pass, for example, because
+    /// dropping one entry drops another.
+    ///
+    /// Entries that are detached before they are yielded are still skipped. Their targets
+    /// are released when the iterator is dropped. Otherwise, this iterator provides the
+    /// same guarantees as [iter](Self::iter).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let mut iter = list.iter_retained();
+    /// let [_, entry] = entries;
+    /// drop(entry);
+    /// assert_eq!(iter.next().map(|e| *e), Some(0));
+    /// assert_eq!(iter.next().map(|e| *e), Some(1));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter_retained(&self) -> RetainedIter<'_, T, I> {
+        let iter = self.iter();
+        let retained = {
+            let data = &*self.data.lock();
+            (0..data.members.index_len())
+                .filter_map(|idx| data.members.get_by_index(idx))
+                .filter(|member| member.is_visible_to(&iter.visibility))
+                .filter_map(|member| Some((member.id, member.upgrade()?)))
+                .collect()
+        };
+        RetainedIter { iter, retained }
+    }
+}
+
+impl<T, I> Iterator for RetainedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    type Item = Arc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (id, t) = self.iter.next_with_id()?;
+        self.retained.remove(&id);
+        Some(t)
+    }
+}
+
+impl<T, I> Clone for RetainedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            retained: self.retained.clone(),
+        }
+    }
+}
+
+impl<T, I> Debug for RetainedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RetainedIter")
+            .field("iter", &self.iter)
+            .field("retained", &self.retained.len())
+            .finish()
+    }
+}
+
+impl<T, I> FusedIterator for RetainedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+}
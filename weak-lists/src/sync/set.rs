@@ -0,0 +1,130 @@
+use {
+    crate::{
+        id::EntryId,
+        sync::{Difference, Intersection, IterChain, SyncWeakList, Union},
+    },
+    alloc::sync::Arc,
+    core::iter::FusedIterator,
+};
+
+impl<T, I> Iterator for Intersection<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    type Item = Arc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = &self.other;
+        self.iter.by_ref().find(|t| other.contains(t))
+    }
+}
+
+impl<T, I> FusedIterator for Intersection<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+}
+
+impl<T, I> Iterator for Difference<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    type Item = Arc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = &self.other;
+        self.iter.by_ref().find(|t| !other.contains(t))
+    }
+}
+
+impl<T, I> FusedIterator for Difference<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+}
+
+impl<T, I> Iterator for Union<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    type Item = Arc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let seen = &mut self.seen;
+        self.first
+            .by_ref()
+            .chain(self.second.by_ref())
+            .find(|t| seen.insert(t))
+    }
+}
+
+impl<T, I> FusedIterator for Union<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+}
+
+/// Creates an iterator over the entries of multiple lists.
+///
+/// The lists are visited in order. Entries are compared by the address of their targets
+/// and each target is returned at most once. Otherwise, the iterator provides the same
+/// guarantees as [iter](SyncWeakList::iter) for each list.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use weak_lists::{sync::iter_chain, SyncWeakList, SyncWeakListElement};
+///
+/// let topics: [_; 3] = std::array::from_fn(|_| SyncWeakList::default());
+/// let a = Arc::new(1);
+/// let b = Arc::new(2);
+/// let a1 = SyncWeakListElement::new(Arc::downgrade(&a));
+/// let a2 = SyncWeakListElement::new(Arc::downgrade(&a));
+/// let b1 = SyncWeakListElement::new(Arc::downgrade(&b));
+/// a1.attach(&topics[0]);
+/// a2.attach(&topics[2]);
+/// b1.attach(&topics[1]);
+/// let subscribers: Vec<_> = iter_chain(&topics).collect();
+/// assert_eq!(subscribers, [a, b]);
+/// ```
+pub fn iter_chain<'a, T, I>(
+    lists: impl IntoIterator<Item = &'a SyncWeakList<T, I>>,
+) -> IterChain<'a, T, I>
+where
+    T: ?Sized + 'a,
+    I: EntryId,
+{
+    IterChain {
+        iters: lists.into_iter().map(|l| l.iter()).collect(),
+        seen: Default::default(),
+    }
+}
+
+impl<T, I> Iterator for IterChain<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    type Item = Arc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let seen = &mut self.seen;
+        self.iters
+            .iter_mut()
+            .find_map(|iter| iter.find(|t| seen.insert(t)))
+    }
+}
+
+impl<T, I> FusedIterator for IterChain<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+}
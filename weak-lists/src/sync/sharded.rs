@@ -0,0 +1,143 @@
+use {
+    crate::sync::{ShardedIter, SyncWeakList, SyncWeakListElement, SyncWeakListSharded},
+    alloc::sync::Arc,
+    core::{
+        array,
+        fmt::{Debug, Formatter},
+        iter::FusedIterator,
+        sync::atomic::{AtomicUsize, Ordering::Relaxed},
+    },
+};
+
+impl<T, const N: usize> SyncWeakListSharded<T, N>
+where
+    T: ?Sized,
+{
+    /// Attaches the element to one of the shards.
+    ///
+    /// The shards are chosen in a round-robin fashion. If the element was previously
+    /// attached to a list, it is automatically detached from that list.
+    ///
+    /// See [SyncWeakListElement::attach].
+    pub fn attach(&self, element: &SyncWeakListElement<T>) {
+        let idx = self.next.fetch_add(1, Relaxed) % N;
+        element.attach(&self.shards[idx]);
+    }
+
+    /// Attaches the element to the shard determined by a key.
+    ///
+    /// Elements with the same key are attached to the same shard. If the element was
+    /// previously attached to a list, it is automatically detached from that list.
+    ///
+    /// See [SyncWeakListElement::attach].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::sync::{SyncWeakListElement, SyncWeakListSharded};
+    ///
+    /// let list = SyncWeakListSharded::<_, 4>::default();
+    /// let entries = [Arc::new(0), Arc::new(1)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// list.attach_with_key(&elements[0], 6);
+    /// list.attach_with_key(&elements[1], 6);
+    /// assert_eq!(list.shards()[2].iter().count(), 2);
+    /// ```
+    pub fn attach_with_key(&self, element: &SyncWeakListElement<T>, key: u64) {
+        let idx = (key % N as u64) as usize;
+        element.attach(&self.shards[idx]);
+    }
+
+    /// Returns the shards of the list.
+    pub fn shards(&self) -> &[SyncWeakList<T>; N] {
+        &self.shards
+    }
+
+    /// Removes all elements from all shards.
+    pub fn clear(&self) {
+        for shard in &self.shards {
+            shard.clear();
+        }
+    }
+
+    /// Creates an iterator over the entries of all shards.
+    ///
+    /// The shards are visited one after another. Only one shard is iterated over at a
+    /// time. Within each shard, this iterator provides the same guarantees as
+    /// [SyncWeakList::iter].
+    pub fn iter(&self) -> ShardedIter<'_, T> {
+        ShardedIter {
+            iter: self.shards.iter().flatten(),
+        }
+    }
+}
+
+impl<T, const N: usize> Default for SyncWeakListSharded<T, N>
+where
+    T: ?Sized,
+{
+    fn default() -> Self {
+        assert!(N > 0, "a sharded list must have at least one shard");
+        Self {
+            shards: array::from_fn(|_| SyncWeakList::default()),
+            next: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a SyncWeakListSharded<T, N>
+where
+    T: ?Sized,
+{
+    type Item = Arc<T>;
+    type IntoIter = ShardedIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T, const N: usize> Debug for SyncWeakListSharded<T, N>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SyncWeakListSharded")
+            .field("shards", &self.shards)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Iterator for ShardedIter<'_, T>
+where
+    T: ?Sized,
+{
+    type Item = Arc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+impl<T> Clone for ShardedIter<'_, T>
+where
+    T: ?Sized,
+{
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<T> Debug for ShardedIter<'_, T>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<T> FusedIterator for ShardedIter<'_, T> where T: ?Sized {}
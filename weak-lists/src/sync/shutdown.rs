@@ -0,0 +1,74 @@
+use crate::{id::EntryId, sync::SyncWeakList};
+#[cfg(feature = "std")]
+use alloc::{sync::Arc, vec::Vec};
+
+impl<T, I> SyncWeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Closes the list.
+    ///
+    /// Elements can no longer be attached to a closed list. Elements that are already
+    /// attached stay attached. A closed list cannot be reopened.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entry = Arc::new(1);
+    /// let element = SyncWeakListElement::new(Arc::downgrade(&entry));
+    /// list.close();
+    /// assert!(list.is_closed());
+    /// element.attach(&list);
+    /// assert_eq!(element.id(), None);
+    /// ```
+    pub fn close(&self) {
+        self.data.lock().closed = true;
+    }
+
+    /// Returns whether the list has been [closed](Self::close).
+    pub fn is_closed(&self) -> bool {
+        self.data.lock().closed
+    }
+
+    /// Closes the list, waits for all iterators, and detaches all elements.
+    ///
+    /// This function combines [close](Self::close), [quiesce](Self::quiesce), and
+    /// [drain](Self::drain). Returns the targets of the detached elements that were
+    /// still alive.
+    ///
+    /// After this function returns, no element is attached to the list, no element can be
+    /// attached to the list, and no iteration over the list that started before this
+    /// function was called is still in progress.
+    ///
+    /// Calling this function while the current thread holds an iterator over the list
+    /// deadlocks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// assert_eq!(list.shutdown(), entries);
+    /// assert_eq!(elements[0].id(), None);
+    /// elements[0].attach(&list);
+    /// assert!(list.iter().next().is_none());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn shutdown(&self) -> Vec<Arc<T>> {
+        self.close();
+        self.quiesce();
+        self.drain().collect()
+    }
+}
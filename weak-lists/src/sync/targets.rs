@@ -0,0 +1,257 @@
+use {
+    crate::{
+        id::EntryId,
+        sync::{SyncWeakList, TargetSet},
+    },
+    alloc::{
+        collections::btree_map::Entry,
+        sync::{Arc, Weak},
+        vec::Vec,
+    },
+};
+
+fn address<T>(t: *const T) -> usize
+where
+    T: ?Sized,
+{
+    t as *const () as usize
+}
+
+impl<T> TargetSet<T>
+where
+    T: ?Sized,
+{
+    /// Creates a set containing the live targets of the list.
+    pub(super) fn of<I>(list: &SyncWeakList<T, I>) -> Self
+    where
+        I: EntryId,
+    {
+        let mut set = Self::default();
+        let data = &*list.data.lock();
+        for idx in 0..data.members.index_len() {
+            if let Some(member) = data.members.get_by_index(idx) {
+                if member.is_alive() {
+                    set.targets
+                        .entry(address(member.t.as_ptr()))
+                        .or_insert_with(|| member.t.clone());
+                }
+            }
+        }
+        set
+    }
+
+    /// Returns whether the set contains the target.
+    pub(super) fn contains(&self, t: &Arc<T>) -> bool {
+        self.targets.contains_key(&address(Arc::as_ptr(t)))
+    }
+
+    /// Adds the target to the set. Returns whether the set did not already contain the
+    /// target.
+    pub(super) fn insert(&mut self, t: &Arc<T>) -> bool {
+        match self.targets.entry(address(Arc::as_ptr(t))) {
+            Entry::Vacant(e) => {
+                // Holding a weak reference ensures that the address is not reused while
+                // it is part of the set.
+                e.insert(Arc::downgrade(t));
+                true
+            }
+            Entry::Occupied(_) => false,
+        }
+    }
+}
+
+impl<T, I> SyncWeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns whether the live entries of the list are exactly the given targets.
+    ///
+    /// Targets are compared by identity. The order of the entries is ignored but each
+    /// target must occur as often as it occurs in the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(0)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// elements[0].attach(&list);
+    /// assert!(list.members_equal(&entries[..1]));
+    /// assert!(!list.members_equal(&entries[1..]));
+    /// elements[1].attach(&list);
+    /// assert!(list.members_equal(&[entries[1].clone(), entries[0].clone()]));
+    /// ```
+    pub fn members_equal(&self, targets: &[Arc<T>]) -> bool {
+        let mut members: Vec<_> = {
+            let data = &*self.data.lock();
+            (0..data.members.index_len())
+                .filter_map(|idx| data.members.get_by_index(idx))
+                .filter(|member| member.is_alive())
+                .map(|member| address(member.t.as_ptr()))
+                .collect()
+        };
+        let mut targets: Vec<_> = targets.iter().map(|t| address(Arc::as_ptr(t))).collect();
+        members.sort_unstable();
+        targets.sort_unstable();
+        members == targets
+    }
+
+    /// Appends the targets of all entries of the list to `out`.
+    ///
+    /// The targets are not upgraded and the entries whose targets have already been
+    /// dropped are included. Therefore, this function does not affect the strong counts of
+    /// the targets. The targets are appended in the order in which [iter](Self::iter)
+    /// would visit them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let mut out = vec![];
+    /// list.export_weak(&mut out);
+    /// assert_eq!(Arc::strong_count(&entries[0]), 1);
+    /// let [_, entry] = entries;
+    /// drop(entry);
+    /// assert_eq!(out.iter().filter(|t| t.strong_count() > 0).count(), 1);
+    /// ```
+    pub fn export_weak(&self, out: &mut Vec<Weak<T>>) {
+        let data = &*self.data.lock();
+        out.reserve(data.members.len());
+        for idx in 0..data.members.index_len() {
+            if let Some(member) = data.members.get_by_index(idx) {
+                out.push(member.t.clone());
+            }
+        }
+    }
+
+    /// Splits the live targets of the list into those for which `f` returns `true` and
+    /// those for which it returns `false`.
+    ///
+    /// The targets are collected in a single pass over the list and appear in the order in
+    /// which [iter](Self::iter) would visit them.
+    /// The lock of the list is acquired only once. `f` is invoked after the lock has been
+    /// released.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1), Arc::new(2)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let (even, odd) = list.partition(|e| **e % 2 == 0);
+    /// assert_eq!(even, [entries[0].clone(), entries[2].clone()]);
+    /// assert_eq!(odd, [entries[1].clone()]);
+    /// ```
+    pub fn partition(&self, f: impl FnMut(&Arc<T>) -> bool) -> (Vec<Arc<T>>, Vec<Arc<T>>) {
+        let targets: Vec<_> = {
+            let data = &*self.data.lock();
+            (0..data.members.index_len())
+                .filter_map(|idx| data.members.get_by_index(idx))
+                .filter(|member| member.enabled)
+                .filter_map(|member| member.target().cloned())
+                .collect()
+        };
+        targets.iter().filter_map(Weak::upgrade).partition(f)
+    }
+
+    /// Invokes `f` with each live target of the list and collects the results.
+    ///
+    /// This is equivalent to `list.iter().map(f).collect()` but the lock of the list is
+    /// acquired only once instead of once per entry. `f` is invoked after the lock has
+    /// been released. The output is allocated once with the number of live entries as its
+    /// capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1), Arc::new(2)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// assert_eq!(list.map_collect(|e| *e * 2), [0, 2, 4]);
+    /// ```
+    pub fn map_collect<U>(&self, f: impl FnMut(Arc<T>) -> U) -> Vec<U> {
+        let targets: Vec<_> = {
+            let data = &*self.data.lock();
+            (0..data.members.index_len())
+                .filter_map(|idx| data.members.get_by_index(idx))
+                .filter(|member| member.enabled && member.is_alive())
+                .filter_map(|member| member.target().cloned())
+                .collect()
+        };
+        let mut out = Vec::with_capacity(targets.len());
+        out.extend(targets.iter().filter_map(Weak::upgrade).map(f));
+        out
+    }
+
+    /// Returns the live targets of the list ordered by the ids of their entries.
+    ///
+    /// Unless the ids have wrapped around, this is the order in which the entries were
+    /// attached. Unlike the order of [iter](Self::iter), this order does not depend on how
+    /// the list reuses the storage of detached entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1), Arc::new(2)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// elements[0].detach();
+    /// elements[0].attach(&list);
+    /// let targets: Vec<_> = list.targets_by_id().iter().map(|t| **t).collect();
+    /// assert_eq!(targets, [1, 2, 0]);
+    /// ```
+    #[cfg(feature = "deterministic")]
+    pub fn targets_by_id(&self) -> Vec<Arc<T>> {
+        let mut members: Vec<_> = {
+            let data = &*self.data.lock();
+            (0..data.members.index_len())
+                .filter_map(|idx| data.members.get_by_index(idx))
+                .filter(|member| member.enabled)
+                .filter_map(|member| Some((member.id, member.target()?.clone())))
+                .collect()
+        };
+        members.sort_unstable_by_key(|(id, _)| *id);
+        members.iter().filter_map(|(_, t)| t.upgrade()).collect()
+    }
+}
+
+impl<T> Default for TargetSet<T>
+where
+    T: ?Sized,
+{
+    fn default() -> Self {
+        Self {
+            targets: Default::default(),
+        }
+    }
+}
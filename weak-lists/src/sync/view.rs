@@ -0,0 +1,115 @@
+use {
+    crate::{
+        id::EntryId,
+        sync::{DeadAgeStats, Iter, SyncWeakList, SyncWeakListView},
+    },
+    alloc::sync::Arc,
+    core::fmt::{Debug, Formatter},
+};
+
+impl<T, I> SyncWeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns a read-only view of the list.
+    ///
+    /// The view can be handed to code that should be able to iterate over the list but
+    /// not to attach elements to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use weak_lists::{sync::SyncWeakListView, SyncWeakList, SyncWeakListElement};
+    ///
+    /// fn dispatch(view: SyncWeakListView<'_, u32>) -> u32 {
+    ///     view.iter().map(|e| *e).sum()
+    /// }
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(1), Arc::new(2)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// assert_eq!(dispatch(list.view()), 3);
+    /// ```
+    pub fn view(&self) -> SyncWeakListView<'_, T, I> {
+        SyncWeakListView { list: self }
+    }
+}
+
+impl<'a, T, I> SyncWeakListView<'a, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Creates an iterator over the entries of the list.
+    ///
+    /// See [SyncWeakList::iter].
+    pub fn iter(&self) -> Iter<'a, T, I> {
+        self.list.iter()
+    }
+
+    /// Invokes the callback for each entry of the list.
+    ///
+    /// See [SyncWeakList::for_each].
+    pub fn for_each(&self, f: impl FnMut(Arc<T>)) -> usize {
+        self.list.for_each(f)
+    }
+
+    /// Returns an estimate of the number of live entries in the list.
+    ///
+    /// See [SyncWeakList::live_len_hint].
+    pub fn live_len_hint(&self) -> usize {
+        self.list.live_len_hint()
+    }
+
+    /// Returns statistics about the entries whose targets have been dropped.
+    ///
+    /// See [SyncWeakList::dead_age_stats].
+    pub fn dead_age_stats(&self) -> DeadAgeStats {
+        self.list.dead_age_stats()
+    }
+}
+
+impl<T, I> Clone for SyncWeakListView<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, I> Copy for SyncWeakListView<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+}
+
+impl<T, I> Debug for SyncWeakListView<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("SyncWeakListView").field(self.list).finish()
+    }
+}
+
+impl<'a, T, I> IntoIterator for SyncWeakListView<'a, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    type Item = Arc<T>;
+    type IntoIter = Iter<'a, T, I>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -0,0 +1,269 @@
+use {
+    crate::{
+        debug::Entry,
+        id::EntryId,
+        sync::{
+            element::AttachOptions, Member, SyncWeakList, SyncWeakListElement, Visibility,
+            WeakListData,
+        },
+    },
+    alloc::vec::Vec,
+    core::ops::Range,
+};
+
+/// The value of [Visibility::late] after the iterator has returned `None`.
+const FINISHED: usize = usize::MAX;
+
+/// When the entry of a newly attached element becomes visible to existing iterators.
+#[derive(Copy, Clone)]
+pub(super) enum Visible {
+    /// See [attach](SyncWeakListElement::attach).
+    Unspecified,
+    /// See [attach_visible_now](SyncWeakListElement::attach_visible_now).
+    Now,
+    /// See [attach_visible_next](SyncWeakListElement::attach_visible_next).
+    Next,
+}
+
+impl<T, I> SyncWeakListElement<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Attaches the element to a list and makes it visible to the existing iterators.
+    ///
+    /// This function behaves like [attach](Self::attach). Additionally, every iterator
+    /// over the list that has not yet returned `None` yields the element unless it is
+    /// detached or its target is dropped before the iterator reaches it. If the iterator
+    /// has already passed the position of the element, the element is yielded after all
+    /// other entries.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// elements[0].attach(&list);
+    /// let iter = list.iter();
+    /// elements[1].attach_visible_now(&list);
+    /// assert!(iter.will_yield(&elements[1]));
+    /// assert_eq!(iter.map(|e| *e).collect::<Vec<_>>(), [0, 1]);
+    /// ```
+    pub fn attach_visible_now(&self, to: &SyncWeakList<T, I>) {
+        let _ = self.attach_with_options(
+            to,
+            AttachOptions {
+                visible: Visible::Now,
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Attaches the element to a list and hides it from the existing iterators.
+    ///
+    /// This function behaves like [attach](Self::attach) except that the iterators over
+    /// the list that exist when this function is called do not yield the element. Only
+    /// iterators created afterwards yield it. This includes iterators that are
+    /// [reset](crate::sync::Iter::reset).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = SyncWeakList::default();
+    /// let entries = [Arc::new(0), Arc::new(1)];
+    /// let elements = entries.each_ref().map(|e| SyncWeakListElement::new(Arc::downgrade(e)));
+    /// elements[0].attach(&list);
+    /// let mut iter = list.iter();
+    /// elements[0].detach();
+    /// // The entry of element 1 reuses the storage of the entry of element 0.
+    /// elements[1].attach_visible_next(&list);
+    /// assert!(!iter.will_yield(&elements[1]));
+    /// assert_eq!(iter.next(), None);
+    /// assert_eq!(list.iter().next(), Some(entries[1].clone()));
+    /// ```
+    pub fn attach_visible_next(&self, to: &SyncWeakList<T, I>) {
+        let _ = self.attach_with_options(
+            to,
+            AttachOptions {
+                visible: Visible::Next,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+impl<I> Visibility<I>
+where
+    I: EntryId,
+{
+    /// Returns the visibility state of an iterator that has already returned `None`.
+    pub(super) fn finished() -> Self {
+        Self {
+            iteration: 0,
+            late: FINISHED,
+            extra: Vec::new(),
+            max_epoch: u64::MAX,
+            now: 0,
+        }
+    }
+
+    /// Marks the iterator as having returned `None`.
+    pub(super) fn finish(&mut self) {
+        self.late = FINISHED;
+        self.extra = Vec::new();
+    }
+}
+
+impl<T, I> Member<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns whether the iterator with the visibility state may yield the member.
+    pub(super) fn is_visible_to(&self, visibility: &Visibility<I>) -> bool {
+        self.enabled
+            && self.extra.as_ref().is_none_or(|e| {
+                e.visible_from <= visibility.iteration && e.epoch <= visibility.max_epoch
+            })
+    }
+}
+
+impl<T, I> WeakListData<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Starts a new iteration and returns the visibility state of its iterator.
+    ///
+    /// `now` is the time returned by the clock of the list, if any.
+    pub(super) fn start_visibility(&mut self, now: Option<u64>) -> Visibility<I> {
+        if self.active_iterators == 0 {
+            self.late.clear();
+        }
+        self.iterations += 1;
+        Visibility {
+            iteration: self.iterations,
+            late: self.late.len(),
+            extra: Vec::new(),
+            max_epoch: u64::MAX,
+            now: now.unwrap_or(self.iterations),
+        }
+    }
+
+    /// Returns the first iteration that may yield an entry attached now.
+    pub(super) fn visible_from(&self, visible: Visible) -> u64 {
+        match visible {
+            Visible::Next => self.iterations + 1,
+            _ => 0,
+        }
+    }
+
+    /// Records that the member with the id has been attached by
+    /// [attach_visible_now](SyncWeakListElement::attach_visible_now).
+    pub(super) fn record_late(&mut self, id: I) {
+        if self.active_iterators == 0 {
+            return;
+        }
+        let len = self.members.index_len();
+        // New members are usually appended.
+        let idx = (0..len)
+            .rev()
+            .find(|&idx| matches!(self.members.get_by_index(idx), Some(m) if m.id == id));
+        if let Some(idx) = idx {
+            self.late.push((id, idx));
+        }
+    }
+
+    /// Moves the late members that the iterator would otherwise miss to its extra members.
+    ///
+    /// `iter` is the range of indices that the iterator has not yet visited.
+    pub(super) fn catch_up(&self, iter: &Range<usize>, visibility: &mut Visibility<I>) {
+        if visibility.late >= self.late.len() {
+            return;
+        }
+        for &(id, idx) in &self.late[visibility.late..] {
+            if !iter.contains(&idx) {
+                visibility.extra.push((id, idx));
+            }
+        }
+        visibility.late = self.late.len();
+    }
+
+    /// Returns the index of the next extra member of the iterator.
+    pub(super) fn pop_extra(&self, visibility: &mut Visibility<I>) -> Option<usize> {
+        // The extra members are yielded in the order in which they were attached.
+        while !visibility.extra.is_empty() {
+            let (id, idx) = visibility.extra.remove(0);
+            if matches!(self.members.get_by_index(idx), Some(m) if m.id == id) {
+                return Some(idx);
+            }
+        }
+        None
+    }
+
+    /// Returns the entries that the iterator has not yet visited.
+    ///
+    /// `iter` is the range of indices that the iterator has not yet visited.
+    pub(super) fn remaining(
+        &self,
+        iter: &Range<usize>,
+        visibility: &Visibility<I>,
+    ) -> Vec<Entry<I>> {
+        let mut remaining = Vec::new();
+        if visibility.late == FINISHED {
+            return remaining;
+        }
+        let late = self.late.get(visibility.late..).unwrap_or_default();
+        let indices = iter.clone().map(|idx| (None, idx));
+        let extra = visibility
+            .extra
+            .iter()
+            .chain(late.iter().filter(|(_, idx)| !iter.contains(idx)))
+            .map(|&(id, idx)| (Some(id), idx));
+        for (id, idx) in indices.chain(extra) {
+            let Some(member) = self.members.get_by_index(idx) else {
+                continue;
+            };
+            if id.is_some_and(|id| id != member.id) || !member.is_visible_to(visibility) {
+                continue;
+            }
+            remaining.push(Entry {
+                id: member.id,
+                alive: member.is_alive(),
+            });
+        }
+        remaining
+    }
+
+    /// Returns whether the member with the id is a late member that the iterator will
+    /// yield.
+    pub(super) fn will_yield_late(
+        &self,
+        iter: &Range<usize>,
+        visibility: &Visibility<I>,
+        id: I,
+    ) -> bool {
+        if visibility.late == FINISHED {
+            return false;
+        }
+        let late = self.late.get(visibility.late..).unwrap_or_default();
+        visibility
+            .extra
+            .iter()
+            .chain(late.iter().filter(|(_, idx)| !iter.contains(idx)))
+            .any(|&(i, idx)| {
+                i == id
+                    && matches!(
+                        self.members.get_by_index(idx),
+                        Some(m) if m.id == id && m.is_visible_to(visibility),
+                    )
+            })
+    }
+}
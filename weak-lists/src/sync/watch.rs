@@ -0,0 +1,130 @@
+use crate::{id::EntryId, sync::WeakListData};
+#[cfg(feature = "std")]
+use {crate::sync::SyncWeakList, core::time::Duration};
+
+#[cfg(feature = "std")]
+impl<T, I> SyncWeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Blocks until the membership of the list changes or the timeout elapses.
+    ///
+    /// The membership changes when an element is attached to or detached from the list or
+    /// when the list is cleared. Targets being dropped do not count as changes.
+    ///
+    /// Returns `true` if the membership changed and `false` if the timeout elapsed.
+    ///
+    /// Changes that happened before this function was called are not observed. Callers
+    /// should therefore re-check the state of the list in a loop.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{sync::Arc, thread, time::Duration};
+    /// use weak_lists::{SyncWeakList, SyncWeakListElement};
+    ///
+    /// let list = Arc::new(SyncWeakList::default());
+    /// assert!(!list.wait_for_change(Duration::from_millis(1)));
+    /// let list2 = list.clone();
+    /// let thread = thread::spawn(move || {
+    ///     let entry = Arc::new(1);
+    ///     let element = SyncWeakListElement::new(Arc::downgrade(&entry));
+    ///     element.attach(&list2);
+    ///     (entry, element)
+    /// });
+    /// while list.iter().next().is_none() {
+    ///     list.wait_for_change(Duration::from_millis(100));
+    /// }
+    /// thread.join().unwrap();
+    /// ```
+    pub fn wait_for_change(&self, timeout: Duration) -> bool {
+        let mut data = self.data.lock();
+        let generation = data.generation;
+        let change = data.change.clone();
+        let res = change.wait_while_for(&mut data, |d| d.generation == generation, timeout);
+        !res.timed_out()
+    }
+
+    /// Blocks until no iterator over the list exists.
+    ///
+    /// This can be used during teardown to ensure that no iteration is in progress before
+    /// resources used by the elements are destroyed. New iterators might be created as
+    /// soon as this function returns.
+    ///
+    /// Calling this function while the current thread holds an iterator over the list
+    /// deadlocks. See [quiesce_timeout](Self::quiesce_timeout) for a version with a
+    /// timeout.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{sync::Arc, thread, time::Duration};
+    /// use weak_lists::SyncWeakList;
+    ///
+    /// let list = Arc::new(SyncWeakList::<u32>::default());
+    /// let iter = list.iter_owned();
+    /// let thread = thread::spawn(move || {
+    ///     thread::sleep(Duration::from_millis(10));
+    ///     drop(iter);
+    /// });
+    /// list.quiesce();
+    /// assert!(!list.is_iterating());
+    /// thread.join().unwrap();
+    /// ```
+    pub fn quiesce(&self) {
+        let mut data = self.data.lock();
+        let change = data.change.clone();
+        change.wait_while(&mut data, |d| d.active_iterators > 0);
+    }
+
+    /// Blocks until no iterator over the list exists or the timeout elapses.
+    ///
+    /// Returns `true` if no iterator exists and `false` if the timeout elapsed. See
+    /// [quiesce](Self::quiesce) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use weak_lists::SyncWeakList;
+    ///
+    /// let list = SyncWeakList::<u32>::default();
+    /// let iter = list.iter();
+    /// assert!(!list.quiesce_timeout(Duration::from_millis(1)));
+    /// drop(iter);
+    /// assert!(list.quiesce_timeout(Duration::from_millis(1)));
+    /// ```
+    pub fn quiesce_timeout(&self, timeout: Duration) -> bool {
+        let mut data = self.data.lock();
+        let change = data.change.clone();
+        let res = change.wait_while_for(&mut data, |d| d.active_iterators > 0, timeout);
+        !res.timed_out()
+    }
+}
+
+impl<T, I> WeakListData<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Records a membership change and wakes up threads blocked in
+    /// [wait_for_change](crate::sync::SyncWeakList::wait_for_change).
+    pub(super) fn changed(&mut self) {
+        #[cfg(feature = "std")]
+        {
+            self.generation += 1;
+            self.change.notify_all();
+        }
+    }
+
+    /// Unregisters an iterator and wakes up threads blocked in
+    /// [quiesce](crate::sync::SyncWeakList::quiesce) if it was the last one.
+    pub(super) fn iterator_dropped(&mut self) {
+        self.active_iterators -= 1;
+        #[cfg(feature = "std")]
+        if self.active_iterators == 0 {
+            self.change.notify_all();
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Helpers for testing code built on top of this crate.
+//!
+//! This module is only available with the `test-util` feature.
+
+#[cfg(test)]
+mod tests;
+
+use {
+    crate::unsync::{WeakList, WeakListElement},
+    alloc::{rc::Weak, vec::Vec},
+    core::{
+        cell::RefCell,
+        fmt::{Debug, Formatter},
+    },
+};
+
+/// Asserts that the live entries of a list are exactly the given targets.
+///
+/// The list can be a [WeakList] or a [SyncWeakList](crate::SyncWeakList). See
+/// [members_equal](WeakList::members_equal) for how the targets are compared.
+///
+/// # Examples
+///
+/// ```
+/// use {
+///     std::rc::Rc,
+///     weak_lists::{assert_members, WeakList, WeakListElement},
+/// };
+///
+/// let list = WeakList::default();
+/// let entries = [Rc::new(0), Rc::new(1)];
+/// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+/// elements[1].attach(&list);
+/// assert_members!(list, [entries[1]]);
+/// ```
+#[macro_export]
+macro_rules! assert_members {
+    ($list:expr, [$($target:expr),* $(,)?] $(,)?) => {
+        assert!(
+            $list.members_equal(&[$(::core::clone::Clone::clone(&$target)),*]),
+            "the entries of `{}` are not the expected targets",
+            ::core::stringify!($list),
+        )
+    };
+}
+
+/// A call recorded by a [MockElement].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MockCall {
+    /// The element was attached to a list.
+    Attach,
+    /// The element was detached from its list.
+    Detach,
+}
+
+/// A list element that records the calls made to it.
+///
+/// # Examples
+///
+/// ```
+/// use {
+///     std::rc::Rc,
+///     weak_lists::{
+///         test_util::{MockCall, MockElement},
+///         WeakList,
+///     },
+/// };
+///
+/// let list = WeakList::default();
+/// let entry = Rc::new(1);
+/// let element = MockElement::new(Rc::downgrade(&entry));
+/// element.attach(&list);
+/// element.detach();
+/// assert_eq!(element.calls(), [MockCall::Attach, MockCall::Detach]);
+/// ```
+pub struct MockElement<T>
+where
+    T: ?Sized,
+{
+    element: WeakListElement<T>,
+    calls: RefCell<Vec<MockCall>>,
+}
+
+impl<T> MockElement<T>
+where
+    T: ?Sized,
+{
+    /// Creates a new mock element.
+    pub fn new(t: Weak<T>) -> Self {
+        Self {
+            element: WeakListElement::new(t),
+            calls: Default::default(),
+        }
+    }
+
+    /// Attaches the element to a list and records the call.
+    pub fn attach(&self, to: &WeakList<T>) {
+        self.calls.borrow_mut().push(MockCall::Attach);
+        self.element.attach(to);
+    }
+
+    /// Detaches the element from its list and records the call.
+    pub fn detach(&self) {
+        self.calls.borrow_mut().push(MockCall::Detach);
+        self.element.detach();
+    }
+
+    /// Returns the calls recorded so far.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.calls.borrow().clone()
+    }
+
+    /// Returns the underlying element.
+    pub fn element(&self) -> &WeakListElement<T> {
+        &self.element
+    }
+}
+
+impl<T> Debug for MockElement<T>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MockElement")
+            .field("element", &self.element)
+            .field("calls", &self.calls.borrow())
+            .finish()
+    }
+}
@@ -0,0 +1,33 @@
+use {
+    crate::{
+        assert_members,
+        test_util::{MockCall, MockElement},
+        WeakList,
+    },
+    alloc::rc::Rc,
+};
+
+#[test]
+fn assert_members_succeeds() {
+    let list = WeakList::default();
+    let entries = [Rc::new(0), Rc::new(1)];
+    let elements = entries
+        .each_ref()
+        .map(|e| MockElement::new(Rc::downgrade(e)));
+    assert_members!(list, []);
+    elements[0].attach(&list);
+    elements[1].attach(&list);
+    assert_members!(list, [entries[1], entries[0]]);
+    elements[0].detach();
+    assert_members!(list, [entries[1]]);
+    assert_eq!(elements[0].calls(), [MockCall::Attach, MockCall::Detach]);
+    assert_eq!(elements[1].calls(), [MockCall::Attach]);
+}
+
+#[test]
+#[should_panic = "the entries of `list` are not the expected targets"]
+fn assert_members_fails() {
+    let list = WeakList::default();
+    let entry = Rc::new(0);
+    assert_members!(list, [entry]);
+}
@@ -1,14 +1,56 @@
 //! The single-threaded version of the list.
 
+mod aging;
+mod builder;
+mod cascade;
+mod cells;
+mod clock;
+mod deferred;
+mod detach;
+mod dispatch;
+mod drain;
 mod element;
+mod enabled;
+mod epoch;
 mod iter;
+mod limited;
 mod list;
+mod multi;
+mod once;
+mod paired;
+mod pin;
+mod policy;
+pub mod raw;
+mod reattach;
+mod recency;
+mod retained;
+mod set;
+mod static_list;
+mod targets;
 #[cfg(test)]
 mod tests;
+mod view;
+mod visibility;
+
+pub use set::iter_chain;
 
 use {
-    alloc::rc::{Rc, Weak},
-    core::{cell::UnsafeCell, ops::Range},
+    crate::{
+        id::{EntryId, MembershipId},
+        policy::LivenessPolicy,
+    },
+    alloc::{
+        boxed::Box,
+        collections::BTreeMap,
+        rc::{Rc, Weak},
+        vec::{self, Vec},
+    },
+    core::{
+        any::Any,
+        cell::{Cell, RefCell, UnsafeCell},
+        marker::PhantomData,
+        ops::Range,
+    },
     stable_map::StableMap,
 };
 
@@ -20,6 +62,8 @@ use {
 ///
 /// This list supports concurrent iteration and modification.
 ///
+/// The type parameter `I` is the type of the ids of the entries. See [EntryId].
+///
 /// # Examples
 ///
 /// ```
@@ -85,20 +129,118 @@ use {
 /// // Callback 0 invoked
 /// // Callback 2 invoked
 /// ```
-pub struct WeakList<T>
+pub struct WeakList<T, I = u64>
 where
     T: ?Sized,
+    I: EntryId,
 {
-    data: Rc<UnsafeCell<WeakListData<T>>>,
+    data: Rc<UnsafeCell<WeakListData<T, I>>>,
+}
+
+/// A builder for [WeakList]s.
+///
+/// # Examples
+///
+/// ```
+/// use weak_lists::unsync::{WeakListBuilder, WeakList};
+///
+/// let list: WeakList<u32> = WeakListBuilder::default().capacity(1024).build();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct WeakListBuilder {
+    capacity: usize,
+    #[cfg(feature = "deterministic")]
+    first_id: u64,
+    track_invocations: bool,
+    max_entries_per_target: Option<usize>,
+    #[cfg(feature = "debug-registry")]
+    debug_name: Option<alloc::string::String>,
 }
 
-struct WeakListData<T>
+struct WeakListData<T, I>
 where
     T: ?Sized,
+    I: EntryId,
 {
-    next_id: u64,
+    next_id: I,
+    /// The sequence number of the next attachment. See
+    /// [attach_seq](WeakListElement::attach_seq).
+    next_seq: u64,
     active_iterators: usize,
-    members: StableMap<u64, Weak<T>>,
+    members: StableMap<I, Member<T, I>>,
+    /// The index at which [dispatch_some](WeakList::dispatch_some) resumes.
+    cursor: usize,
+    /// Whether the list compacts itself when iteration starts. See [raw].
+    auto_compact: bool,
+    /// See [compaction_epoch](WeakList::compaction_epoch).
+    compaction_epoch: u64,
+    /// The number of iterations that have been started. See [dead_age_stats](WeakList::dead_age_stats).
+    iterations: u64,
+    /// The number of members that an iterator has skipped because their targets had
+    /// been dropped. See [live_len_hint](WeakList::live_len_hint).
+    known_dead: usize,
+    /// See [set_clock](WeakList::set_clock).
+    clock: Option<Rc<dyn Fn() -> u64>>,
+    /// The members that were cleared while their elements had requested to be reattached.
+    /// See [reopen_pending](WeakList::reopen_pending).
+    pending: Vec<Member<T, I>>,
+    /// The entries that were attached by [attach_visible_now](WeakListElement::attach_visible_now)
+    /// while iterators existed, together with their indices.
+    late: Vec<(I, usize)>,
+    /// The keys that have been notified since the outermost active iteration started.
+    /// See [notify_coalesced](WeakList::notify_coalesced).
+    coalesced: Vec<u64>,
+    /// Whether the dispatch helpers record invocations. See [track_invocations](WeakListBuilder::track_invocations).
+    track_invocations: bool,
+    /// See [set_max_concurrent_iterations](WeakList::set_max_concurrent_iterations).
+    max_concurrent_iterations: Option<usize>,
+    /// See [max_entries_per_target](WeakListBuilder::max_entries_per_target).
+    max_entries_per_target: Option<usize>,
+    /// The lists that are cleared together with this list. See [add_child](WeakList::add_child).
+    children: Vec<Weak<UnsafeCell<WeakListData<T, I>>>>,
+    #[cfg(feature = "paranoid")]
+    borrowed: bool,
+}
+
+struct Member<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    id: I,
+    t: Weak<T>,
+    entry: Rc<UnsafeCell<EntryData<T, I>>>,
+    /// The time at which the first iterator that failed to upgrade `t` was created.
+    dead_since: Option<u64>,
+    /// A copy of [EntryData::reattach].
+    reattach: bool,
+    /// A copy of [EntryData::enabled].
+    enabled: bool,
+    /// See [attach_seq](WeakListElement::attach_seq).
+    seq: u64,
+    /// `None` if all fields of the extra state have their default values.
+    extra: Option<Box<MemberExtra>>,
+}
+
+/// The state of a [Member] that is only used by some features.
+///
+/// It is stored separately so that members that do not use these features stay small.
+#[derive(Clone, Default)]
+struct MemberExtra {
+    /// See [attach_with_group](WeakListElement::attach_with_group).
+    group: Option<u32>,
+    /// See [with_keepalive](WeakListElement::with_keepalive).
+    keepalive: Option<Weak<dyn Any>>,
+    /// See [invocation_stats](WeakList::invocation_stats).
+    invocations: InvocationStats,
+    /// The first iteration that may yield the member. See
+    /// [attach_visible_next](WeakListElement::attach_visible_next).
+    visible_from: u64,
+    /// Whether the member is removed once it has been yielded. See
+    /// [attach_once](WeakListElement::attach_once).
+    once: bool,
+    /// See [attach_with_epoch](WeakListElement::attach_with_epoch).
+    epoch: u64,
 }
 
 /// An element that can be inserted into a weak list.
@@ -107,30 +249,511 @@ where
 /// detaches itself from the previous list.
 ///
 /// When this object is dropped, it detaches itself from its current list.
-pub struct WeakListElement<T>
+///
+/// If `T` is sized, this object has the size of four pointers. Additionally, each
+/// element allocates its shared state in a separate `Rc` whose size depends on the id
+/// type. On 64-bit targets with the default id type, this allocation has 40 bytes.
+/// Applications with many elements can use a smaller id type such as `u32` to reduce it.
+/// See [new_with_id_type](Self::new_with_id_type).
+///
+/// While the element is attached, the list stores a record of 64 bytes for it on 64-bit
+/// targets. Attaching with a group, a keepalive object, a visibility or epoch
+/// restriction, or [once](Self::attach_once), and tracking invocations, allocates
+/// another block of state for this record.
+pub struct WeakListElement<T, I = u64>
 where
     T: ?Sized,
+    I: EntryId,
 {
     t: Weak<T>,
-    data: UnsafeCell<EntryData<T>>,
+    /// See [with_keepalive](Self::with_keepalive).
+    keepalive: Option<Weak<dyn Any>>,
+    data: Rc<UnsafeCell<EntryData<T, I>>>,
+}
+
+struct EntryData<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    id: I,
+    owner: Weak<UnsafeCell<WeakListData<T, I>>>,
+    /// See [set_reattach_on_clear](WeakListElement::set_reattach_on_clear).
+    reattach: bool,
+    /// See [defer_attach](WeakListElement::defer_attach).
+    deferred: bool,
+    /// See [set_enabled](WeakListElement::set_enabled).
+    enabled: bool,
+}
+
+/// A read-only view of a [WeakList].
+///
+/// This object is created by calling [view](WeakList::view). It provides the functions of
+/// the list that only observe the entries, such as [iter](Self::iter), but no way to
+/// attach elements to the list or to detach them. This object can be copied freely.
+pub struct WeakListView<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    list: &'a WeakList<T, I>,
 }
 
-struct EntryData<T>
+/// A handle that can detach a [WeakListElement] but not attach it.
+///
+/// This object is created by calling [detach_handle](WeakListElement::detach_handle). It does not
+/// keep the element alive.
+pub struct DetachHandle<T, I = u64>
 where
     T: ?Sized,
+    I: EntryId,
 {
-    id: u64,
-    owner: Weak<UnsafeCell<WeakListData<T>>>,
+    data: Weak<UnsafeCell<EntryData<T, I>>>,
 }
 
 /// An iterator over list elements.
 ///
 /// This object is created by calling [iter](WeakList::iter) or by using the
 /// [IntoIterator] implementation of `&WeakList`.
-pub struct Iter<'a, T>
+pub struct Iter<'a, T, I = u64>
 where
     T: ?Sized,
+    I: EntryId,
 {
     iter: Range<usize>,
-    data: &'a UnsafeCell<WeakListData<T>>,
+    data: &'a UnsafeCell<WeakListData<T, I>>,
+    skipped: usize,
+    peeked: Option<(usize, I, Rc<T>)>,
+    visibility: Visibility<I>,
+    /// See [skip_target](Self::skip_target).
+    excluded: Option<Weak<T>>,
+}
+
+/// An iterator over list elements and their slots.
+///
+/// This object is created by calling [iter_indexed](WeakList::iter_indexed).
+pub struct IndexedIter<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    iter: Iter<'a, T, I>,
+}
+
+/// An iterator over a limited number of list elements.
+///
+/// This object is created by calling [iter_limited](WeakList::iter_limited).
+pub struct LimitedIter<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    iter: Iter<'a, T, I>,
+    /// The index at which the iterator started.
+    start: usize,
+    /// Whether the iterator has wrapped around at the end of the list.
+    wrapped: bool,
+    /// The number of elements that the iterator may still yield.
+    remaining: usize,
+}
+
+/// An iterator over list elements that keeps the entries alive until they are yielded.
+///
+/// This object is created by calling [iter_retained](WeakList::iter_retained).
+pub struct RetainedIter<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    iter: Iter<'a, T, I>,
+    /// The targets that have not yet been yielded, keyed by the ids of their members.
+    retained: BTreeMap<I, Rc<T>>,
+}
+
+/// An iterator over list elements that yields the most recently attached entries first.
+///
+/// This object is created by calling [iter_by_recency](WeakList::iter_by_recency).
+pub struct RecencyIter<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    list: &'a WeakList<T, I>,
+    /// The ids and sequence numbers of the entries that have not yet been yielded.
+    entries: vec::IntoIter<(I, u64)>,
+}
+
+/// A list whose iterators only yield the entries accepted by a [LivenessPolicy].
+///
+/// This list wraps a [WeakList]. Elements are attached to the wrapped list, which can
+/// be accessed with [list](Self::list). See [policy](crate::policy) for details.
+pub struct PolicyWeakList<T, P, I = u64>
+where
+    T: ?Sized,
+    P: LivenessPolicy<T>,
+    I: EntryId,
+{
+    list: WeakList<T, I>,
+    policy: PhantomData<fn() -> P>,
+}
+
+/// An iterator over the entries of a [PolicyWeakList].
+///
+/// This object is created by calling [iter](PolicyWeakList::iter).
+pub struct PolicyIter<'a, T, P, I = u64>
+where
+    T: ?Sized,
+    P: LivenessPolicy<T>,
+    I: EntryId,
+{
+    iter: Iter<'a, T, I>,
+    policy: PhantomData<fn() -> P>,
+}
+
+/// An iterator over list elements that does not borrow the list.
+///
+/// This object is created by calling [iter_owned](WeakList::iter_owned).
+///
+/// If the list is dropped while this iterator exists, the iterator no longer yields any
+/// elements.
+pub struct OwnedIter<T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    iter: Range<usize>,
+    data: Weak<UnsafeCell<WeakListData<T, I>>>,
+    visibility: Visibility<I>,
+}
+
+/// An iterator over the list elements that are also elements of another list.
+///
+/// This object is created by calling [intersect_iter](WeakList::intersect_iter).
+pub struct Intersection<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    iter: Iter<'a, T, I>,
+    other: TargetSet<T>,
+}
+
+/// An iterator over the list elements that are not elements of another list.
+///
+/// This object is created by calling [difference_iter](WeakList::difference_iter).
+pub struct Difference<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    iter: Iter<'a, T, I>,
+    other: TargetSet<T>,
+}
+
+/// An iterator over the elements of two lists.
+///
+/// This object is created by calling [union_iter](WeakList::union_iter).
+pub struct Union<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    first: Iter<'a, T, I>,
+    second: Iter<'a, T, I>,
+    seen: TargetSet<T>,
+}
+
+/// An iterator over the elements of multiple lists.
+///
+/// This object is created by calling [iter_chain].
+pub struct IterChain<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    iters: Vec<Iter<'a, T, I>>,
+    seen: TargetSet<T>,
+}
+
+/// A list holding weak references to up to `N` elements without allocating.
+///
+/// Unlike [WeakList], the members of this list are stored inline. Attaching an element
+/// to this list fails if the list is full. Elements borrow the list they are attached
+/// to, therefore the list must outlive its elements.
+///
+/// Like [WeakList], this list supports concurrent iteration and modification.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use weak_lists::{
+///     unsync::{StaticWeakList, StaticWeakListElement},
+///     Error,
+/// };
+///
+/// let list = StaticWeakList::<u32, 1>::new();
+/// let entries = [Rc::new(0), Rc::new(1)];
+/// let elements = entries
+///     .each_ref()
+///     .map(|e| StaticWeakListElement::new(Rc::downgrade(e)));
+/// assert_eq!(elements[0].attach(&list), Ok(()));
+/// assert_eq!(elements[1].attach(&list), Err(Error::CapacityExceeded));
+/// elements[0].detach();
+/// assert_eq!(elements[1].attach(&list), Ok(()));
+/// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [1]);
+/// ```
+pub struct StaticWeakList<T, const N: usize>
+where
+    T: ?Sized,
+{
+    slots: UnsafeCell<[Option<Weak<T>>; N]>,
+}
+
+/// An element that can be inserted into a [StaticWeakList].
+///
+/// Each element can be attached to 0 or 1 list. Attaching it to a list automatically
+/// detaches itself from the previous list.
+///
+/// When this object is dropped, it detaches itself from its current list.
+pub struct StaticWeakListElement<'a, T, const N: usize>
+where
+    T: ?Sized,
+{
+    t: Weak<T>,
+    slot: Cell<Option<(&'a StaticWeakList<T, N>, usize)>>,
+}
+
+/// An iterator over the elements of a [StaticWeakList].
+///
+/// This object is created by calling [iter](StaticWeakList::iter) or by using the
+/// [IntoIterator] implementation of `&StaticWeakList`.
+pub struct StaticIter<'a, T, const N: usize>
+where
+    T: ?Sized,
+{
+    iter: Range<usize>,
+    list: &'a StaticWeakList<T, N>,
+}
+
+/// An iterator that removes the yielded elements from the list.
+///
+/// This object is created by calling [drain](WeakList::drain).
+pub struct Drain<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    list: &'a WeakList<T, I>,
+    iter: Iter<'a, T, I>,
+}
+
+/// An iterator that removes the elements matching a predicate from the list.
+///
+/// This object is created by calling [extract_if](WeakList::extract_if).
+pub struct ExtractIf<'a, T, F, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    list: &'a WeakList<T, I>,
+    iter: Iter<'a, T, I>,
+    pred: F,
+}
+
+/// A list holding weak references to pinned elements.
+///
+/// This list behaves like [WeakList] except that its elements can only be created from
+/// `Pin<Rc<T>>` and that its iterators yield `Pin<Rc<T>>`.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use weak_lists::unsync::{PinWeakList, PinWeakListElement};
+///
+/// let list = PinWeakList::default();
+/// let entry = Rc::pin(1);
+/// let element = PinWeakListElement::new(&entry);
+/// element.attach(&list);
+/// assert_eq!(list.iter().next(), Some(entry.clone()));
+/// ```
+pub struct PinWeakList<T>
+where
+    T: ?Sized,
+{
+    list: WeakList<T>,
+}
+
+/// An element that can be inserted into a [PinWeakList].
+///
+/// This object behaves like [WeakListElement].
+pub struct PinWeakListElement<T>
+where
+    T: ?Sized,
+{
+    element: WeakListElement<T>,
+}
+
+/// An iterator over the elements of a [PinWeakList].
+///
+/// This object is created by calling [iter](PinWeakList::iter) or by using the [IntoIterator]
+/// implementation of `&PinWeakList`.
+pub struct PinIter<'a, T>
+where
+    T: ?Sized,
+{
+    iter: Iter<'a, T>,
+}
+
+/// An element that is a member of two lists at the same time.
+///
+/// This is useful if an object has to be registered in a global list and in a list of its
+/// parent. The element is always attached to both lists or to neither of them.
+///
+/// When this object is dropped, it detaches itself from both lists.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use weak_lists::unsync::{WeakList, PairedWeakListElement};
+///
+/// let global = WeakList::default();
+/// let parent = WeakList::default();
+/// let entry = Rc::new(1);
+/// let element = PairedWeakListElement::new(Rc::downgrade(&entry));
+/// element.attach(&global, &parent);
+/// assert_eq!(global.iter().next(), Some(entry.clone()));
+/// assert_eq!(parent.iter().next(), Some(entry.clone()));
+/// element.detach();
+/// assert!(global.iter().next().is_none());
+/// assert!(parent.iter().next().is_none());
+/// ```
+pub struct PairedWeakListElement<T>
+where
+    T: ?Sized,
+{
+    global: WeakListElement<T>,
+    parent: WeakListElement<T>,
+}
+
+/// An element that is a member of any number of lists at the same time.
+///
+/// Each call to [attach](Self::attach) adds a membership and returns its id. The
+/// memberships are independent of each other and can be removed individually with
+/// [detach](Self::detach) or all at once with [detach_all](Self::detach_all). A list can
+/// contain the element multiple times.
+///
+/// When this object is dropped, it detaches itself from all lists.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use weak_lists::unsync::{MultiWeakListElement, WeakList};
+///
+/// let topics = [WeakList::default(), WeakList::default()];
+/// let entry = Rc::new(1);
+/// let element = MultiWeakListElement::new(Rc::downgrade(&entry));
+/// let ids = topics.each_ref().map(|t| element.attach(t));
+/// assert!(element.detach(ids[0]));
+/// assert!(topics[0].iter().next().is_none());
+/// assert_eq!(topics[1].iter().next(), Some(entry.clone()));
+/// element.detach_all();
+/// assert!(topics[1].iter().next().is_none());
+/// ```
+pub struct MultiWeakListElement<T>
+where
+    T: ?Sized,
+{
+    t: Weak<T>,
+    next_id: Cell<u64>,
+    memberships: RefCell<Vec<(MembershipId, WeakListElement<T>)>>,
+}
+
+/// The state that an iterator uses to yield the entries attached by
+/// [attach_visible_now](WeakListElement::attach_visible_now) while it runs.
+#[derive(Clone)]
+struct Visibility<I>
+where
+    I: EntryId,
+{
+    /// The iteration started by the iterator.
+    iteration: u64,
+    /// The number of elements of [WeakListData::late] that the iterator has processed.
+    late: usize,
+    /// The late entries that the iterator has not yet reached and would otherwise miss.
+    extra: Vec<(I, usize)>,
+    /// The time at which the iteration was started. See [set_clock](WeakList::set_clock).
+    now: u64,
+    /// The largest epoch of the entries that the iterator yields. See
+    /// [iter_epoch_at_most](WeakList::iter_epoch_at_most).
+    max_epoch: u64,
+}
+
+struct TargetSet<T>
+where
+    T: ?Sized,
+{
+    targets: BTreeMap<usize, Weak<T>>,
+}
+
+/// Statistics about the entries of a list whose targets have been dropped.
+///
+/// Ages are measured in ticks of the clock of the list. By default, the clock counts
+/// iterations. Every iterator created for the list starts a new iteration. A different
+/// clock can be set with [set_clock](WeakList::set_clock). The age of an entry is the
+/// time that has passed since the creation of the first iterator that skipped the entry.
+///
+/// This object is returned by [dead_age_stats](WeakList::dead_age_stats).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DeadAgeStats {
+    /// The number of entries whose targets have been dropped.
+    pub dead: usize,
+    /// The number of those entries that have not yet been skipped by any iterator.
+    pub unobserved: usize,
+    /// The sum of the ages of the entries that have been skipped.
+    pub total_age: u64,
+    /// The largest age of the entries that have been skipped.
+    pub max_age: u64,
+}
+
+/// How [for_each_mut](WeakList::for_each_mut) handles targets that are already borrowed.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BorrowPolicy {
+    /// The target is skipped.
+    #[default]
+    Skip,
+    /// The function panics.
+    Panic,
+}
+
+/// Statistics about the callbacks invoked for an entry by the dispatch helpers.
+///
+/// Iterations are counted as described in [DeadAgeStats].
+///
+/// This object is returned by [invocation_stats](WeakList::invocation_stats).
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct InvocationStats {
+    /// The number of times a callback has been invoked for the entry.
+    pub count: u64,
+    /// The iteration during which a callback was last invoked for the entry.
+    pub last_iteration: Option<u64>,
+}
+
+/// A panic caught while invoking a callback for a list element.
+///
+/// This object is returned by [for_each_catch_unwind](WeakList::for_each_catch_unwind).
+#[cfg(feature = "std")]
+pub struct CaughtPanic<T>
+where
+    T: ?Sized,
+{
+    /// The element for which the callback panicked.
+    pub member: Rc<T>,
+    /// The panic payload.
+    pub payload: alloc::boxed::Box<dyn core::any::Any + Send>,
 }
@@ -0,0 +1,123 @@
+use crate::{
+    id::EntryId,
+    unsync::{clock::read_clock, DeadAgeStats, WeakList, WeakListData},
+};
+
+impl<T, I> WeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns an estimate of the number of live entries in the list.
+    ///
+    /// The estimate is maintained when elements are attached and detached and when
+    /// iterators skip entries whose targets have been dropped. Computing it does not
+    /// iterate over the list. Entries whose targets have been dropped are counted until
+    /// an iterator has skipped them or they are detached. The estimate is therefore never
+    /// smaller than the number of live entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// assert_eq!(list.live_len_hint(), 2);
+    /// let [_, entry] = entries;
+    /// drop(entry);
+    /// assert_eq!(list.live_len_hint(), 2);
+    /// list.iter().for_each(drop);
+    /// assert_eq!(list.live_len_hint(), 1);
+    /// ```
+    pub fn live_len_hint(&self) -> usize {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *WeakListData::ptr(&self.data)
+        };
+        data.members.len() - data.known_dead
+    }
+
+    /// Returns statistics about the entries whose targets have been dropped.
+    ///
+    /// Such entries remain in the list until their elements are detached. These
+    /// statistics can be used to determine whether dead entries are removed promptly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entry = Rc::new(1);
+    /// let element = WeakListElement::new(Rc::downgrade(&entry));
+    /// element.attach(&list);
+    /// drop(entry);
+    /// assert_eq!(list.dead_age_stats().unobserved, 1);
+    /// list.iter().for_each(drop);
+    /// list.iter().for_each(drop);
+    /// list.iter().for_each(drop);
+    /// let stats = list.dead_age_stats();
+    /// assert_eq!(stats.dead, 1);
+    /// assert_eq!(stats.unobserved, 0);
+    /// assert_eq!(stats.max_age, 2);
+    /// ```
+    pub fn dead_age_stats(&self) -> DeadAgeStats {
+        let now = read_clock(&self.data);
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            // - The strong_count calls below only run well-known code that does not
+            //   depend on T.
+            &mut *WeakListData::ptr(&self.data)
+        };
+        let mut stats = DeadAgeStats::default();
+        for idx in 0..data.members.index_len() {
+            let Some(member) = data.members.get_by_index(idx) else {
+                continue;
+            };
+            if member.is_alive() {
+                continue;
+            }
+            stats.dead += 1;
+            match member.dead_since {
+                Some(since) => {
+                    let age = now.unwrap_or(data.iterations).saturating_sub(since);
+                    stats.total_age += age;
+                    stats.max_age = stats.max_age.max(age);
+                }
+                None => stats.unobserved += 1,
+            }
+        }
+        stats
+    }
+}
+
+impl<T, I> WeakListData<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Records that an iterator created at `now` failed to upgrade the member at the index.
+    pub(super) fn observe_dead(&mut self, idx: usize, now: u64) {
+        if let Some(member) = self.members.get_by_index_mut(idx) {
+            if member.dead_since.is_none() {
+                member.dead_since = Some(now);
+                self.known_dead += 1;
+            }
+        }
+    }
+}
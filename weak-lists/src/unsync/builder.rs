@@ -0,0 +1,254 @@
+#[cfg(feature = "debug-registry")]
+use crate::debug::registry::{self, ListStats};
+use {
+    crate::{
+        id::EntryId,
+        unsync::{WeakList, WeakListBuilder, WeakListData},
+    },
+    alloc::{
+        rc::{Rc, Weak},
+        vec::Vec,
+    },
+    core::cell::UnsafeCell,
+    stable_map::StableMap,
+};
+
+impl WeakListBuilder {
+    /// Sets the number of elements the list can hold without reallocating.
+    ///
+    /// The default is 0.
+    pub fn capacity(&mut self, capacity: usize) -> &mut Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets the id of the first entry of the list.
+    ///
+    /// Subsequent entries receive the following ids. If the id does not fit into the id type
+    /// of the list, it is truncated. The default is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{unsync::WeakListBuilder, WeakListElement};
+    ///
+    /// let list = WeakListBuilder::default().first_id(100).build();
+    /// let entry = Rc::new(1);
+    /// let element = WeakListElement::new(Rc::downgrade(&entry));
+    /// element.attach(&list);
+    /// assert_eq!(element.id(), Some(100));
+    /// ```
+    #[cfg(feature = "deterministic")]
+    pub fn first_id(&mut self, id: u64) -> &mut Self {
+        self.first_id = id;
+        self
+    }
+
+    /// Sets whether the list records the callbacks invoked by the dispatch helpers.
+    ///
+    /// If this is enabled, [for_each](WeakList::for_each), [dispatch_some](WeakList::dispatch_some),
+    /// and `for_each_catch_unwind` record for each entry how often and when they invoked
+    /// the callback. The records can be retrieved with
+    /// [invocation_stats](WeakList::invocation_stats). This is useful to find entries that
+    /// never receive callbacks.
+    ///
+    /// The default is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{unsync::WeakListBuilder, WeakListElement};
+    ///
+    /// let list = WeakListBuilder::default().track_invocations(true).build();
+    /// let entry = Rc::new(1);
+    /// let element = WeakListElement::new(Rc::downgrade(&entry));
+    /// element.attach(&list);
+    /// list.for_each(|_| ());
+    /// list.for_each(|_| ());
+    /// assert_eq!(list.invocation_stats(element.id().unwrap()).unwrap().count, 2);
+    /// ```
+    pub fn track_invocations(&mut self, track: bool) -> &mut Self {
+        self.track_invocations = track;
+        self
+    }
+
+    /// Sets the maximum number of entries of the list that may reference the same target.
+    ///
+    /// Targets are compared by identity. If an element is attached to the list while
+    /// this many entries with the same live target are attached, the element is left
+    /// detached. This protects against registering the same target repeatedly through
+    /// different elements. Checking the limit requires a scan of the list.
+    ///
+    /// The limit applies to the `attach` functions of
+    /// [WeakListElement](crate::unsync::WeakListElement) and
+    /// [PairedWeakListElement](crate::unsync::PairedWeakListElement). By default, there
+    /// is no limit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{unsync::WeakListBuilder, Error, WeakListElement};
+    ///
+    /// let list = WeakListBuilder::default().max_entries_per_target(1).build();
+    /// let entry = Rc::new(1);
+    /// let elements = [(); 2].map(|_| WeakListElement::new(Rc::downgrade(&entry)));
+    /// assert_eq!(elements[0].try_attach(&list), Ok(()));
+    /// assert_eq!(elements[1].try_attach(&list), Err(Error::TargetLimitReached));
+    /// assert_eq!(list.iter().count(), 1);
+    /// ```
+    pub fn max_entries_per_target(&mut self, max: usize) -> &mut Self {
+        self.max_entries_per_target = Some(max);
+        self
+    }
+
+    /// Sets the name of the list in the output of [dump_all](crate::dump_all).
+    ///
+    /// By default, the list has no name.
+    ///
+    /// This function is only available if the `debug-registry` feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use weak_lists::unsync::{WeakListBuilder, WeakList};
+    ///
+    /// let list: WeakList<u32> = WeakListBuilder::default()
+    ///     .debug_name("callbacks")
+    ///     .build();
+    /// let mut out = String::new();
+    /// weak_lists::dump_all(&mut out).unwrap();
+    /// assert!(out.contains("WeakList \"callbacks\""));
+    /// ```
+    #[cfg(feature = "debug-registry")]
+    pub fn debug_name(&mut self, name: &str) -> &mut Self {
+        self.debug_name = Some(name.into());
+        self
+    }
+
+    /// Creates a new list.
+    pub fn build<T>(&self) -> WeakList<T>
+    where
+        T: ?Sized,
+    {
+        self.build_with_id_type()
+    }
+
+    /// Creates a new list with a custom id type.
+    ///
+    /// See [EntryId] for details.
+    pub fn build_with_id_type<T, I>(&self) -> WeakList<T, I>
+    where
+        T: ?Sized,
+        I: EntryId,
+    {
+        let list = WeakList {
+            data: Rc::new(UnsafeCell::new(WeakListData {
+                #[cfg(not(feature = "deterministic"))]
+                next_id: I::FIRST,
+                #[cfg(feature = "deterministic")]
+                next_id: I::from_u64(self.first_id),
+                next_seq: 0,
+                active_iterators: 0,
+                cursor: 0,
+                auto_compact: true,
+                compaction_epoch: 0,
+                iterations: 0,
+                known_dead: 0,
+                clock: None,
+                pending: Vec::new(),
+                late: Vec::new(),
+                coalesced: Vec::new(),
+                track_invocations: self.track_invocations,
+                max_concurrent_iterations: None,
+                max_entries_per_target: self.max_entries_per_target,
+                children: Vec::new(),
+                members: StableMap::with_capacity(self.capacity),
+                #[cfg(feature = "paranoid")]
+                borrowed: false,
+            })),
+        };
+        #[cfg(feature = "debug-registry")]
+        // SAFETY: The list is unregistered when its data is dropped.
+        unsafe {
+            registry::register(
+                false,
+                list.data.get() as usize,
+                "WeakList",
+                self.debug_name.clone(),
+                debug_stats::<T, I>,
+            );
+        }
+        list
+    }
+}
+
+impl<T, I> WeakListData<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns the number of entries with the target that can be added without exceeding
+    /// the limit set with [max_entries_per_target](WeakListBuilder::max_entries_per_target).
+    pub(super) fn target_budget(&self, t: &Weak<T>) -> usize {
+        let Some(max) = self.max_entries_per_target else {
+            return usize::MAX;
+        };
+        if t.strong_count() == 0 {
+            return usize::MAX;
+        }
+        let entries = self
+            .members
+            .values()
+            .filter(|member| Weak::ptr_eq(&member.t, t) && member.t.strong_count() > 0)
+            .count();
+        max.saturating_sub(entries)
+    }
+}
+
+#[cfg(feature = "debug-registry")]
+impl<T, I> WeakListData<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn debug_stats(&self) -> ListStats {
+        ListStats {
+            entries: self.members.len(),
+            alive: self
+                .members
+                .values()
+                .filter(|m| m.t.strong_count() > 0)
+                .count(),
+            active_iterators: self.active_iterators,
+            iterations: self.iterations,
+        }
+    }
+}
+
+#[cfg(feature = "debug-registry")]
+impl<T, I> Drop for WeakListData<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn drop(&mut self) {
+        registry::unregister(false, self as *const Self as usize);
+    }
+}
+
+/// # Safety
+///
+/// `list` must be the address of a live `WeakListData<T, I>` that is not mutably borrowed.
+#[cfg(feature = "debug-registry")]
+unsafe fn debug_stats<T, I>(list: usize) -> ListStats
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    // SAFETY: Requirement of this function.
+    let data = unsafe { &*(list as *const WeakListData<T, I>) };
+    data.debug_stats()
+}
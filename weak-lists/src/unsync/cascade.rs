@@ -0,0 +1,139 @@
+use {
+    crate::{
+        id::EntryId,
+        unsync::{Member, WeakList, WeakListData},
+    },
+    alloc::{
+        rc::{Rc, Weak},
+        vec::Vec,
+    },
+    core::{cell::UnsafeCell, mem, ptr},
+};
+
+impl<T, I> WeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Makes `child` a dependent of this list.
+    ///
+    /// When this list is cleared or dropped, `child` is cleared as well. This applies
+    /// recursively to the dependents of `child`. The list only holds a weak reference to
+    /// `child`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let server = WeakList::default();
+    /// let connection = WeakList::default();
+    /// server.add_child(&connection);
+    /// let entry = Rc::new(1);
+    /// let element = WeakListElement::new(Rc::downgrade(&entry));
+    /// element.attach(&connection);
+    /// drop(server);
+    /// assert!(connection.iter().next().is_none());
+    /// ```
+    pub fn add_child(&self, child: &WeakList<T, I>) {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *WeakListData::ptr(&self.data)
+        };
+        data.children.retain(|c| c.strong_count() > 0);
+        data.children.push(Rc::downgrade(&child.data));
+    }
+
+    /// Removes `child` from the dependents of this list.
+    ///
+    /// See [add_child](Self::add_child).
+    pub fn remove_child(&self, child: &WeakList<T, I>) {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *WeakListData::ptr(&self.data)
+        };
+        data.children
+            .retain(|c| c.strong_count() > 0 && !ptr::eq(c.as_ptr(), Rc::as_ptr(&child.data)));
+    }
+}
+
+/// Clears the list and, recursively, its dependents.
+///
+/// Returns the members that were removed from this list. Their elements have been
+/// detached.
+pub(super) fn clear<T, I>(data: &UnsafeCell<WeakListData<T, I>>) -> Vec<Member<T, I>>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    let (removed, children) = {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to data. This applies to all code that
+            //   creates references to data.
+            // - Therefore, this is an exclusive reference to data.
+            // - In particular, dropping the Weak objects below will never run the drop
+            //   impl of T itself.
+            &mut *WeakListData::ptr(data)
+        };
+        let removed = data.clear_members();
+        data.cursor = 0;
+        (removed, mem::take(&mut data.children))
+    };
+    for member in &removed {
+        member.detach_removed();
+    }
+    // The children are taken out of the list while they are being cleared. This
+    // terminates the recursion if the dependencies contain a cycle.
+    clear_children(&children);
+    let data = unsafe {
+        // SAFETY: See the previous safety comment.
+        &mut *WeakListData::ptr(data)
+    };
+    data.children = children;
+    data.children.retain(|c| c.strong_count() > 0);
+    removed
+}
+
+fn clear_children<T, I>(children: &[Weak<UnsafeCell<WeakListData<T, I>>>])
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    for child in children {
+        if let Some(child) = child.upgrade() {
+            clear(&child);
+        }
+    }
+}
+
+impl<T, I> Drop for WeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn drop(&mut self) {
+        let children: Vec<_> = {
+            let data = unsafe {
+                // SAFETY:
+                // - While we hold this reference, we do not call any functions that might
+                //   create additional references to self.data. This applies to all code
+                //   that creates references to self.data.
+                // - Therefore, this is an exclusive reference to self.data.
+                &mut *WeakListData::ptr(&self.data)
+            };
+            mem::take(&mut data.children)
+        };
+        clear_children(&children);
+    }
+}
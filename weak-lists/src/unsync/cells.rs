@@ -0,0 +1,53 @@
+use {
+    crate::{
+        id::EntryId,
+        unsync::{BorrowPolicy, WeakList},
+    },
+    core::cell::RefCell,
+};
+
+impl<T, I> WeakList<RefCell<T>, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Invokes the callback with a mutable borrow of each target of the list.
+    ///
+    /// This function behaves like [for_each](Self::for_each) but borrows each target
+    /// mutably for the duration of the callback. If a target is already borrowed, for
+    /// example because the callback is invoked recursively, `policy` determines whether
+    /// the target is skipped or whether this function panics.
+    ///
+    /// Returns the number of targets that were skipped because they were already
+    /// borrowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{cell::RefCell, rc::Rc};
+    /// use weak_lists::{unsync::BorrowPolicy, WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(RefCell::new(0)), Rc::new(RefCell::new(1))];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let borrow = entries[0].borrow();
+    /// assert_eq!(list.for_each_mut(BorrowPolicy::Skip, |e| *e += 1), 1);
+    /// drop(borrow);
+    /// assert_eq!(*entries[0].borrow(), 0);
+    /// assert_eq!(*entries[1].borrow(), 2);
+    /// ```
+    pub fn for_each_mut(&self, policy: BorrowPolicy, mut f: impl FnMut(&mut T)) -> usize {
+        let mut skipped = 0;
+        self.for_each(|cell| match policy {
+            BorrowPolicy::Skip => match cell.try_borrow_mut() {
+                Ok(mut t) => f(&mut t),
+                Err(_) => skipped += 1,
+            },
+            BorrowPolicy::Panic => f(&mut cell.borrow_mut()),
+        });
+        skipped
+    }
+}
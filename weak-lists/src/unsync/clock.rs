@@ -0,0 +1,81 @@
+use {
+    crate::{
+        id::EntryId,
+        unsync::{WeakList, WeakListData},
+    },
+    alloc::rc::Rc,
+    core::cell::UnsafeCell,
+};
+
+impl<T, I> WeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Sets the clock of the list.
+    ///
+    /// The clock returns the current time in arbitrary ticks. It is used by the time-based
+    /// features of the list such as [dead_age_stats](Self::dead_age_stats). By default,
+    /// the clock counts the iterations of the list. A custom clock can be used to measure
+    /// wall-clock time or to control time in tests. The list never calls the clock while
+    /// it is borrowed. The clock must not decrease.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{cell::Cell, rc::Rc};
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let time = Rc::new(Cell::new(100));
+    /// list.set_clock({
+    ///     let time = time.clone();
+    ///     move || time.get()
+    /// });
+    /// let entry = Rc::new(1);
+    /// let element = WeakListElement::new(Rc::downgrade(&entry));
+    /// element.attach(&list);
+    /// drop(entry);
+    /// list.iter().for_each(drop);
+    /// time.set(150);
+    /// assert_eq!(list.dead_age_stats().max_age, 50);
+    /// ```
+    pub fn set_clock(&self, clock: impl Fn() -> u64 + 'static) {
+        let old = {
+            let data = unsafe {
+                // SAFETY:
+                // - While we hold this reference, we do not call any functions that might
+                //   create additional references to self.data. This applies to all code
+                //   that creates references to self.data.
+                // - Therefore, this is an exclusive reference to self.data.
+                // - The previous clock is dropped after the reference has been released.
+                &mut *WeakListData::ptr(&self.data)
+            };
+            data.clock.replace(Rc::new(clock))
+        };
+        drop(old);
+    }
+}
+
+/// Returns the current time of the clock set with [set_clock](WeakList::set_clock).
+///
+/// Returns `None` if no clock has been set.
+pub(super) fn read_clock<T, I>(data: &UnsafeCell<WeakListData<T, I>>) -> Option<u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    let clock = {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to data. This applies to all code that
+            //   creates references to data.
+            // - Therefore, this is an exclusive reference to data.
+            // - The clock is invoked after the reference has been released.
+            &mut *WeakListData::ptr(data)
+        };
+        data.clock.clone()?
+    };
+    Some(clock())
+}
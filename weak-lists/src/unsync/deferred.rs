@@ -0,0 +1,98 @@
+use crate::{
+    error::Error,
+    id::EntryId,
+    unsync::{element::AttachOptions, WeakList, WeakListElement},
+};
+
+impl<T, I> WeakListElement<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Records that the element should be attached to a list that does not exist yet.
+    ///
+    /// The element is detached from its current list. Once the list exists,
+    /// [adopt_pending](WeakList::adopt_pending) completes the attachment. This is useful
+    /// if elements are created before the list that they are registered with. Attaching
+    /// or detaching the element cancels the deferred attachment.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let entry = Rc::new(1);
+    /// let element = WeakListElement::new(Rc::downgrade(&entry));
+    /// element.defer_attach();
+    /// assert!(element.is_attach_deferred());
+    /// let list = WeakList::default();
+    /// assert_eq!(list.adopt_pending(&element), Ok(()));
+    /// assert!(!element.is_attach_deferred());
+    /// assert_eq!(list.iter().next(), Some(entry));
+    /// ```
+    pub fn defer_attach(&self) {
+        self.detach();
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *self.data.get()
+        };
+        data.deferred = true;
+    }
+
+    /// Returns whether the element has a deferred attachment.
+    ///
+    /// See [defer_attach](Self::defer_attach).
+    pub fn is_attach_deferred(&self) -> bool {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *self.data.get()
+        };
+        data.deferred
+    }
+
+    fn set_deferred(&self, deferred: bool) {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *self.data.get()
+        };
+        data.deferred = deferred;
+    }
+}
+
+impl<T, I> WeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Completes the deferred attachment of an element.
+    ///
+    /// See [defer_attach](WeakListElement::defer_attach). The element is attached to
+    /// this list as if by [attach](WeakListElement::attach).
+    ///
+    /// Returns [Error::NotDeferred] if the element has no deferred attachment. If the
+    /// element cannot be attached, the error is returned and the attachment remains
+    /// deferred.
+    pub fn adopt_pending(&self, element: &WeakListElement<T, I>) -> Result<(), Error> {
+        if !element.is_attach_deferred() {
+            return Err(Error::NotDeferred);
+        }
+        let res = element.attach_with_options(self, AttachOptions::default());
+        if res.is_err() {
+            element.set_deferred(true);
+        }
+        res
+    }
+}
@@ -0,0 +1,78 @@
+use {
+    crate::{
+        id::EntryId,
+        unsync::{element, DetachHandle, WeakListElement},
+    },
+    alloc::rc::Rc,
+    core::fmt::{Debug, Formatter},
+};
+
+impl<T, I> WeakListElement<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns a handle that can detach this element but not attach it to a list.
+    ///
+    /// This can be handed out as a subscription token without allowing the holder to
+    /// attach the element to other lists.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entry = Rc::new(1);
+    /// let element = WeakListElement::new(Rc::downgrade(&entry));
+    /// element.attach(&list);
+    /// let handle = element.detach_handle();
+    /// handle.detach();
+    /// assert!(list.iter().next().is_none());
+    /// ```
+    pub fn detach_handle(&self) -> DetachHandle<T, I> {
+        DetachHandle {
+            data: Rc::downgrade(&self.data),
+        }
+    }
+}
+
+impl<T, I> DetachHandle<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Detaches the element from its current list.
+    ///
+    /// This has the same effect as [WeakListElement::detach]. If the element has been
+    /// attached to a different list since this handle was created, it is detached from
+    /// that list. If the element has been dropped, this function does nothing.
+    pub fn detach(&self) {
+        if let Some(data) = self.data.upgrade() {
+            element::detach(&data);
+        }
+    }
+}
+
+impl<T, I> Clone for DetachHandle<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl<T, I> Debug for DetachHandle<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DetachHandle").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,284 @@
+#[cfg(feature = "std")]
+use {crate::unsync::CaughtPanic, alloc::vec::Vec, core::panic::AssertUnwindSafe, std::panic};
+use {
+    crate::{
+        id::EntryId,
+        unsync::{InvocationStats, Iter, TargetSet, WeakList, WeakListData},
+    },
+    alloc::rc::Rc,
+};
+
+impl<T, I> WeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Invokes the callback for each entry of the list.
+    ///
+    /// This function provides the same guarantees as [iter](Self::iter).
+    ///
+    /// Entries whose targets have already been dropped are skipped. The number of such
+    /// entries is returned. Use [is_target_alive] to validate an individual element.
+    ///
+    /// [is_target_alive]: crate::unsync::WeakListElement::is_target_alive
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{cell::Cell, rc::Rc};
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entry = Rc::new(1);
+    /// let element = WeakListElement::new(Rc::downgrade(&entry));
+    /// element.attach(&list);
+    /// let sum = Cell::new(0);
+    /// assert_eq!(list.for_each(|e| sum.set(sum.get() + *e)), 0);
+    /// assert_eq!(sum.get(), 1);
+    /// drop(entry);
+    /// assert_eq!(list.for_each(|e| sum.set(sum.get() + *e)), 1);
+    /// ```
+    pub fn for_each(&self, f: impl FnMut(Rc<T>)) -> usize {
+        self.for_each_in(self.iter(), f)
+    }
+
+    /// Invokes the callback for each entry yielded by the iterator.
+    fn for_each_in(&self, mut iter: Iter<'_, T, I>, mut f: impl FnMut(Rc<T>)) -> usize {
+        while let Some((id, t)) = iter.next_with_id() {
+            self.record_invocation(id);
+            f(t);
+        }
+        iter.skipped()
+    }
+
+    /// Invokes the callback at most once for each target of the list.
+    ///
+    /// This function behaves like [for_each](Self::for_each) but deduplicates the
+    /// targets by identity. Even if the callback detaches and reattaches elements, or if
+    /// multiple elements share a target, no target is invoked more than once per call.
+    ///
+    /// Returns the number of invocations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entry = Rc::new(1);
+    /// let elements = [(); 2].map(|_| WeakListElement::new(Rc::downgrade(&entry)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let mut invoked = 0;
+    /// let n = list.dispatch_exactly_once(|_| {
+    ///     invoked += 1;
+    ///     elements[1].detach();
+    ///     elements[1].attach(&list);
+    /// });
+    /// assert_eq!(n, 1);
+    /// assert_eq!(invoked, 1);
+    /// ```
+    pub fn dispatch_exactly_once(&self, mut f: impl FnMut(Rc<T>)) -> usize {
+        let mut iter = self.iter();
+        let mut seen = TargetSet::default();
+        let mut invoked = 0;
+        while let Some((id, t)) = iter.next_with_id() {
+            if !seen.insert(&t) {
+                continue;
+            }
+            self.record_invocation(id);
+            f(t);
+            invoked += 1;
+        }
+        invoked
+    }
+
+    /// Invokes the callback for at most `n` entries of the list.
+    ///
+    /// The list remembers where the previous call stopped and the next call resumes from
+    /// there, wrapping around at the end of the list. This can be used to spread the work
+    /// over multiple calls.
+    ///
+    /// Returns the number of entries for which the callback was invoked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1), Rc::new(2)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let mut seen = vec![];
+    /// assert_eq!(list.dispatch_some(2, |e| seen.push(*e)), 2);
+    /// assert_eq!(seen, [0, 1]);
+    /// assert_eq!(list.dispatch_some(2, |e| seen.push(*e)), 2);
+    /// assert_eq!(seen, [0, 1, 2, 0]);
+    /// ```
+    pub fn dispatch_some(&self, n: usize, mut f: impl FnMut(Rc<T>)) -> usize {
+        let mut iter = self.iter_limited(n);
+        let mut dispatched = 0;
+        while let Some((id, t)) = iter.next_with_id() {
+            self.record_invocation(id);
+            f(t);
+            dispatched += 1;
+        }
+        dispatched
+    }
+
+    /// Invokes the callback for each entry of the list unless the key has already been
+    /// notified in the current iteration nesting.
+    ///
+    /// The list remembers the keys passed to this function while any of its iterations
+    /// are active, including the iteration started by this function itself. The keys are
+    /// forgotten when the next outermost iteration starts. If the key has been notified
+    /// before in the current nesting, the callback is not invoked. This prevents callback
+    /// storms when several state changes trigger the same notification, for example when
+    /// a callback causes the notification that is currently being delivered.
+    ///
+    /// To coalesce the notifications of a larger unit of work, such as a tick of an
+    /// event loop, hold an iterator of the list while performing it.
+    ///
+    /// Returns `None` if the notification was suppressed. Otherwise, returns the number
+    /// of entries whose targets have already been dropped, like
+    /// [for_each](Self::for_each).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::{cell::Cell, rc::Rc};
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// const REDRAW: u64 = 0;
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let calls = Cell::new(0);
+    /// let tick = list.iter();
+    /// for _ in 0..3 {
+    ///     list.notify_coalesced(REDRAW, |_| calls.set(calls.get() + 1));
+    /// }
+    /// drop(tick);
+    /// assert_eq!(calls.get(), 2);
+    /// let res = list.notify_coalesced(REDRAW, |_| calls.set(calls.get() + 1));
+    /// assert_eq!(res, Some(0));
+    /// assert_eq!(calls.get(), 4);
+    /// ```
+    pub fn notify_coalesced(&self, key: u64, f: impl FnMut(Rc<T>)) -> Option<usize> {
+        let iter = self.iter();
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *WeakListData::ptr(&self.data)
+        };
+        if data.coalesced.contains(&key) {
+            return None;
+        }
+        data.coalesced.push(key);
+        Some(self.for_each_in(iter, f))
+    }
+
+    /// Invokes the callback for each entry of the list, catching panics.
+    ///
+    /// If the callback panics for an entry, the panic is recorded and the iteration
+    /// continues with the next entry. The recorded panics are returned.
+    ///
+    /// Otherwise, this function provides the same guarantees as [iter](Self::iter).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1), Rc::new(2)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let mut sum = 0;
+    /// let panics = list.for_each_catch_unwind(|e| {
+    ///     assert_ne!(**e, 1);
+    ///     sum += **e;
+    /// });
+    /// assert_eq!(sum, 2);
+    /// assert_eq!(panics.len(), 1);
+    /// assert_eq!(*panics[0].member, 1);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn for_each_catch_unwind(&self, mut f: impl FnMut(&Rc<T>)) -> Vec<CaughtPanic<T>> {
+        let mut panics = Vec::new();
+        let mut iter = self.iter();
+        while let Some((id, member)) = iter.next_with_id() {
+            self.record_invocation(id);
+            if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| f(&member))) {
+                panics.push(CaughtPanic { member, payload });
+            }
+        }
+        panics
+    }
+
+    /// Returns the callbacks invoked for the entry with the given id.
+    ///
+    /// Returns `None` if the list does not contain an entry with this id or if the list
+    /// does not record invocations. See
+    /// [track_invocations](crate::unsync::WeakListBuilder::track_invocations).
+    pub fn invocation_stats(&self, id: I) -> Option<InvocationStats> {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *WeakListData::ptr(&self.data)
+        };
+        if !data.track_invocations {
+            return None;
+        }
+        Some(data.members.get(&id)?.invocations())
+    }
+
+    fn record_invocation(&self, id: I) {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *WeakListData::ptr(&self.data)
+        };
+        data.record_invocation(id);
+    }
+}
+
+impl<T, I> WeakListData<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Records that a dispatch helper is about to invoke a callback for the entry.
+    fn record_invocation(&mut self, id: I) {
+        if !self.track_invocations {
+            return;
+        }
+        if let Some(member) = self.members.get_mut(&id) {
+            let invocations = &mut member.extra_mut().invocations;
+            invocations.count += 1;
+            invocations.last_iteration = Some(self.iterations);
+        }
+    }
+}
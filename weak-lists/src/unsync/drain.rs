@@ -0,0 +1,177 @@
+use {
+    crate::{
+        id::EntryId,
+        unsync::{Drain, ExtractIf, WeakList, WeakListData},
+    },
+    alloc::{rc::Rc, vec::Vec},
+    core::{
+        fmt::{Debug, Formatter},
+        iter::FusedIterator,
+    },
+};
+
+impl<T, I> WeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Creates an iterator that detaches each yielded element from the list.
+    ///
+    /// This iterator provides the same guarantees as [iter](Self::iter). When the iterator
+    /// is dropped, the elements it has not yet yielded are detached as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1), Rc::new(2)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let mut drain = list.drain();
+    /// assert_eq!(drain.next(), Some(entries[0].clone()));
+    /// drop(drain);
+    /// assert!(list.iter().next().is_none());
+    /// ```
+    pub fn drain(&self) -> Drain<'_, T, I> {
+        Drain {
+            list: self,
+            iter: self.iter(),
+        }
+    }
+
+    /// Creates an iterator that detaches and yields the elements matching the predicate.
+    ///
+    /// Elements for which the predicate returns `false` stay attached to the list. If the
+    /// iterator is dropped before it is exhausted, the remaining elements stay attached to
+    /// the list as well.
+    ///
+    /// Otherwise, this iterator provides the same guarantees as [iter](Self::iter).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1), Rc::new(2)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let even: Vec<_> = list.extract_if(|e| **e % 2 == 0).map(|e| *e).collect();
+    /// assert_eq!(even, [0, 2]);
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [1]);
+    /// ```
+    pub fn extract_if<F>(&self, pred: F) -> ExtractIf<'_, T, F, I>
+    where
+        F: FnMut(&Rc<T>) -> bool,
+    {
+        ExtractIf {
+            list: self,
+            iter: self.iter(),
+            pred,
+        }
+    }
+}
+
+impl<T, I> Drop for Drain<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn drop(&mut self) {
+        let ids: Vec<_> = {
+            let data = unsafe {
+                // SAFETY:
+                // - While we hold this reference, we do not call any functions that might
+                //   create additional references to self.list.data. This applies to all
+                //   code that creates references to self.list.data.
+                // - Therefore, this is an exclusive reference to self.list.data.
+                &mut *WeakListData::ptr(&self.list.data)
+            };
+            self.iter
+                .iter
+                .clone()
+                .filter_map(|idx| data.members.get_by_index(idx))
+                .map(|member| member.id)
+                .collect()
+        };
+        for id in ids {
+            self.list.detach_member(id);
+        }
+    }
+}
+
+impl<T, I> Iterator for Drain<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    type Item = Rc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (id, t) = self.iter.next_with_id()?;
+        self.list.detach_member(id);
+        Some(t)
+    }
+}
+
+impl<T, I> Debug for Drain<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Drain").finish_non_exhaustive()
+    }
+}
+
+impl<T, I> FusedIterator for Drain<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+}
+
+impl<T, F, I> Iterator for ExtractIf<'_, T, F, I>
+where
+    T: ?Sized,
+    F: FnMut(&Rc<T>) -> bool,
+    I: EntryId,
+{
+    type Item = Rc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((id, t)) = self.iter.next_with_id() {
+            if (self.pred)(&t) {
+                self.list.detach_member(id);
+                return Some(t);
+            }
+        }
+        None
+    }
+}
+
+impl<T, F, I> Debug for ExtractIf<'_, T, F, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ExtractIf").finish_non_exhaustive()
+    }
+}
+
+impl<T, F, I> FusedIterator for ExtractIf<'_, T, F, I>
+where
+    T: ?Sized,
+    F: FnMut(&Rc<T>) -> bool,
+    I: EntryId,
+{
+}
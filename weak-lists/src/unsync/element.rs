@@ -1,10 +1,20 @@
 use {
-    crate::unsync::{EntryData, WeakList, WeakListElement},
-    alloc::rc::{Rc, Weak},
+    crate::{
+        error::Error,
+        id::EntryId,
+        unsync::{
+            visibility::Visible, EntryData, InvocationStats, Member, MemberExtra, WeakList,
+            WeakListData, WeakListElement,
+        },
+    },
+    alloc::{
+        boxed::Box,
+        rc::{Rc, Weak},
+    },
     core::{
         cell::UnsafeCell,
         fmt::{Debug, Formatter},
-        mem,
+        mem, ptr,
     },
 };
 
@@ -35,24 +45,158 @@ where
     /// Since only weak references are stored, this does not create any actual reference
     /// cycles.
     pub fn new(t: Weak<T>) -> Self {
+        Self::new_with_id_type(t)
+    }
+
+    /// Creates a new list element from a strong reference.
+    ///
+    /// This is a shorthand for `WeakListElement::new(Rc::downgrade(t))` that additionally
+    /// performs an unsizing coercion. This is useful when `T` is a trait object and the
+    /// type of the target is only known to implement that trait.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// #![feature(unsize)]
+    ///
+    /// use std::{marker::Unsize, rc::Rc};
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// trait Callback {}
+    ///
+    /// fn register<U>(list: &WeakList<dyn Callback>, t: &Rc<U>) -> WeakListElement<dyn Callback>
+    /// where
+    ///     U: Unsize<dyn Callback>,
+    /// {
+    ///     let element = WeakListElement::from_rc(t);
+    ///     element.attach(list);
+    ///     element
+    /// }
+    /// ```
+    #[cfg(feature = "unsize")]
+    pub fn from_rc<U>(t: &Rc<U>) -> Self
+    where
+        U: ?Sized + core::marker::Unsize<T>,
+    {
+        let t: Weak<U> = Rc::downgrade(t);
+        Self::new(t)
+    }
+
+    /// Creates two list elements for different trait objects from one strong reference.
+    ///
+    /// This is useful when the same object should be registered in lists of different
+    /// trait objects. Both elements hold a weak reference to the same allocation. Each
+    /// element can be attached to one list of its type.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// #![feature(unsize)]
+    ///
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// trait Draw {}
+    /// trait Input {}
+    ///
+    /// struct Button;
+    ///
+    /// impl Draw for Button {}
+    /// impl Input for Button {}
+    ///
+    /// let draw = WeakList::<dyn Draw>::default();
+    /// let input = WeakList::<dyn Input>::default();
+    /// let button = Rc::new(Button);
+    /// let (a, b) = WeakListElement::<dyn Draw>::from_rc_pair::<_, dyn Input>(&button);
+    /// a.attach(&draw);
+    /// b.attach(&input);
+    /// assert_eq!(draw.iter().count(), 1);
+    /// assert_eq!(input.iter().count(), 1);
+    /// ```
+    #[cfg(feature = "unsize")]
+    pub fn from_rc_pair<U, V>(t: &Rc<U>) -> (Self, WeakListElement<V>)
+    where
+        U: ?Sized + core::marker::Unsize<T> + core::marker::Unsize<V>,
+        V: ?Sized,
+    {
+        (Self::from_rc(t), WeakListElement::from_rc(t))
+    }
+}
+
+impl<T, I> WeakListElement<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Creates a new list element for a list with a custom id type.
+    ///
+    /// This function behaves like [new](WeakListElement::new). See [EntryId] for details.
+    pub fn new_with_id_type(t: Weak<T>) -> Self {
         Self {
             t,
-            data: UnsafeCell::new(EntryData {
-                id: 0,
+            keepalive: None,
+            data: Rc::new(UnsafeCell::new(EntryData {
+                id: I::FIRST,
                 owner: Default::default(),
-            }),
+                reattach: false,
+                deferred: false,
+                enabled: true,
+            })),
         }
     }
 
+    /// Creates a new list element whose entry is only valid while another object is alive.
+    ///
+    /// This function behaves like [new](WeakListElement::new). Additionally, iterators skip the
+    /// entry if `keepalive` cannot be upgraded. This is useful if the target of the element is
+    /// only valid while another object, such as a session, is alive.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let callback = Rc::new(1);
+    /// let session = Rc::new("session");
+    /// let element = WeakListElement::with_keepalive(Rc::downgrade(&callback), Rc::downgrade(&session));
+    /// element.attach(&list);
+    /// assert_eq!(list.iter().next(), Some(callback.clone()));
+    /// drop(session);
+    /// assert!(list.iter().next().is_none());
+    /// ```
+    pub fn with_keepalive<K>(t: Weak<T>, keepalive: Weak<K>) -> Self
+    where
+        K: 'static,
+    {
+        let mut element = Self::new_with_id_type(t);
+        element.keepalive = Some(keepalive as _);
+        element
+    }
+
     /// Attaches the list element to a list.
     ///
     /// If this object was previously attached to a list, it is automatically detached
-    /// from that list.
+    /// from that list. This also applies if the element is already attached to `to`. The
+    /// element then receives a new id and might move to a different position in the list.
+    /// Use [ensure_attached](Self::ensure_attached) to keep the existing entry instead.
     ///
     /// The list will only hold on a weak reference to this element and vice versa.
     ///
+    /// If the element cannot be attached, for example because all ids of the [EntryId]
+    /// type of the list are in use, it is left detached. Use [try_attach](Self::try_attach)
+    /// to detect this.
+    ///
     /// Any existing iterator over the list might or might not see this element, this is
-    /// unspecified.
+    /// unspecified. Use [will_yield](crate::unsync::Iter::will_yield) to determine whether
+    /// a specific iterator will see this element.
+    /// Use [attach_visible_now](Self::attach_visible_now) or
+    /// [attach_visible_next](Self::attach_visible_next) to choose the behavior.
+    ///
+    /// If the target of this element is still being constructed by [Rc::new_cyclic],
+    /// iterators skip the element until the construction has completed. See
+    /// [adopt_on_init](WeakList::adopt_on_init) for a way to attach the element afterwards.
     ///
     /// # Examples
     ///
@@ -81,7 +225,86 @@ where
     /// assert!(clients1.iter().next().is_none());
     /// assert!(clients2.iter().next().is_some());
     /// ```
-    pub fn attach(&self, to: &WeakList<T>) {
+    pub fn attach(&self, to: &WeakList<T, I>) {
+        let _ = self.attach_with_options(to, AttachOptions::default());
+    }
+
+    /// Attaches the element to a list as a member of a group.
+    ///
+    /// This function behaves like [attach](Self::attach). Additionally, the element can
+    /// be detached together with all other members of the group by calling
+    /// [detach_group](WeakList::detach_group).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1), Rc::new(2)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// elements[0].attach_with_group(&list, 1);
+    /// elements[1].attach_with_group(&list, 2);
+    /// elements[2].attach_with_group(&list, 1);
+    /// list.detach_group(1);
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [1]);
+    /// ```
+    pub fn attach_with_group(&self, to: &WeakList<T, I>, group: u32) {
+        let _ = self.attach_with_options(
+            to,
+            AttachOptions {
+                group: Some(group),
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Attaches the element to a list using a caller-chosen key as its id.
+    ///
+    /// This function behaves like [attach](Self::attach) except that the entry receives
+    /// the id `key` instead of an id allocated by the list. The entry can then be
+    /// accessed with [get_key](WeakList::get_key) and [detach_key](WeakList::detach_key).
+    ///
+    /// Returns [Error::IdInUse] if the list already contains an entry with this id. The
+    /// element is left detached in this case.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::rc::Rc;
+    /// use weak_lists::{Error, WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// assert_eq!(elements[0].attach_keyed(&list, 7), Ok(()));
+    /// assert_eq!(elements[1].attach_keyed(&list, 7), Err(Error::IdInUse));
+    /// assert_eq!(elements[0].id(), Some(7));
+    /// assert_eq!(list.get_key(7), Some(entries[0].clone()));
+    /// ```
+    pub fn attach_keyed(&self, to: &WeakList<T, I>, key: I) -> Result<(), Error> {
+        self.attach_with_options(
+            to,
+            AttachOptions {
+                key: Some(key),
+                ..Default::default()
+            },
+        )
+    }
+
+    pub(super) fn attach_with_options(
+        &self,
+        to: &WeakList<T, I>,
+        options: AttachOptions<I>,
+    ) -> Result<(), Error> {
+        let AttachOptions {
+            group,
+            key,
+            visible,
+            once,
+            epoch,
+        } = options;
         self.detach();
         let data = unsafe {
             // SAFETY:
@@ -89,21 +312,173 @@ where
             //   create additional references to self.data. This applies to all code that
             //   creates references to self.data.
             // - Therefore, this is an exclusive reference to self.data.
-            // - In particular, the clone call below clones an Rc and is therefore safe.
+            // - In particular, the clone calls below clone an Rc and a Weak and are therefore
+            //   safe.
             // - The insert call only adds an element to a map and is therefore safe.
-            // - list_data.next_id cannot overflow, therefore the insert call returns none
-            //   and no drop code runs. But even if it did run, it would run after all
-            //   uses of the mutable references have concluded.
+            // - The id is never in use, therefore the insert call returns none and no drop
+            //   code runs. But even if it did run, it would run after all uses of the
+            //   mutable references have concluded.
             &mut *self.data.get()
         };
-        data.owner = Rc::downgrade(&to.data);
         let list_data = unsafe {
             // SAFETY: See the previous safety comment.
-            &mut *to.data.get()
+            &mut *WeakListData::ptr(&to.data)
+        };
+        if list_data.target_budget(&self.t) == 0 {
+            return Err(Error::TargetLimitReached);
+        }
+        data.id = match key {
+            Some(key) if list_data.members.contains_key(&key) => return Err(Error::IdInUse),
+            Some(key) => key,
+            None => list_data.allocate_id()?,
+        };
+        data.owner = Rc::downgrade(&to.data);
+        let visible_from = list_data.visible_from(visible);
+        let seq = list_data.allocate_seq();
+        list_data.members.insert(
+            data.id,
+            Member {
+                id: data.id,
+                t: self.t.clone(),
+                entry: self.data.clone(),
+                dead_since: None,
+                reattach: data.reattach,
+                enabled: data.enabled,
+                seq,
+                extra: MemberExtra {
+                    group,
+                    keepalive: self.keepalive.clone(),
+                    visible_from,
+                    once,
+                    epoch,
+                    ..Default::default()
+                }
+                .boxed(),
+            },
+        );
+        if let Visible::Now = visible {
+            list_data.record_late(data.id);
+        }
+        Ok(())
+    }
+
+    /// Returns the id of the entry of this element in its current list.
+    ///
+    /// Returns `None` if the element is not attached to a list. The id can be used with
+    /// [replace](WeakList::replace).
+    pub fn id(&self) -> Option<I> {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *self.data.get()
+        };
+        (data.owner.strong_count() > 0).then_some(data.id)
+    }
+
+    /// Returns whether the target of this element is still alive.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::rc::Rc;
+    /// use weak_lists::WeakListElement;
+    ///
+    /// let target = Rc::new(1);
+    /// let element = WeakListElement::new(Rc::downgrade(&target));
+    /// assert!(element.is_target_alive());
+    /// drop(target);
+    /// assert!(!element.is_target_alive());
+    /// ```
+    pub fn is_target_alive(&self) -> bool {
+        self.t.strong_count() > 0
+    }
+
+    /// Attaches the element to a list if its target is still alive.
+    ///
+    /// If the target of this element has already been dropped, the element is left
+    /// unchanged and this function returns [Error::TargetDropped]. Otherwise this function
+    /// behaves like [attach](Self::attach) but returns an error if the element was not
+    /// attached, for example [Error::IdsExhausted] if all ids of the list are in use.
+    ///
+    /// Note that the target is not yet alive while it is being constructed by
+    /// [Rc::new_cyclic]. Use [attach](Self::attach) in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::rc::Rc;
+    /// use weak_lists::{Error, WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let target = Rc::new(1);
+    /// let element = WeakListElement::new(Rc::downgrade(&target));
+    /// assert_eq!(element.try_attach(&list), Ok(()));
+    /// drop(target);
+    /// element.detach();
+    /// assert_eq!(element.try_attach(&list), Err(Error::TargetDropped));
+    /// assert!(list.iter().next().is_none());
+    /// ```
+    pub fn try_attach(&self, to: &WeakList<T, I>) -> Result<(), Error> {
+        if !self.is_target_alive() {
+            return Err(Error::TargetDropped);
+        }
+        self.attach_with_options(to, AttachOptions::default())
+    }
+
+    /// Attaches the element to a list unless it is already attached to that list.
+    ///
+    /// If the element is already attached to `to`, this function does nothing and the
+    /// element keeps its id and its position in the list. Otherwise, this function
+    /// behaves like [try_attach](Self::try_attach) except that it does not check whether
+    /// the target is alive.
+    ///
+    /// Returns whether the element was attached by this call.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let id = elements[0].id();
+    /// assert_eq!(elements[0].ensure_attached(&list), Ok(false));
+    /// assert_eq!(elements[0].id(), id);
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [0, 1]);
+    /// ```
+    pub fn ensure_attached(&self, to: &WeakList<T, I>) -> Result<bool, Error> {
+        let is_member = {
+            let data = unsafe {
+                // SAFETY:
+                // - While we hold these references, we do not call any functions that
+                //   might create additional references to self.data or to.data. This
+                //   applies to all code that creates references to self.data or to.data.
+                // - Therefore, these are exclusive references.
+                &mut *self.data.get()
+            };
+            let list_data = unsafe {
+                // SAFETY: See the previous safety comment.
+                &mut *WeakListData::ptr(&to.data)
+            };
+            ptr::eq(data.owner.as_ptr(), &*to.data)
+                && matches!(
+                    list_data.members.get(&data.id),
+                    Some(m) if Rc::ptr_eq(&m.entry, &self.data),
+                )
         };
-        data.id = list_data.next_id;
-        list_data.next_id += 1;
-        list_data.members.insert(data.id, self.t.clone());
+        if is_member {
+            return Ok(false);
+        }
+        self.attach_with_options(to, AttachOptions::default())?;
+        Ok(true)
     }
 
     /// Detaches the element from its current list.
@@ -133,40 +508,140 @@ where
     /// assert!(clients.iter().next().is_none());
     /// ```
     pub fn detach(&self) {
-        let data = unsafe {
+        detach(&self.data);
+    }
+}
+
+/// Detaches the element with the given data from its current list.
+pub(super) fn detach<T, I>(entry: &Rc<UnsafeCell<EntryData<T, I>>>)
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    let data = unsafe {
+        // SAFETY:
+        // - While we hold this reference, we do not call any functions that might
+        //   create additional references to entry. This applies to all code that
+        //   creates references to entry.
+        // - Therefore, this is an exclusive reference to entry.
+        // - All drop code below runs after the last use of the references has
+        //   concluded. However, even if it did run, it could be shown that that code
+        //   is harmless and does not run any code that depends on T.
+        &mut *entry.get()
+    };
+    data.deferred = false;
+    let prev = mem::take(&mut data.owner).upgrade();
+    if let Some(prev) = prev {
+        let list_data = unsafe {
+            // SAFETY: See the previous safety comment.
+            &mut *WeakListData::ptr(&prev)
+        };
+        // The list might have been cleared and the id reused by another entry.
+        let is_member = match list_data.members.get(&data.id) {
+            Some(member) => Rc::ptr_eq(&member.entry, entry),
+            None => false,
+        };
+        if is_member {
+            list_data.remove_member(data.id);
+        }
+    }
+}
+
+impl<T, I> Member<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns the target of the member unless its keepalive object has been dropped.
+    pub(super) fn target(&self) -> Option<&Weak<T>> {
+        match self.extra.as_ref().and_then(|e| e.keepalive.as_ref()) {
+            Some(k) if k.strong_count() == 0 => None,
+            _ => Some(&self.t),
+        }
+    }
+
+    /// Returns the extra state of the member, allocating it if necessary.
+    pub(super) fn extra_mut(&mut self) -> &mut MemberExtra {
+        self.extra.get_or_insert_with(Default::default)
+    }
+
+    /// See [MemberExtra::group].
+    pub(super) fn group(&self) -> Option<u32> {
+        self.extra.as_ref().and_then(|e| e.group)
+    }
+
+    /// See [MemberExtra::invocations].
+    pub(super) fn invocations(&self) -> InvocationStats {
+        self.extra
+            .as_ref()
+            .map(|e| e.invocations)
+            .unwrap_or_default()
+    }
+
+    /// See [MemberExtra::once].
+    pub(super) fn once(&self) -> bool {
+        self.extra.as_ref().is_some_and(|e| e.once)
+    }
+
+    /// Returns whether the target and the keepalive object of the member are alive.
+    pub(super) fn is_alive(&self) -> bool {
+        self.target().is_some_and(|t| t.strong_count() > 0)
+    }
+
+    /// Upgrades the target of the member unless its keepalive object has been dropped.
+    pub(super) fn upgrade(&self) -> Option<Rc<T>> {
+        self.target()?.upgrade()
+    }
+
+    /// Detaches the element of a member that has been removed from its list.
+    pub(super) fn detach_removed(&self) {
+        let entry = unsafe {
             // SAFETY:
             // - While we hold this reference, we do not call any functions that might
-            //   create additional references to self.data. This applies to all code that
-            //   creates references to self.data.
-            // - Therefore, this is an exclusive reference to self.data.
-            // - All drop code below runs after the last use of the references has
-            //   concluded. However, even if it did run, it could be shown that that code
-            //   is harmless and does not run any code that depends on T.
-            &mut *self.data.get()
+            //   create additional references to self.entry. This applies to all code
+            //   that creates references to self.entry.
+            // - Therefore, this is an exclusive reference to self.entry.
+            &mut *self.entry.get()
         };
-        let prev = mem::take(&mut data.owner).upgrade();
-        if let Some(prev) = prev {
-            let list_data = unsafe {
-                // SAFETY: See the previous safety comment.
-                &mut *prev.get()
-            };
-            list_data.members.remove(&data.id);
-        }
+        entry.owner = Weak::new();
+    }
+}
+
+impl MemberExtra {
+    /// Boxes the state unless all fields have their default values.
+    pub(super) fn boxed(self) -> Option<Box<Self>> {
+        let MemberExtra {
+            group,
+            keepalive,
+            invocations,
+            visible_from,
+            once,
+            epoch,
+        } = &self;
+        let is_default = group.is_none()
+            && keepalive.is_none()
+            && *invocations == InvocationStats::default()
+            && *visible_from == 0
+            && !once
+            && *epoch == 0;
+        (!is_default).then(|| Box::new(self))
     }
 }
 
-impl<T> Drop for WeakListElement<T>
+impl<T, I> Drop for WeakListElement<T, I>
 where
     T: ?Sized,
+    I: EntryId,
 {
     fn drop(&mut self) {
         self.detach();
     }
 }
 
-impl<T> Debug for WeakListElement<T>
+impl<T, I> Debug for WeakListElement<T, I>
 where
     T: ?Sized,
+    I: EntryId,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let data = unsafe {
@@ -184,3 +659,28 @@ where
             .finish_non_exhaustive()
     }
 }
+
+/// The options of [attach_with_options](WeakListElement::attach_with_options).
+pub(super) struct AttachOptions<I> {
+    /// See [attach_with_group](WeakListElement::attach_with_group).
+    pub(super) group: Option<u32>,
+    /// See [attach_keyed](WeakListElement::attach_keyed).
+    pub(super) key: Option<I>,
+    pub(super) visible: Visible,
+    /// See [attach_once](WeakListElement::attach_once).
+    pub(super) once: bool,
+    /// See [attach_with_epoch](WeakListElement::attach_with_epoch).
+    pub(super) epoch: u64,
+}
+
+impl<I> Default for AttachOptions<I> {
+    fn default() -> Self {
+        Self {
+            group: None,
+            key: None,
+            visible: Visible::Unspecified,
+            once: false,
+            epoch: 0,
+        }
+    }
+}
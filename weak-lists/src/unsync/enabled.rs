@@ -0,0 +1,79 @@
+use {
+    crate::{
+        id::EntryId,
+        unsync::{WeakListData, WeakListElement},
+    },
+    alloc::rc::Rc,
+};
+
+impl<T, I> WeakListElement<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Sets whether iterators yield the entry of the element.
+    ///
+    /// A disabled element remains attached to its list and keeps its id and its position.
+    /// Iterators skip its entry until it is enabled again. This includes the iterators
+    /// used by the dispatch helpers such as [for_each](crate::WeakList::for_each) and the
+    /// snapshots taken by [map_collect](crate::WeakList::map_collect). The flag is
+    /// checked when an iterator reaches the entry. It is retained when the element is
+    /// attached to another list.
+    ///
+    /// Elements are enabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// elements[0].set_enabled(false);
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [1]);
+    /// elements[0].set_enabled(true);
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [0, 1]);
+    /// ```
+    pub fn set_enabled(&self, enabled: bool) {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *self.data.get()
+        };
+        data.enabled = enabled;
+        if let Some(list) = data.owner.upgrade() {
+            let list_data = unsafe {
+                // SAFETY: See the previous safety comment.
+                &mut *WeakListData::ptr(&list)
+            };
+            if let Some(member) = list_data.members.get_mut(&data.id) {
+                if Rc::ptr_eq(&member.entry, &self.data) {
+                    member.enabled = enabled;
+                }
+            }
+        }
+    }
+
+    /// Returns whether iterators yield the entry of the element.
+    ///
+    /// See [set_enabled](Self::set_enabled).
+    pub fn is_enabled(&self) -> bool {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *self.data.get()
+        };
+        data.enabled
+    }
+}
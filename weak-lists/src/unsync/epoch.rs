@@ -0,0 +1,77 @@
+use crate::{
+    id::EntryId,
+    unsync::{element::AttachOptions, Iter, WeakList, WeakListElement},
+};
+
+impl<T, I> WeakListElement<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Attaches the element to a list with an epoch.
+    ///
+    /// This function behaves like [attach](Self::attach). Additionally, the entry is
+    /// tagged with `epoch`, which can be used to exclude the entry from iteration with
+    /// [iter_epoch_at_most](WeakList::iter_epoch_at_most). Elements attached with other
+    /// functions have the epoch 0.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1), Rc::new(2)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// elements[0].attach(&list);
+    /// elements[1].attach_with_epoch(&list, 2);
+    /// elements[2].attach_with_epoch(&list, 1);
+    /// let e1: Vec<_> = list.iter_epoch_at_most(1).map(|e| *e).collect();
+    /// assert_eq!(e1, [0, 2]);
+    /// ```
+    pub fn attach_with_epoch(&self, to: &WeakList<T, I>, epoch: u64) {
+        let _ = self.attach_with_options(
+            to,
+            AttachOptions {
+                epoch,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+impl<T, I> WeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Creates an iterator over the entries of the list whose epoch is at most `epoch`.
+    ///
+    /// The epoch of an entry is set with
+    /// [attach_with_epoch](WeakListElement::attach_with_epoch). Otherwise, this function behaves
+    /// like [iter](Self::iter). In particular, entries that are attached while the
+    /// iterator exists are yielded only if their epoch is at most `epoch`. The limit is
+    /// retained when the iterator is [reset](Iter::reset).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// elements[0].attach_with_epoch(&list, 1);
+    /// let mut iter = list.iter_epoch_at_most(1);
+    /// elements[1].attach_with_epoch(&list, 2);
+    /// iter.reset();
+    /// assert_eq!(iter.map(|e| *e).collect::<Vec<_>>(), [0]);
+    /// ```
+    pub fn iter_epoch_at_most(&self, epoch: u64) -> Iter<'_, T, I> {
+        let mut iter = self.iter();
+        iter.visibility.max_epoch = epoch;
+        iter
+    }
+}
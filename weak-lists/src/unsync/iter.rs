@@ -1,53 +1,386 @@
 use {
-    crate::unsync::Iter,
-    alloc::rc::Rc,
+    crate::{
+        debug::Entry,
+        id::EntryId,
+        unsync::{
+            clock::read_clock, Iter, Member, OwnedIter, Visibility, WeakListData, WeakListElement,
+        },
+    },
+    alloc::{
+        rc::{Rc, Weak},
+        vec::Vec,
+    },
     core::{
+        cell::UnsafeCell,
         fmt::{Debug, Formatter},
         iter::FusedIterator,
+        ops::Range,
+        ptr,
     },
 };
 
-impl<T> Drop for Iter<'_, T>
+#[cfg(not(feature = "paranoid"))]
+fn next<T, I>(
+    data: &UnsafeCell<WeakListData<T, I>>,
+    iter: &mut Range<usize>,
+    skipped: &mut usize,
+    visibility: &mut Visibility<I>,
+    excluded: Option<&Weak<T>>,
+) -> Option<(usize, I, Rc<T>)>
 where
     T: ?Sized,
+    I: EntryId,
 {
-    fn drop(&mut self) {
-        let data = unsafe { &mut *self.data.get() };
-        data.active_iterators -= 1;
+    loop {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to data. This applies to all code that
+            //   creates references to data.
+            // - Therefore, this is an exclusive reference to data.
+            // - The get_by_index and upgrade calls below only run well-known code
+            //   that does not depend on T.
+            &mut *WeakListData::ptr(data)
+        };
+        data.catch_up(iter, visibility);
+        let Some(idx) = iter.next().or_else(|| data.pop_extra(visibility)) else {
+            visibility.finish();
+            return None;
+        };
+        if let Some(member) = data.members.get_by_index(idx) {
+            if !member.is_visible_to(visibility) || member.is_excluded(excluded) {
+                continue;
+            }
+            if let Some(t) = member.upgrade() {
+                let id = member.id;
+                if member.once() {
+                    data.detach_once(id);
+                }
+                return Some((idx, id, t));
+            }
+            *skipped += 1;
+            data.observe_dead(idx, visibility.now);
+        }
     }
 }
 
-impl<T> Iterator for Iter<'_, T>
+/// The `paranoid` version of [next].
+///
+/// The reference to the list data is only held while the member is copied out of the
+/// list. The member is upgraded after the reference has been dropped. While the reference
+/// is held, the data is marked as borrowed and any other access to the list data causes a
+/// panic. See [WeakListData::ptr].
+#[cfg(feature = "paranoid")]
+fn next<T, I>(
+    data: &UnsafeCell<WeakListData<T, I>>,
+    iter: &mut Range<usize>,
+    skipped: &mut usize,
+    visibility: &mut Visibility<I>,
+    excluded: Option<&Weak<T>>,
+) -> Option<(usize, I, Rc<T>)>
 where
     T: ?Sized,
+    I: EntryId,
 {
-    type Item = Rc<T>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        for idx in self.iter.by_ref() {
+    loop {
+        let member = {
             let data = unsafe {
-                // SAFETY:
-                // - While we hold this reference, we do not call any functions that might
-                //   create additional references to self.data. This applies to all code that
-                //   creates references to self.data.
-                // - Therefore, this is an exclusive reference to self.data.
-                // - The get_by_index and upgrade calls below only run well-known code
-                //   that does not depend on T.
-                &mut *self.data.get()
+                // SAFETY: See the non-paranoid version of this function. The
+                //         get_by_index call and the clone of the Weak only run well-known
+                //         code that does not depend on T.
+                &mut *WeakListData::ptr(data)
+            };
+            data.borrowed = true;
+            data.catch_up(iter, visibility);
+            let Some(idx) = iter.next().or_else(|| data.pop_extra(visibility)) else {
+                data.borrowed = false;
+                visibility.finish();
+                return None;
             };
-            if let Some(member) = data.members.get_by_index(idx) {
-                if let Some(member) = member.upgrade() {
-                    return Some(member);
+            let member = data
+                .members
+                .get_by_index(idx)
+                .filter(|member| member.is_visible_to(visibility))
+                .filter(|member| !member.is_excluded(excluded))
+                .map(|member| (idx, member.id, member.once(), member.target().cloned()));
+            data.borrowed = false;
+            member
+        };
+        if let Some((idx, id, once, t)) = member {
+            if let Some(t) = t.and_then(|t| t.upgrade()) {
+                if once {
+                    let data = unsafe {
+                        // SAFETY: See the non-paranoid version of this function.
+                        &mut *WeakListData::ptr(data)
+                    };
+                    data.detach_once(id);
                 }
+                return Some((idx, id, t));
             }
+            *skipped += 1;
+            let data = unsafe {
+                // SAFETY: See the non-paranoid version of this function.
+                &mut *WeakListData::ptr(data)
+            };
+            data.observe_dead(idx, visibility.now);
         }
-        None
     }
 }
 
-impl<T> Clone for Iter<'_, T>
+impl<T, I> Iter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns whether this iterator will yield the element.
+    ///
+    /// This is the case if the element is attached to the list and this iterator has not
+    /// yet passed it. The element will then be yielded unless it is detached or its target
+    /// is dropped before this iterator reaches it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// elements[0].attach(&list);
+    /// let mut iter = list.iter();
+    /// elements[1].attach(&list);
+    /// assert!(iter.will_yield(&elements[0]));
+    /// assert!(!iter.will_yield(&elements[1]));
+    /// iter.next();
+    /// assert!(!iter.will_yield(&elements[0]));
+    /// ```
+    pub fn will_yield(&self, element: &WeakListElement<T, I>) -> bool {
+        let entry = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to element.data. This applies to all code
+            //   that creates references to element.data.
+            // - Therefore, this is an exclusive reference to element.data.
+            &mut *element.data.get()
+        };
+        if !ptr::eq(entry.owner.as_ptr(), self.data) {
+            return false;
+        }
+        if matches!(&self.peeked, Some((_, id, _)) if *id == entry.id) {
+            return true;
+        }
+        let data = unsafe {
+            // SAFETY: See the previous safety comment.
+            &mut *WeakListData::ptr(self.data)
+        };
+        let visible = |idx| {
+            matches!(
+                data.members.get_by_index(idx),
+                Some(m) if m.id == entry.id && m.is_visible_to(&self.visibility),
+            )
+        };
+        self.iter.clone().any(visible)
+            || data.will_yield_late(&self.iter, &self.visibility, entry.id)
+    }
+
+    /// Returns the number of entries this iterator has skipped because their targets had
+    /// already been dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let [_, entry] = entries;
+    /// drop(entry);
+    /// let mut iter = list.iter();
+    /// assert!(iter.next().is_some());
+    /// assert!(iter.next().is_none());
+    /// assert_eq!(iter.skipped(), 1);
+    /// ```
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// Rewinds the iterator to the current state of the list.
+    ///
+    /// Afterwards, this iterator behaves as if it had just been created by calling
+    /// [iter](crate::unsync::WeakList::iter).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// elements[0].attach(&list);
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.next(), Some(entries[0].clone()));
+    /// elements[1].attach(&list);
+    /// iter.reset();
+    /// assert_eq!(iter.count(), 2);
+    /// ```
+    pub fn reset(&mut self) {
+        let now = read_clock(self.data);
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *WeakListData::ptr(self.data)
+        };
+        if data.active_iterators == 1 && data.auto_compact {
+            data.compact();
+        }
+        // The late members are only relevant to the other iterators.
+        data.active_iterators -= 1;
+        self.visibility = Visibility {
+            max_epoch: self.visibility.max_epoch,
+            ..data.start_visibility(now)
+        };
+        data.active_iterators += 1;
+        self.iter = 0..data.members.index_len();
+        self.skipped = 0;
+        self.peeked = None;
+    }
+
+    /// Returns the entry that will be returned by the next call to [next](Self::next)
+    /// without advancing the iterator.
+    ///
+    /// The peeked entry is returned by the next call to `next` even if it is detached
+    /// from the list in the meantime.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let mut iter = list.iter();
+    /// assert_eq!(iter.peek(), Some(&entries[0]));
+    /// elements[0].detach();
+    /// assert_eq!(iter.next(), Some(entries[0].clone()));
+    /// assert_eq!(iter.peek(), Some(&entries[1]));
+    /// ```
+    pub fn peek(&mut self) -> Option<&Rc<T>> {
+        if self.peeked.is_none() {
+            self.peeked = self.next_with_index();
+        }
+        self.peeked.as_ref().map(|(_, _, t)| t)
+    }
+
+    /// Makes the iterator skip the entries whose target is `target`.
+    ///
+    /// This is useful for nested iterations over the same list where the inner iteration
+    /// should not yield the target of the outer iteration. Targets are compared by
+    /// identity. Only one target can be skipped. Calling this function again replaces the
+    /// previous target. The target is still skipped after [reset](Self::reset).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1), Rc::new(2)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let mut pairs = Vec::new();
+    /// for a in list.iter() {
+    ///     for b in list.iter().skip_target(&a) {
+    ///         pairs.push((*a, *b));
+    ///     }
+    /// }
+    /// assert_eq!(pairs, [(0, 1), (0, 2), (1, 0), (1, 2), (2, 0), (2, 1)]);
+    /// ```
+    pub fn skip_target(mut self, target: &Rc<T>) -> Self {
+        if matches!(&self.peeked, Some((_, _, t)) if Rc::ptr_eq(t, target)) {
+            self.peeked = None;
+        }
+        self.excluded = Some(Rc::downgrade(target));
+        self
+    }
+
+    /// Like [Iterator::next] but also returns the id of the member.
+    pub(super) fn next_with_id(&mut self) -> Option<(I, Rc<T>)> {
+        self.next_with_index().map(|(_, id, t)| (id, t))
+    }
+
+    /// Like [Iterator::next] but also returns the slot and the id of the member.
+    pub(super) fn next_with_index(&mut self) -> Option<(usize, I, Rc<T>)> {
+        if let Some(peeked) = self.peeked.take() {
+            return Some(peeked);
+        }
+        next(
+            self.data,
+            &mut self.iter,
+            &mut self.skipped,
+            &mut self.visibility,
+            self.excluded.as_ref(),
+        )
+    }
+}
+
+impl<T, I> Member<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns whether the target of the member is skipped by an iterator. See
+    /// [skip_target](Iter::skip_target).
+    fn is_excluded(&self, excluded: Option<&Weak<T>>) -> bool {
+        excluded.is_some_and(|excluded| Weak::ptr_eq(&self.t, excluded))
+    }
+}
+
+impl<T, I> Drop for Iter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn drop(&mut self) {
+        let data = unsafe { &mut *WeakListData::ptr(self.data) };
+        data.active_iterators -= 1;
+    }
+}
+
+impl<T, I> Iterator for Iter<'_, T, I>
 where
     T: ?Sized,
+    I: EntryId,
+{
+    type Item = Rc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_id().map(|(_, t)| t)
+    }
+}
+
+impl<T, I> Clone for Iter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
 {
     fn clone(&self) -> Self {
         let data = unsafe {
@@ -56,23 +389,140 @@ where
             //   create additional references to self.data. This applies to all code that
             //   creates references to self.data.
             // - Therefore, this is an exclusive reference to self.data.
-            &mut *self.data.get()
+            &mut *WeakListData::ptr(self.data)
         };
         data.active_iterators += 1;
         Self {
             iter: self.iter.clone(),
             data: self.data,
+            skipped: self.skipped,
+            peeked: self.peeked.clone(),
+            visibility: self.visibility.clone(),
+            excluded: self.excluded.clone(),
         }
     }
 }
 
-impl<T> Debug for Iter<'_, T>
+impl<T, I> Debug for Iter<'_, T, I>
 where
-    T: ?Sized + Debug,
+    T: ?Sized,
+    I: EntryId,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        f.debug_list().entries(self.clone()).finish()
+        let mut remaining = Vec::new();
+        if let Some((_, id, _)) = &self.peeked {
+            remaining.push(Entry {
+                id: *id,
+                alive: true,
+            });
+        }
+        {
+            let data = unsafe {
+                // SAFETY:
+                // - While we hold this reference, we do not call any functions that might
+                //   create additional references to self.data. This applies to all code
+                //   that creates references to self.data.
+                // - Therefore, this is an exclusive reference to self.data.
+                // - The remaining call only runs well-known code that does not depend on T.
+                &mut *WeakListData::ptr(self.data)
+            };
+            remaining.extend(data.remaining(&self.iter, &self.visibility));
+        }
+        f.debug_list().entries(remaining).finish()
     }
 }
 
-impl<T> FusedIterator for Iter<'_, T> where T: ?Sized {}
+impl<T, I> FusedIterator for Iter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+}
+
+impl<T, I> Drop for OwnedIter<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn drop(&mut self) {
+        if let Some(data) = self.data.upgrade() {
+            let data = unsafe { &mut *WeakListData::ptr(&data) };
+            data.active_iterators -= 1;
+        }
+    }
+}
+
+impl<T, I> Iterator for OwnedIter<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    type Item = Rc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Some(data) = self.data.upgrade() else {
+            self.iter = 0..0;
+            self.visibility.finish();
+            return None;
+        };
+        next(&data, &mut self.iter, &mut 0, &mut self.visibility, None).map(|(_, _, t)| t)
+    }
+}
+
+impl<T, I> Clone for OwnedIter<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn clone(&self) -> Self {
+        if let Some(data) = self.data.upgrade() {
+            let data = unsafe {
+                // SAFETY:
+                // - While we hold this reference, we do not call any functions that might
+                //   create additional references to self.data. This applies to all code that
+                //   creates references to self.data.
+                // - Therefore, this is an exclusive reference to self.data.
+                &mut *WeakListData::ptr(&data)
+            };
+            data.active_iterators += 1;
+        }
+        Self {
+            iter: self.iter.clone(),
+            data: self.data.clone(),
+            visibility: self.visibility.clone(),
+        }
+    }
+}
+
+impl<T, I> Debug for OwnedIter<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let remaining = match self.data.upgrade() {
+            Some(data) => {
+                let data = unsafe {
+                    // SAFETY:
+                    // - While we hold this reference, we do not call any functions that
+                    //   might create additional references to self.data. This applies to
+                    //   all code that creates references to self.data.
+                    // - Therefore, this is an exclusive reference to self.data.
+                    // - The remaining call only runs well-known code that does not depend
+                    //   on T.
+                    &mut *WeakListData::ptr(&data)
+                };
+                data.remaining(&self.iter, &self.visibility)
+            }
+            None => Vec::new(),
+        };
+        f.debug_list().entries(remaining).finish()
+    }
+}
+
+impl<T, I> FusedIterator for OwnedIter<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+}
@@ -0,0 +1,147 @@
+use {
+    crate::{
+        id::EntryId,
+        unsync::{LimitedIter, WeakList, WeakListData},
+    },
+    alloc::rc::Rc,
+    core::{
+        fmt::{Debug, Formatter},
+        iter::FusedIterator,
+    },
+};
+
+impl<T, I> WeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Creates an iterator over at most `max` entries of the list.
+    ///
+    /// The list remembers where the previous limited iterator stopped and the next one
+    /// resumes from there, wrapping around at the end of the list. The entries that were
+    /// not yielded because of the limit are therefore yielded first by the next limited
+    /// iterator. This can be used to spread the work over multiple iterations.
+    ///
+    /// Otherwise, this iterator provides the same guarantees as [iter](Self::iter).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1), Rc::new(2)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let iter = list.iter_limited(2);
+    /// assert_eq!(iter.map(|e| *e).collect::<Vec<_>>(), [0, 1]);
+    /// let iter = list.iter_limited(2);
+    /// assert_eq!(iter.map(|e| *e).collect::<Vec<_>>(), [2, 0]);
+    /// ```
+    pub fn iter_limited(&self, max: usize) -> LimitedIter<'_, T, I> {
+        let mut iter = self.iter();
+        let start = {
+            let data = unsafe {
+                // SAFETY:
+                // - While we hold this reference, we do not call any functions that might
+                //   create additional references to self.data. This applies to all code
+                //   that creates references to self.data.
+                // - Therefore, this is an exclusive reference to self.data.
+                &mut *WeakListData::ptr(&self.data)
+            };
+            data.cursor.min(iter.iter.end)
+        };
+        iter.iter.start = start;
+        LimitedIter {
+            iter,
+            start,
+            wrapped: false,
+            remaining: max,
+        }
+    }
+}
+
+impl<T, I> LimitedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Like [Iterator::next] but also returns the id of the member.
+    pub(super) fn next_with_id(&mut self) -> Option<(I, Rc<T>)> {
+        if self.remaining == 0 {
+            return None;
+        }
+        loop {
+            let Some((id, t)) = self.iter.next_with_id() else {
+                if self.wrapped {
+                    self.remaining = 0;
+                    return None;
+                }
+                self.wrapped = true;
+                self.iter.iter = 0..self.start;
+                continue;
+            };
+            let data = unsafe {
+                // SAFETY:
+                // - While we hold this reference, we do not call any functions that might
+                //   create additional references to self.iter.data. This applies to all
+                //   code that creates references to self.iter.data.
+                // - Therefore, this is an exclusive reference to self.iter.data.
+                &mut *WeakListData::ptr(self.iter.data)
+            };
+            data.cursor = self.iter.iter.start;
+            self.remaining -= 1;
+            return Some((id, t));
+        }
+    }
+}
+
+impl<T, I> Iterator for LimitedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    type Item = Rc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_id().map(|(_, t)| t)
+    }
+}
+
+impl<T, I> Clone for LimitedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            start: self.start,
+            wrapped: self.wrapped,
+            remaining: self.remaining,
+        }
+    }
+}
+
+impl<T, I> Debug for LimitedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LimitedIter")
+            .field("iter", &self.iter)
+            .field("remaining", &self.remaining)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, I> FusedIterator for LimitedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+}
@@ -1,18 +1,40 @@
 use {
-    crate::unsync::{Iter, WeakList, WeakListData},
-    alloc::rc::Rc,
+    crate::{
+        error::Error,
+        id::EntryId,
+        unsync::{
+            cascade, clock::read_clock, visibility::Visible, Difference, EntryData, Intersection,
+            Iter, Member, MemberExtra, OwnedIter, TargetSet, Union, Visibility, WeakList,
+            WeakListBuilder, WeakListData, WeakListElement,
+        },
+    },
+    alloc::{
+        boxed::Box,
+        rc::{Rc, Weak},
+        vec::Vec,
+    },
     core::{
         cell::UnsafeCell,
         fmt::{Debug, Formatter},
+        mem,
+        ops::Range,
+        ptr,
     },
 };
 
-impl<T> WeakList<T>
+impl<T, I> WeakList<T, I>
 where
     T: ?Sized,
+    I: EntryId,
 {
     /// Removes all elements from the list.
     ///
+    /// The elements are detached as if by [detach](WeakListElement::detach). Elements that
+    /// have requested to be reattached are an exception, see
+    /// [set_reattach_on_clear](WeakListElement::set_reattach_on_clear).
+    ///
+    /// The dependents of this list are cleared as well. See [add_child](Self::add_child).
+    ///
     /// # Examples
     ///
     /// ```
@@ -28,17 +50,298 @@ where
     /// assert!(list.iter().next().is_none());
     /// ```
     pub fn clear(&self) {
+        cascade::clear(&self.data);
+    }
+
+    /// Removes all elements from the list and invokes `f` with their targets.
+    ///
+    /// This function behaves like [clear](Self::clear). After the list has been cleared,
+    /// `f` is invoked for each detached element whose target is still alive. The list
+    /// can be modified from within `f`.
+    ///
+    /// `f` is not invoked for the dependents of this list or for elements that have
+    /// requested to be reattached.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entry = Rc::new(1);
+    /// let element = WeakListElement::new(Rc::downgrade(&entry));
+    /// element.attach(&list);
+    /// let mut cleared = vec![];
+    /// list.clear_notify(|e| cleared.push(*e));
+    /// assert_eq!(cleared, [1]);
+    /// assert!(element.id().is_none());
+    /// ```
+    pub fn clear_notify(&self, mut f: impl FnMut(Rc<T>)) {
+        for member in cascade::clear(&self.data) {
+            if let Some(target) = member.upgrade() {
+                f(target);
+            }
+        }
+    }
+
+    /// Detaches all elements that were attached to this list as members of the group.
+    ///
+    /// See [attach_with_group](crate::unsync::WeakListElement::attach_with_group).
+    pub fn detach_group(&self, group: u32) {
+        let ids: Vec<_> = {
+            let data = unsafe {
+                // SAFETY:
+                // - While we hold this reference, we do not call any functions that might
+                //   create additional references to self.data. This applies to all code
+                //   that creates references to self.data.
+                // - Therefore, this is an exclusive reference to self.data.
+                &mut *WeakListData::ptr(&self.data)
+            };
+            (0..data.members.index_len())
+                .filter_map(|idx| data.members.get_by_index(idx))
+                .filter(|member| member.group() == Some(group))
+                .map(|member| member.id)
+                .collect()
+        };
+        for id in ids {
+            self.detach_member(id);
+        }
+    }
+
+    /// Creates a new `Rc` with [Rc::new_cyclic] and attaches its element to this list.
+    ///
+    /// Elements that are attached while their target is still being constructed by
+    /// [Rc::new_cyclic] are skipped by iterators until the construction has completed.
+    /// This function instead attaches the element returned by `element` once the `Rc`
+    /// has been fully constructed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::{Rc, Weak};
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// struct Client {
+    ///     element: WeakListElement<Client>,
+    /// }
+    ///
+    /// let clients = WeakList::default();
+    /// let client = clients.adopt_on_init(
+    ///     |slf| Client {
+    ///         element: WeakListElement::new(slf.clone()),
+    ///     },
+    ///     |client| &client.element,
+    /// );
+    /// assert!(clients.iter().next().is_some());
+    /// ```
+    pub fn adopt_on_init<U>(
+        &self,
+        data_fn: impl FnOnce(&Weak<U>) -> U,
+        element: impl FnOnce(&U) -> &WeakListElement<T, I>,
+    ) -> Rc<U> {
+        let u = Rc::new_cyclic(data_fn);
+        element(&u).attach(self);
+        u
+    }
+
+    /// Creates a new element for the target of `t` and attaches it to this list.
+    ///
+    /// This is a shorthand for creating the element with
+    /// [new_with_id_type](WeakListElement::new_with_id_type) and attaching it with
+    /// [attach](WeakListElement::attach). The entry is removed from the list when the returned element
+    /// is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::WeakList;
+    ///
+    /// let list = WeakList::default();
+    /// let entry = Rc::new(1);
+    /// let element = list.attach_new(&entry);
+    /// assert_eq!(element.id(), Some(0));
+    /// assert_eq!(list.iter().next(), Some(entry));
+    /// ```
+    pub fn attach_new(&self, t: &Rc<T>) -> WeakListElement<T, I> {
+        let element = WeakListElement::new_with_id_type(Rc::downgrade(t));
+        element.attach(self);
+        element
+    }
+
+    /// Replaces the element of an entry.
+    ///
+    /// The element is detached from its current list and takes over the position of the
+    /// entry with the given id. The element previously stored in the entry is detached.
+    ///
+    /// Only the id and the position of the entry carry over. Everything else is reset as
+    /// if the element had been attached with [attach](WeakListElement::attach): the
+    /// entry is not one-shot, belongs to no group, has epoch 0, is visible to all
+    /// iterators, and its invocation statistics start from zero.
+    ///
+    /// Returns [Error::NotFound] if the list does not contain an entry with the given id.
+    /// The element is left detached in this case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1), Rc::new(2)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// elements[0].attach(&list);
+    /// elements[1].attach(&list);
+    /// let id = elements[0].id().unwrap();
+    /// assert_eq!(list.replace(id, &elements[2]), Ok(()));
+    /// assert_eq!(elements[0].id(), None);
+    /// assert_eq!(elements[2].id(), Some(id));
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [2, 1]);
+    /// ```
+    pub fn replace(&self, id: I, element: &WeakListElement<T, I>) -> Result<(), Error> {
+        {
+            let entry = unsafe {
+                // SAFETY:
+                // - While we hold this reference, we do not call any functions that might
+                //   create additional references to element.data. This applies to all code
+                //   that creates references to element.data.
+                // - Therefore, this is an exclusive reference to element.data.
+                &mut *element.data.get()
+            };
+            if entry.id == id && ptr::eq(entry.owner.as_ptr(), &*self.data) {
+                return Ok(());
+            }
+        }
+        element.detach();
         let data = unsafe {
             // SAFETY:
             // - While we hold this reference, we do not call any functions that might
             //   create additional references to self.data. This applies to all code that
             //   creates references to self.data.
             // - Therefore, this is an exclusive reference to self.data.
-            // - In particular, dropping the Weak objects below will never run the drop
-            //   impl of T itself.
-            &mut *self.data.get()
+            // - The same applies to the references to the entries below. The entries are
+            //   distinct since the element was detached.
+            // - The Weaks and the entry replaced below are dropped after all references
+            //   have been released. Dropping them never runs the drop impl of T or of the
+            //   keepalive object itself.
+            &mut *WeakListData::ptr(&self.data)
+        };
+        let visible_from = data.visible_from(Visible::Unspecified);
+        let Some(member) = data.members.get_mut(&id) else {
+            return Err(Error::NotFound);
+        };
+        let old_t = mem::replace(&mut member.t, element.t.clone());
+        let old_extra = mem::replace(
+            &mut member.extra,
+            MemberExtra {
+                keepalive: element.keepalive.clone(),
+                visible_from,
+                ..Default::default()
+            }
+            .boxed(),
+        );
+        let old_entry = mem::replace(&mut member.entry, element.data.clone());
+        if member.dead_since.take().is_some() {
+            data.known_dead -= 1;
+        }
+        let new = unsafe {
+            // SAFETY: See the previous safety comment.
+            &mut *element.data.get()
+        };
+        new.owner = Rc::downgrade(&self.data);
+        new.id = id;
+        member.reattach = new.reattach;
+        member.seq = data.next_seq;
+        data.next_seq += 1;
+        member.enabled = new.enabled;
+        let old = unsafe {
+            // SAFETY: See the previous safety comment.
+            &mut *old_entry.get()
         };
-        data.members.clear();
+        old.owner = Weak::new();
+        drop((old_t, old_extra, old_entry));
+        Ok(())
+    }
+
+    /// Returns the target of the entry with the given key.
+    ///
+    /// The key of an entry is its id. See [attach_keyed](WeakListElement::attach_keyed).
+    ///
+    /// Returns `None` if the list does not contain an entry with this key or if the target
+    /// of the entry has been dropped.
+    pub fn get_key(&self, key: I) -> Option<Rc<T>> {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *WeakListData::ptr(&self.data)
+        };
+        data.members.get(&key)?.upgrade()
+    }
+
+    /// Returns whether the list contains an entry with the given id.
+    ///
+    /// This function does not check whether the target of the entry is still alive. Ids
+    /// are allocated in increasing order and are only reused once the id space has been
+    /// exhausted. An id obtained from [id](WeakListElement::id) can therefore be used to check
+    /// whether the element is still attached without access to the element itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::WeakList;
+    ///
+    /// let list = WeakList::default();
+    /// let entry = Rc::new(1);
+    /// let element = list.attach_new(&entry);
+    /// let id = element.id().unwrap();
+    /// assert!(list.contains_id(id));
+    /// drop(element);
+    /// assert!(!list.contains_id(id));
+    /// ```
+    pub fn contains_id(&self, id: I) -> bool {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *WeakListData::ptr(&self.data)
+        };
+        data.members.contains_key(&id)
+    }
+
+    /// Detaches the entry with the given key.
+    ///
+    /// The key of an entry is its id. See [attach_keyed](WeakListElement::attach_keyed).
+    ///
+    /// Returns [Error::NotFound] if the list does not contain an entry with this key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{Error, WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entry = Rc::new(1);
+    /// let element = WeakListElement::new(Rc::downgrade(&entry));
+    /// assert_eq!(element.attach_keyed(&list, 3), Ok(()));
+    /// assert_eq!(list.detach_key(3), Ok(()));
+    /// assert_eq!(list.detach_key(3), Err(Error::NotFound));
+    /// assert_eq!(element.id(), None);
+    /// assert_eq!(list.get_key(3), None);
+    /// ```
+    pub fn detach_key(&self, key: I) -> Result<(), Error> {
+        match self.detach_member(key) {
+            true => Ok(()),
+            false => Err(Error::NotFound),
+        }
     }
 
     /// Creates an iterator over the entries of the list.
@@ -47,23 +350,702 @@ where
     /// was part of the list when this iterator was created, and if the element was not
     /// removed during the iteration, then the element will be returned exactly once by
     /// this iterator.
-    pub fn iter(&self) -> Iter<'_, T> {
+    ///
+    /// The entries are yielded in the order of their slots. Entries that are attached
+    /// later might reuse the slots of detached entries and are therefore not necessarily
+    /// yielded after the existing entries. However, the relative order of the entries
+    /// that remain in the list never changes. In particular, it is preserved when the list
+    /// is compacted. See [raw](crate::unsync::raw) for details.
+    ///
+    /// The iteration order is therefore not necessarily the order in which the entries were
+    /// attached. Unless the ids have wrapped around, the ids of the entries reflect that
+    /// order instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries: Vec<_> = (0..8).map(Rc::new).collect();
+    /// let elements: Vec<_> = entries
+    ///     .iter()
+    ///     .map(|e| WeakListElement::new(Rc::downgrade(e)))
+    ///     .collect();
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// for idx in [0, 2, 4, 5, 6] {
+    ///     elements[idx].detach();
+    /// }
+    /// // Creating the iterator compacts the list.
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [1, 3, 7]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T, I> {
+        let (iter, visibility) = self
+            .start_iteration()
+            .unwrap_or_else(|| self.start_rejected_iteration());
+        Iter {
+            iter,
+            data: &self.data,
+            skipped: 0,
+            peeked: None,
+            visibility,
+            excluded: None,
+        }
+    }
+
+    /// Creates an iterator over the entries of the list unless too many iterators exist.
+    ///
+    /// Returns [Error::TooManyIterators] if the number of iterators over the list has
+    /// reached the limit set with
+    /// [set_max_concurrent_iterations](Self::set_max_concurrent_iterations).
+    /// Otherwise, this function behaves like [iter](Self::iter).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use weak_lists::WeakList;
+    ///
+    /// let list = WeakList::<u32>::default();
+    /// list.set_max_concurrent_iterations(Some(1));
+    /// let iter = list.try_iter();
+    /// assert!(iter.is_ok());
+    /// assert!(list.try_iter().is_err());
+    /// drop(iter);
+    /// assert!(list.try_iter().is_ok());
+    /// ```
+    pub fn try_iter(&self) -> Result<Iter<'_, T, I>, Error> {
+        let (iter, visibility) = self.start_iteration().ok_or(Error::TooManyIterators)?;
+        Ok(Iter {
+            iter,
+            data: &self.data,
+            skipped: 0,
+            peeked: None,
+            visibility,
+            excluded: None,
+        })
+    }
+
+    /// Creates a boxed iterator over the entries of the list.
+    ///
+    /// This function behaves like [iter](Self::iter). It is useful for trait objects that
+    /// cannot name the type of the iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// trait Plugin {
+    ///     fn hooks(&self) -> Box<dyn Iterator<Item = Rc<u32>> + '_>;
+    /// }
+    ///
+    /// struct Host {
+    ///     hooks: WeakList<u32>,
+    /// }
+    ///
+    /// impl Plugin for Host {
+    ///     fn hooks(&self) -> Box<dyn Iterator<Item = Rc<u32>> + '_> {
+    ///         self.hooks.boxed_iter()
+    ///     }
+    /// }
+    ///
+    /// let host = Host { hooks: WeakList::default() };
+    /// let entry = Rc::new(1);
+    /// let element = WeakListElement::new(Rc::downgrade(&entry));
+    /// element.attach(&host.hooks);
+    /// let plugin: &dyn Plugin = &host;
+    /// assert_eq!(plugin.hooks().count(), 1);
+    /// ```
+    pub fn boxed_iter(&self) -> Box<dyn Iterator<Item = Rc<T>> + '_> {
+        Box::new(self.iter())
+    }
+
+    /// Returns whether an iterator over this list currently exists.
+    ///
+    /// This can be used by callbacks to detect that they are being invoked from within an
+    /// iteration over this list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use weak_lists::WeakList;
+    ///
+    /// let list = WeakList::<u32>::default();
+    /// assert!(!list.is_iterating());
+    /// let iter = list.iter();
+    /// assert!(list.is_iterating());
+    /// drop(iter);
+    /// assert!(!list.is_iterating());
+    /// ```
+    pub fn is_iterating(&self) -> bool {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *WeakListData::ptr(&self.data)
+        };
+        data.active_iterators > 0
+    }
+
+    /// Limits the number of iterators that can exist at the same time.
+    ///
+    /// Once `max` iterators over the list exist, [iter](Self::iter) and
+    /// [iter_owned](Self::iter_owned) return iterators that do not yield any entries and
+    /// [try_iter](Self::try_iter) returns [Error::TooManyIterators]. The iterators of the
+    /// dispatch helpers such as [for_each](Self::for_each) are included. This protects
+    /// against callbacks that recursively trigger the dispatch that invoked them.
+    /// Iterators that already exist are not affected, even if they are
+    /// [reset](crate::unsync::Iter::reset).
+    ///
+    /// `None` removes the limit. The default is `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::WeakList;
+    ///
+    /// let list = WeakList::default();
+    /// let entry = Rc::new(1);
+    /// let _element = list.attach_new(&entry);
+    /// list.set_max_concurrent_iterations(Some(2));
+    /// let mut depth = 0;
+    /// fn dispatch(list: &WeakList<i32>, depth: &mut usize) {
+    ///     list.for_each(|_| {
+    ///         *depth += 1;
+    ///         dispatch(list, depth);
+    ///     });
+    /// }
+    /// dispatch(&list, &mut depth);
+    /// assert_eq!(depth, 2);
+    /// ```
+    pub fn set_max_concurrent_iterations(&self, max: Option<usize>) {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *WeakListData::ptr(&self.data)
+        };
+        data.max_concurrent_iterations = max;
+    }
+
+    /// Returns an estimate of the number of bytes allocated by the list.
+    ///
+    /// The estimate is based on the capacity of the internal storage of the list and on
+    /// the state that entries allocate when they use features such as groups or
+    /// keepalive objects. It does not include the size of this object, the elements,
+    /// their targets, or the state of active iterators. This function visits every entry
+    /// of the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entry = Rc::new(1);
+    /// let element = WeakListElement::new(Rc::downgrade(&entry));
+    /// element.attach(&list);
+    /// assert!(list.memory_usage() > 0);
+    /// ```
+    pub fn memory_usage(&self) -> usize {
         let data = unsafe {
             // SAFETY:
             // - While we hold this reference, we do not call any functions that might
             //   create additional references to self.data. This applies to all code that
             //   creates references to self.data.
             // - Therefore, this is an exclusive reference to self.data.
-            // - In particular, the calls to compact and index_len are safe.
-            &mut *self.data.get()
+            &mut *WeakListData::ptr(&self.data)
+        };
+        data.memory_usage()
+    }
+
+    /// Creates an iterator over the entries of the list that does not borrow the list.
+    ///
+    /// This iterator provides the same guarantees as [iter](Self::iter). If the list is
+    /// dropped while the iterator exists, the iterator no longer yields any entries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let mut iter = list.iter_owned();
+    /// assert_eq!(iter.next(), Some(entries[0].clone()));
+    /// drop(list);
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter_owned(&self) -> OwnedIter<T, I> {
+        let (iter, visibility) = self
+            .start_iteration()
+            .unwrap_or_else(|| self.start_rejected_iteration());
+        OwnedIter {
+            iter,
+            visibility,
+            data: Rc::downgrade(&self.data),
+        }
+    }
+
+    /// Creates an iterator over the entries of the list that are also entries of `other`.
+    ///
+    /// Entries are compared by the address of their targets. The entries of `other` are
+    /// determined when this function is called. Otherwise, the iterator provides the same
+    /// guarantees as [iter](Self::iter).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let subscribed = WeakList::default();
+    /// let authorized = WeakList::default();
+    /// let a = Rc::new(1);
+    /// let b = Rc::new(2);
+    /// let a1 = WeakListElement::new(Rc::downgrade(&a));
+    /// let b1 = WeakListElement::new(Rc::downgrade(&b));
+    /// let b2 = WeakListElement::new(Rc::downgrade(&b));
+    /// a1.attach(&subscribed);
+    /// b1.attach(&subscribed);
+    /// b2.attach(&authorized);
+    /// let both: Vec<_> = subscribed.intersect_iter(&authorized).collect();
+    /// assert_eq!(both, [b]);
+    /// ```
+    pub fn intersect_iter(&self, other: &WeakList<T, I>) -> Intersection<'_, T, I> {
+        Intersection {
+            iter: self.iter(),
+            other: TargetSet::of(other),
+        }
+    }
+
+    /// Creates an iterator over the entries of the list that are not entries of `other`.
+    ///
+    /// Entries are compared by the address of their targets. The entries of `other` are
+    /// determined when this function is called. Otherwise, the iterator provides the same
+    /// guarantees as [iter](Self::iter).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let subscribed = WeakList::default();
+    /// let banned = WeakList::default();
+    /// let a = Rc::new(1);
+    /// let b = Rc::new(2);
+    /// let a1 = WeakListElement::new(Rc::downgrade(&a));
+    /// let b1 = WeakListElement::new(Rc::downgrade(&b));
+    /// let b2 = WeakListElement::new(Rc::downgrade(&b));
+    /// a1.attach(&subscribed);
+    /// b1.attach(&subscribed);
+    /// b2.attach(&banned);
+    /// let allowed: Vec<_> = subscribed.difference_iter(&banned).collect();
+    /// assert_eq!(allowed, [a]);
+    /// ```
+    pub fn difference_iter(&self, other: &WeakList<T, I>) -> Difference<'_, T, I> {
+        Difference {
+            iter: self.iter(),
+            other: TargetSet::of(other),
+        }
+    }
+
+    /// Creates an iterator over the entries of the list followed by the entries of
+    /// `other`.
+    ///
+    /// Entries are compared by the address of their targets and each target is returned
+    /// at most once. Otherwise, the iterator provides the same guarantees as
+    /// [iter](Self::iter) for both lists.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let users = WeakList::default();
+    /// let admins = WeakList::default();
+    /// let a = Rc::new(1);
+    /// let b = Rc::new(2);
+    /// let a1 = WeakListElement::new(Rc::downgrade(&a));
+    /// let b1 = WeakListElement::new(Rc::downgrade(&b));
+    /// let b2 = WeakListElement::new(Rc::downgrade(&b));
+    /// a1.attach(&users);
+    /// b1.attach(&users);
+    /// b2.attach(&admins);
+    /// let everyone: Vec<_> = users.union_iter(&admins).collect();
+    /// assert_eq!(everyone, [a, b]);
+    /// ```
+    pub fn union_iter<'a>(&'a self, other: &'a WeakList<T, I>) -> Union<'a, T, I> {
+        Union {
+            first: self.iter(),
+            second: other.iter(),
+            seen: Default::default(),
+        }
+    }
+
+    /// Creates a new list containing the live entries of this list.
+    ///
+    /// The new list is independent of this list. Its entries have the same targets, ids,
+    /// groups, and order as the entries of this list, but they are not associated with any
+    /// [WeakListElement]. Attaching and detaching elements does not affect the other list.
+    ///
+    /// Since there are no elements that detach them, the entries of the new list remain
+    /// in the list until they are removed by id, for example with
+    /// [detach_key](Self::detach_key), or with [drain](Self::drain) or
+    /// [clear](Self::clear).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let copy = list.duplicate();
+    /// elements[0].detach();
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [1]);
+    /// assert_eq!(copy.iter().map(|e| *e).collect::<Vec<_>>(), [0, 1]);
+    /// copy.detach_key(elements[1].id().unwrap());
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [1]);
+    /// assert_eq!(copy.iter().map(|e| *e).collect::<Vec<_>>(), [0]);
+    /// ```
+    pub fn duplicate(&self) -> WeakList<T, I> {
+        let list = WeakListBuilder::default().build_with_id_type();
+        let (data, list_data) = unsafe {
+            // SAFETY:
+            // - While we hold these references, we do not call any functions that might
+            //   create additional references to self.data or list.data. This applies to
+            //   all code that creates references to self.data or list.data.
+            // - Therefore, these are exclusive references.
+            // - The two lists are distinct.
+            // - The insert call never replaces an existing member since ids are unique.
+            (
+                &mut *WeakListData::ptr(&self.data),
+                &mut *WeakListData::ptr(&list.data),
+            )
         };
+        list_data.next_id = data.next_id;
+        list_data.next_seq = data.next_seq;
+        for idx in 0..data.members.index_len() {
+            let Some(member) = data.members.get_by_index(idx) else {
+                continue;
+            };
+            if !member.is_alive() {
+                continue;
+            }
+            list_data.members.insert(
+                member.id,
+                Member {
+                    id: member.id,
+                    t: member.t.clone(),
+                    entry: Rc::new(UnsafeCell::new(EntryData {
+                        id: member.id,
+                        owner: Rc::downgrade(&list.data),
+                        reattach: false,
+                        deferred: false,
+                        enabled: member.enabled,
+                    })),
+                    dead_since: None,
+                    reattach: false,
+                    enabled: member.enabled,
+                    seq: member.seq,
+                    extra: member.extra.as_deref().and_then(|e| {
+                        MemberExtra {
+                            group: e.group,
+                            keepalive: e.keepalive.clone(),
+                            once: e.once,
+                            epoch: e.epoch,
+                            ..Default::default()
+                        }
+                        .boxed()
+                    }),
+                },
+            );
+        }
+        list
+    }
+
+    /// Moves the entries for which `f` returns `true` into a new list.
+    ///
+    /// The elements of the moved entries are attached to the new list.
+    ///
+    /// `f` is invoked for the entries of the list as if by [iter](Self::iter). It may
+    /// modify the list.
+    ///
+    /// The new list has the same settings as this list, for example, its clock, its limits,
+    /// and whether it compacts itself automatically.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let clients = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1), Rc::new(2)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&clients);
+    /// }
+    /// let idle = clients.split_off(|e| **e % 2 == 0);
+    /// assert_eq!(clients.iter().map(|e| *e).collect::<Vec<_>>(), [1]);
+    /// assert_eq!(idle.iter().map(|e| *e).collect::<Vec<_>>(), [0, 2]);
+    /// elements[0].detach();
+    /// assert_eq!(idle.iter().map(|e| *e).collect::<Vec<_>>(), [2]);
+    /// ```
+    pub fn split_off(&self, mut f: impl FnMut(&Rc<T>) -> bool) -> WeakList<T, I> {
+        let list = WeakListBuilder::default().build_with_id_type();
+        unsafe {
+            // SAFETY:
+            // - While we hold these references, we do not call any functions that might
+            //   create additional references to self.data or list.data. This applies to
+            //   all code that creates references to self.data or list.data.
+            // - The new list is distinct from this list.
+            // - Therefore, these are exclusive references.
+            // - The clone call in copy_settings only clones an Rc.
+            (*WeakListData::ptr(&list.data)).copy_settings(&*WeakListData::ptr(&self.data));
+        }
+        let mut iter = self.iter();
+        while let Some((id, t)) = iter.next_with_id() {
+            if f(&t) {
+                self.move_member(id, &list);
+            }
+        }
+        list
+    }
+
+    /// Detaches the member with the given id from this list.
+    ///
+    /// Returns `false` if the list does not contain such a member.
+    pub(super) fn detach_member(&self, id: I) -> bool {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            // - The same applies to the reference to member.entry below.
+            // - In particular, dropping the Member object below will never run the drop
+            //   impl of T itself.
+            &mut *WeakListData::ptr(&self.data)
+        };
+        if let Some(member) = data.remove_member(id) {
+            let entry = unsafe {
+                // SAFETY: See the previous safety comment.
+                &mut *member.entry.get()
+            };
+            entry.owner = Weak::new();
+            return true;
+        }
+        false
+    }
+
+    /// Moves the member with the given id to another list.
+    ///
+    /// `to` must not be this list.
+    fn move_member(&self, id: I, to: &WeakList<T, I>) {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            // - The same applies to the references to to.data and member.entry below.
+            //   These references do not alias since to and self are distinct lists.
+            // - The remove and insert calls only run well-known code that does not
+            //   depend on T.
+            &mut *WeakListData::ptr(&self.data)
+        };
+        let Some(mut member) = data.remove_member(id) else {
+            return;
+        };
+        let to_data = unsafe {
+            // SAFETY: See the previous safety comment.
+            &mut *WeakListData::ptr(&to.data)
+        };
+        let entry = unsafe {
+            // SAFETY: See the previous safety comment.
+            &mut *member.entry.get()
+        };
+        // The other list never contains more members than have been removed from this
+        // list. Therefore an id is always available.
+        let Ok(new_id) = to_data.allocate_id() else {
+            unreachable!();
+        };
+        member.id = new_id;
+        member.dead_since = None;
+        entry.id = member.id;
+        entry.owner = Rc::downgrade(&to.data);
+        to_data.members.insert(member.id, member);
+    }
+
+    /// Registers a new iterator and returns the range of indices it has to visit.
+    ///
+    /// Returns `None` without registering the iterator if the limit set with
+    /// [set_max_concurrent_iterations](Self::set_max_concurrent_iterations) has been
+    /// reached.
+    fn start_iteration(&self) -> Option<(Range<usize>, Visibility<I>)> {
+        let now = read_clock(&self.data);
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *WeakListData::ptr(&self.data)
+        };
+        if let Some(max) = data.max_concurrent_iterations {
+            if data.active_iterators >= max {
+                return None;
+            }
+        }
         if data.active_iterators == 0 {
-            data.members.compact();
+            data.coalesced.clear();
+            if data.auto_compact {
+                data.compact();
+            }
         }
+        let visibility = data.start_visibility(now);
         data.active_iterators += 1;
-        Iter {
-            iter: 0..data.members.index_len(),
-            data: &self.data,
+        Some((0..data.members.index_len(), visibility))
+    }
+
+    /// Registers an iterator that was rejected by [start_iteration](Self::start_iteration).
+    ///
+    /// The iterator does not visit any indices.
+    fn start_rejected_iteration(&self) -> (Range<usize>, Visibility<I>) {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *WeakListData::ptr(&self.data)
+        };
+        data.active_iterators += 1;
+        (0..0, Visibility::finished())
+    }
+}
+
+impl<T, I> WeakListData<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns a pointer to the list data.
+    ///
+    /// With the `paranoid` feature, this function panics if the data is currently borrowed
+    /// by an iterator. This detects code that accesses the list from within the section
+    /// of [Iter::next](crate::unsync::Iter::next) that holds a reference to the data.
+    pub(super) fn ptr(data: &UnsafeCell<Self>) -> *mut Self {
+        let ptr = data.get();
+        #[cfg(feature = "paranoid")]
+        {
+            let borrowed = unsafe {
+                // SAFETY: The flag is read without creating a reference to the data.
+                ptr::addr_of!((*ptr).borrowed).read()
+            };
+            assert!(!borrowed, "list data is already borrowed by an iterator");
+        }
+        ptr
+    }
+
+    /// Returns an estimate of the number of bytes allocated by this object.
+    pub(super) fn memory_usage(&self) -> usize {
+        let member = mem::size_of::<I>() + mem::size_of::<usize>() + mem::size_of::<Member<T, I>>();
+        let child = mem::size_of::<Weak<UnsafeCell<WeakListData<T, I>>>>();
+        self.members.capacity() * member
+            + self.pending.capacity() * mem::size_of::<Member<T, I>>()
+            + self.late.capacity() * mem::size_of::<(I, usize)>()
+            + self.coalesced.capacity() * mem::size_of::<u64>()
+            + self.children.capacity() * child
+            + self.extra_count() * mem::size_of::<MemberExtra>()
+    }
+
+    /// Returns the number of members, including pending members, that have allocated
+    /// extra state.
+    fn extra_count(&self) -> usize {
+        let members =
+            (0..self.members.index_len()).filter_map(|idx| self.members.get_by_index(idx));
+        members
+            .chain(&self.pending)
+            .filter(|member| member.extra.is_some())
+            .count()
+    }
+
+    /// Copies the settings of another list into this list.
+    ///
+    /// This includes the settings of the builder and of the setters of the list, but not
+    /// the state of the list.
+    fn copy_settings(&mut self, other: &Self) {
+        self.auto_compact = other.auto_compact;
+        self.clock = other.clock.clone();
+        self.track_invocations = other.track_invocations;
+        self.max_concurrent_iterations = other.max_concurrent_iterations;
+        self.max_entries_per_target = other.max_entries_per_target;
+    }
+
+    /// Removes a member and updates the number of dead members.
+    pub(super) fn remove_member(&mut self, id: I) -> Option<Member<T, I>> {
+        let member = self.members.remove(&id)?;
+        if member.dead_since.is_some() {
+            self.known_dead -= 1;
+        }
+        Some(member)
+    }
+
+    /// Allocates an id for a new member.
+    ///
+    /// The id is not used by any member of the list. Returns [Error::IdsExhausted] if
+    /// there is no such id.
+    pub(super) fn allocate_id(&mut self) -> Result<I, Error> {
+        let first = self.next_id;
+        loop {
+            let id = self.next_id;
+            self.next_id = id.wrapping_next();
+            if !self.members.contains_key(&id) {
+                return Ok(id);
+            }
+            if self.next_id == first {
+                return Err(Error::IdsExhausted);
+            }
+        }
+    }
+
+    /// Compacts the member map.
+    ///
+    /// This must only be called if there are no active iterators.
+    pub(super) fn compact(&mut self) {
+        let len = self.members.index_len();
+        let cursor = (0..self.cursor.min(len))
+            .filter(|&idx| self.members.get_by_index(idx).is_some())
+            .count();
+        self.members.compact();
+        if self.members.index_len() != len {
+            self.cursor = cursor;
+            self.compaction_epoch += 1;
         }
     }
 }
@@ -73,31 +1055,27 @@ where
     T: ?Sized,
 {
     fn default() -> Self {
-        Self {
-            data: Rc::new(UnsafeCell::new(WeakListData {
-                next_id: 0,
-                active_iterators: 0,
-                members: Default::default(),
-            })),
-        }
+        WeakListBuilder::default().build()
     }
 }
 
-impl<'a, T> IntoIterator for &'a WeakList<T>
+impl<'a, T, I> IntoIterator for &'a WeakList<T, I>
 where
     T: ?Sized,
+    I: EntryId,
 {
     type Item = Rc<T>;
-    type IntoIter = Iter<'a, T>;
+    type IntoIter = Iter<'a, T, I>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.iter()
     }
 }
 
-impl<T> Debug for WeakList<T>
+impl<T, I> Debug for WeakList<T, I>
 where
     T: ?Sized,
+    I: EntryId,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("WeakList")
@@ -0,0 +1,94 @@
+use {
+    crate::{
+        id::MembershipId,
+        unsync::{MultiWeakListElement, WeakList, WeakListElement},
+    },
+    alloc::{rc::Weak, vec::Vec},
+    core::{
+        cell::{Cell, RefCell},
+        fmt::{Debug, Formatter},
+        mem,
+    },
+};
+
+// Attaching and detaching an element never runs code that depends on T. Therefore the
+// memberships are never borrowed recursively.
+
+impl<T> MultiWeakListElement<T>
+where
+    T: ?Sized,
+{
+    /// Creates a new list element.
+    ///
+    /// See [WeakListElement::new].
+    pub fn new(t: Weak<T>) -> Self {
+        Self {
+            t,
+            next_id: Cell::new(0),
+            memberships: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Attaches the element to a list and returns the id of the new membership.
+    ///
+    /// The existing memberships of the element are not affected. Memberships that have
+    /// ended because their list has been dropped or cleared are forgotten.
+    pub fn attach(&self, to: &WeakList<T>) -> MembershipId {
+        let id = MembershipId(self.next_id.get());
+        self.next_id.set(id.0 + 1);
+        let element = WeakListElement::new(self.t.clone());
+        element.attach(to);
+        let stale: Vec<_> = {
+            let memberships = &mut *self.memberships.borrow_mut();
+            let (live, stale) = mem::take(memberships)
+                .into_iter()
+                .partition(|(_, e)| e.id().is_some());
+            *memberships = live;
+            memberships.push((id, element));
+            stale
+        };
+        drop(stale);
+        id
+    }
+
+    /// Detaches the element from the list of a membership.
+    ///
+    /// Returns whether the element had a membership with this id.
+    pub fn detach(&self, id: MembershipId) -> bool {
+        let element = {
+            let memberships = &mut *self.memberships.borrow_mut();
+            let Some(idx) = memberships.iter().position(|(m, _)| *m == id) else {
+                return false;
+            };
+            memberships.swap_remove(idx)
+        };
+        drop(element);
+        true
+    }
+
+    /// Detaches the element from all of its lists.
+    pub fn detach_all(&self) {
+        let memberships = mem::take(&mut *self.memberships.borrow_mut());
+        drop(memberships);
+    }
+
+    /// Returns whether the element is attached to a list through the membership.
+    pub fn is_attached(&self, id: MembershipId) -> bool {
+        self.memberships
+            .borrow()
+            .iter()
+            .any(|(m, e)| *m == id && e.id().is_some())
+    }
+}
+
+impl<T> Debug for MultiWeakListElement<T>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let memberships = self.memberships.borrow();
+        f.debug_map()
+            .entries(memberships.iter().map(|(id, e)| (id, e)))
+            .finish()
+    }
+}
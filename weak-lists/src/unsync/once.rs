@@ -0,0 +1,58 @@
+use crate::{
+    id::EntryId,
+    unsync::{element::AttachOptions, WeakList, WeakListData, WeakListElement},
+};
+
+impl<T, I> WeakListElement<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Attaches the element to a list until its entry has been yielded once.
+    ///
+    /// This function behaves like [attach](Self::attach) except that the element is
+    /// detached as soon as an iterator over the list yields its entry. This includes the
+    /// iterators used by the dispatch helpers such as [for_each](WeakList::for_each).
+    /// Therefore, the entry is yielded by at most one iterator. Entries whose targets have
+    /// been dropped are not yielded and therefore remain attached.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// elements[0].attach_once(&list);
+    /// elements[1].attach(&list);
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [0, 1]);
+    /// assert_eq!(elements[0].id(), None);
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [1]);
+    /// ```
+    pub fn attach_once(&self, to: &WeakList<T, I>) {
+        let _ = self.attach_with_options(
+            to,
+            AttachOptions {
+                once: true,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+impl<T, I> WeakListData<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Detaches the member with the id after it has been yielded by an iterator.
+    ///
+    /// The member must have been attached with [attach_once](WeakListElement::attach_once).
+    pub(super) fn detach_once(&mut self, id: I) {
+        if let Some(member) = self.remove_member(id) {
+            member.detach_removed();
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use {
+    crate::{
+        error::Error,
+        unsync::{
+            element::AttachOptions, PairedWeakListElement, WeakList, WeakListData, WeakListElement,
+        },
+    },
+    alloc::rc::{Rc, Weak},
+    core::fmt::{Debug, Formatter},
+};
+
+// Attaching and detaching an element never runs code that depends on T. Therefore no other
+// code can observe the element in only one of the two lists.
+
+impl<T> PairedWeakListElement<T>
+where
+    T: ?Sized,
+{
+    /// Creates a new list element.
+    ///
+    /// See [WeakListElement::new].
+    pub fn new(t: Weak<T>) -> Self {
+        Self {
+            global: WeakListElement::new(t.clone()),
+            parent: WeakListElement::new(t),
+        }
+    }
+
+    /// Attaches the element to two lists.
+    ///
+    /// If this element was previously attached to lists, it is automatically detached
+    /// from those lists. `global` and `parent` may be the same list, in which case the
+    /// element is contained in the list twice.
+    ///
+    /// If the element cannot be attached to one of the lists, it is attached to neither.
+    /// See [try_attach](Self::try_attach).
+    pub fn attach(&self, global: &WeakList<T>, parent: &WeakList<T>) {
+        let _ = self.try_attach(global, parent);
+    }
+
+    /// Attaches the element to two lists and returns whether it was attached.
+    ///
+    /// This function behaves like [attach](Self::attach). If the element cannot be
+    /// attached to one of the lists, it is left detached from both lists and an error is
+    /// returned:
+    ///
+    /// - [Error::TargetLimitReached] if attaching the element would exceed the
+    ///   [max_entries_per_target](crate::unsync::WeakListBuilder::max_entries_per_target)
+    ///   limit of either list.
+    /// - [Error::IdsExhausted] if all ids of either list are in use.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{
+    ///     unsync::{PairedWeakListElement, WeakListBuilder},
+    ///     Error, WeakList,
+    /// };
+    ///
+    /// let global = WeakList::default();
+    /// let parent = WeakListBuilder::default().max_entries_per_target(1).build();
+    /// let entry = Rc::new(1);
+    /// let elements = [(); 2].map(|_| PairedWeakListElement::new(Rc::downgrade(&entry)));
+    /// assert_eq!(elements[0].try_attach(&global, &parent), Ok(()));
+    /// let res = elements[1].try_attach(&global, &parent);
+    /// assert_eq!(res, Err(Error::TargetLimitReached));
+    /// assert_eq!(global.iter().count(), 1);
+    /// ```
+    pub fn try_attach(&self, global: &WeakList<T>, parent: &WeakList<T>) -> Result<(), Error> {
+        self.detach();
+        let budget = |list: &WeakList<T>| {
+            let data = unsafe {
+                // SAFETY:
+                // - While we hold this reference, we do not call any functions that might
+                //   create additional references to list.data. This applies to all code
+                //   that creates references to list.data.
+                // - Therefore, this is an exclusive reference to list.data.
+                &mut *WeakListData::ptr(&list.data)
+            };
+            data.target_budget(&self.global.t)
+        };
+        let fits = if Rc::ptr_eq(&global.data, &parent.data) {
+            budget(global) >= 2
+        } else {
+            budget(global) >= 1 && budget(parent) >= 1
+        };
+        if !fits {
+            return Err(Error::TargetLimitReached);
+        }
+        self.global
+            .attach_with_options(global, AttachOptions::default())?;
+        if let Err(e) = self
+            .parent
+            .attach_with_options(parent, AttachOptions::default())
+        {
+            self.global.detach();
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Detaches the element from both of its lists.
+    pub fn detach(&self) {
+        self.global.detach();
+        self.parent.detach();
+    }
+}
+
+impl<T> Debug for PairedWeakListElement<T>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PairedWeakListElement")
+            .field("global", &self.global)
+            .field("parent", &self.parent)
+            .finish_non_exhaustive()
+    }
+}
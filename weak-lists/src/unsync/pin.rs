@@ -0,0 +1,141 @@
+use {
+    crate::unsync::{PinIter, PinWeakList, PinWeakListElement, WeakListElement},
+    alloc::rc::Rc,
+    core::{
+        fmt::{Debug, Formatter},
+        iter::FusedIterator,
+        pin::Pin,
+    },
+};
+
+impl<T> PinWeakList<T>
+where
+    T: ?Sized,
+{
+    /// Removes all elements from the list.
+    pub fn clear(&self) {
+        self.list.clear();
+    }
+
+    /// Creates an iterator over the entries of the list.
+    ///
+    /// This iterator provides the same guarantees as [WeakList::iter](crate::unsync::WeakList::iter).
+    pub fn iter(&self) -> PinIter<'_, T> {
+        PinIter {
+            iter: self.list.iter(),
+        }
+    }
+}
+
+impl<T> PinWeakListElement<T>
+where
+    T: ?Sized,
+{
+    /// Creates a new list element.
+    pub fn new(t: &Pin<Rc<T>>) -> Self {
+        let t = unsafe {
+            // SAFETY: The Rc is only used to create a Weak. Since the Weak can only be
+            //         upgraded by the iterators of PinWeakList, which pin the upgraded
+            //         Rc again, the pinning invariant is upheld.
+            Pin::into_inner_unchecked(t.clone())
+        };
+        Self {
+            element: WeakListElement::new(Rc::downgrade(&t)),
+        }
+    }
+
+    /// Attaches the element to a list.
+    ///
+    /// See [WeakListElement::attach].
+    pub fn attach(&self, to: &PinWeakList<T>) {
+        self.element.attach(&to.list);
+    }
+
+    /// Detaches the element from its current list.
+    ///
+    /// See [WeakListElement::detach].
+    pub fn detach(&self) {
+        self.element.detach();
+    }
+}
+
+impl<T> Default for PinWeakList<T>
+where
+    T: ?Sized,
+{
+    fn default() -> Self {
+        Self {
+            list: Default::default(),
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a PinWeakList<T>
+where
+    T: ?Sized,
+{
+    type Item = Pin<Rc<T>>;
+    type IntoIter = PinIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> Debug for PinWeakList<T>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PinWeakList")
+            .field("list", &self.list)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Debug for PinWeakListElement<T>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PinWeakListElement")
+            .field("element", &self.element)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Iterator for PinIter<'_, T>
+where
+    T: ?Sized,
+{
+    type Item = Pin<Rc<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|t| unsafe {
+            // SAFETY: All elements of a PinWeakList were created from pinned Rcs.
+            Pin::new_unchecked(t)
+        })
+    }
+}
+
+impl<T> Clone for PinIter<'_, T>
+where
+    T: ?Sized,
+{
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<T> Debug for PinIter<'_, T>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(&self.iter, f)
+    }
+}
+
+impl<T> FusedIterator for PinIter<'_, T> where T: ?Sized {}
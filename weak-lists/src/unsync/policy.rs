@@ -0,0 +1,142 @@
+use {
+    crate::{
+        id::EntryId,
+        policy::LivenessPolicy,
+        unsync::{PolicyIter, PolicyWeakList, WeakList},
+    },
+    alloc::rc::Rc,
+    core::{
+        fmt::{Debug, Formatter},
+        iter::FusedIterator,
+        marker::PhantomData,
+    },
+};
+
+impl<T, P, I> PolicyWeakList<T, P, I>
+where
+    T: ?Sized,
+    P: LivenessPolicy<T>,
+    I: EntryId,
+{
+    /// Wraps a list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use weak_lists::{policy::Upgrade, unsync::{PolicyWeakList, WeakListBuilder}};
+    ///
+    /// let list = WeakListBuilder::default().capacity(16).build();
+    /// let list = PolicyWeakList::<u32, Upgrade>::from_list(list);
+    /// assert!(list.iter().next().is_none());
+    /// ```
+    pub fn from_list(list: WeakList<T, I>) -> Self {
+        Self {
+            list,
+            policy: PhantomData,
+        }
+    }
+
+    /// Returns the wrapped list.
+    ///
+    /// Elements are attached to this list. Iterators created from it directly do not
+    /// apply the policy.
+    pub fn list(&self) -> &WeakList<T, I> {
+        &self.list
+    }
+
+    /// Unwraps the list.
+    pub fn into_list(self) -> WeakList<T, I> {
+        self.list
+    }
+
+    /// Creates an iterator over the entries of the list that are accepted by the policy.
+    ///
+    /// Otherwise, this iterator provides the same guarantees as [WeakList::iter].
+    pub fn iter(&self) -> PolicyIter<'_, T, P, I> {
+        PolicyIter {
+            iter: self.list.iter(),
+            policy: PhantomData,
+        }
+    }
+}
+
+impl<T, P> Default for PolicyWeakList<T, P>
+where
+    T: ?Sized,
+    P: LivenessPolicy<T>,
+{
+    fn default() -> Self {
+        Self::from_list(WeakList::default())
+    }
+}
+
+impl<T, P, I> Debug for PolicyWeakList<T, P, I>
+where
+    T: ?Sized,
+    P: LivenessPolicy<T>,
+    I: EntryId,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("PolicyWeakList").field(&self.list).finish()
+    }
+}
+
+impl<'a, T, P, I> IntoIterator for &'a PolicyWeakList<T, P, I>
+where
+    T: ?Sized,
+    P: LivenessPolicy<T>,
+    I: EntryId,
+{
+    type Item = Rc<T>;
+    type IntoIter = PolicyIter<'a, T, P, I>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T, P, I> Iterator for PolicyIter<'_, T, P, I>
+where
+    T: ?Sized,
+    P: LivenessPolicy<T>,
+    I: EntryId,
+{
+    type Item = Rc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.by_ref().find(|t| P::is_live(t))
+    }
+}
+
+impl<T, P, I> Clone for PolicyIter<'_, T, P, I>
+where
+    T: ?Sized,
+    P: LivenessPolicy<T>,
+    I: EntryId,
+{
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            policy: PhantomData,
+        }
+    }
+}
+
+impl<T, P, I> Debug for PolicyIter<'_, T, P, I>
+where
+    T: ?Sized,
+    P: LivenessPolicy<T>,
+    I: EntryId,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("PolicyIter").field(&self.iter).finish()
+    }
+}
+
+impl<T, P, I> FusedIterator for PolicyIter<'_, T, P, I>
+where
+    T: ?Sized,
+    P: LivenessPolicy<T>,
+    I: EntryId,
+{
+}
@@ -0,0 +1,302 @@
+//! Low-level access to the entries of a [WeakList].
+//!
+//! This module is intended for frameworks that want to implement their own iteration
+//! strategies. It exposes the storage of the list directly.
+//!
+//! The entries of a list are stored in slots. The following invariants hold:
+//!
+//! - Every entry has an id that is unique within its list. Ids allocated by the list are
+//!   only reused after the id type has been exhausted. See [EntryId].
+//!   Ids can also be chosen by the caller with [WeakListElement::attach_keyed]. The id of an
+//!   element can be retrieved with [WeakListElement::id].
+//! - The slot of an entry does not change until the list is compacted. Compaction removes
+//!   empty slots and moves the remaining entries to lower slots, preserving their order.
+//!   To amortize its cost, compaction does nothing if only few slots are empty.
+//! - When an entry is detached, its slot becomes empty. Empty slots might be reused by
+//!   entries that are attached later.
+//! - The list is never compacted while an iterator is active. Otherwise, the list compacts
+//!   itself when an iterator is created or [reset](crate::unsync::Iter::reset). This can
+//!   be disabled with [set_auto_compact](RawWeakList::set_auto_compact).
+//!
+//! [WeakListElement::id]: crate::unsync::WeakListElement::id
+//! [WeakListElement::attach_keyed]: crate::unsync::WeakListElement::attach_keyed
+
+use {
+    crate::{
+        id::EntryId,
+        unsync::{IndexedIter, WeakList, WeakListData},
+    },
+    alloc::rc::{Rc, Weak},
+    core::{
+        fmt::{Debug, Formatter},
+        iter::FusedIterator,
+    },
+};
+
+/// Low-level access to the entries of a [WeakList].
+///
+/// This object is created by calling [raw](WeakList::raw). See the
+/// [module documentation](self) for the invariants of the storage.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use weak_lists::{WeakList, WeakListElement};
+///
+/// let list = WeakList::default();
+/// let entries = [Rc::new(0), Rc::new(1), Rc::new(2)];
+/// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+/// for element in &elements {
+///     element.attach(&list);
+/// }
+/// let raw = list.raw();
+/// raw.set_auto_compact(false);
+/// elements[0].detach();
+/// elements[1].detach();
+/// assert_eq!(raw.slots(), 3);
+/// assert!(raw.slot(0).is_none());
+/// let (id, target) = raw.slot(2).unwrap();
+/// assert_eq!(Some(id), elements[2].id());
+/// assert_eq!(target.upgrade(), Some(entries[2].clone()));
+/// assert!(raw.compact());
+/// assert_eq!(raw.slots(), 1);
+/// ```
+pub struct RawWeakList<'a, T, I = u64>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    list: &'a WeakList<T, I>,
+}
+
+impl<T, I> WeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns an object providing low-level access to the entries of the list.
+    ///
+    /// See [raw](crate::unsync::raw) for details.
+    pub fn raw(&self) -> RawWeakList<'_, T, I> {
+        RawWeakList { list: self }
+    }
+
+    /// Creates an iterator over the entries of the list and their slots.
+    ///
+    /// This iterator behaves like [iter](Self::iter) but also yields the slot of each
+    /// entry. Since the list is never compacted while an iterator is active, the slots
+    /// remain valid until the iterator is dropped. Afterwards, they remain valid until
+    /// the [compaction_epoch](Self::compaction_epoch) changes. A slot can be reused by a
+    /// different entry after its entry has been detached.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let mut scratch = vec![0; list.raw().slots()];
+    /// for (slot, e) in list.iter_indexed() {
+    ///     scratch[slot] = *e + 1;
+    /// }
+    /// assert_eq!(scratch, [1, 2]);
+    /// ```
+    pub fn iter_indexed(&self) -> IndexedIter<'_, T, I> {
+        IndexedIter { iter: self.iter() }
+    }
+
+    /// Returns a counter that changes whenever the entries of the list move to different
+    /// slots.
+    ///
+    /// This happens when the list is compacted or cleared. See [raw](crate::unsync::raw).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entry = Rc::new(1);
+    /// let element = WeakListElement::new(Rc::downgrade(&entry));
+    /// element.attach(&list);
+    /// let epoch = list.compaction_epoch();
+    /// list.clear();
+    /// assert_ne!(list.compaction_epoch(), epoch);
+    /// ```
+    pub fn compaction_epoch(&self) -> u64 {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *WeakListData::ptr(&self.data)
+        };
+        data.compaction_epoch
+    }
+}
+
+impl<T, I> RawWeakList<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns the number of slots.
+    ///
+    /// This includes empty slots.
+    pub fn slots(&self) -> usize {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.list.data. This applies to all code
+            //   that creates references to self.list.data.
+            // - Therefore, this is an exclusive reference to self.list.data.
+            &mut *WeakListData::ptr(&self.list.data)
+        };
+        data.members.index_len()
+    }
+
+    /// Returns the id and the target of the entry in a slot.
+    ///
+    /// Returns `None` if the slot is empty or out of bounds.
+    pub fn slot(&self, slot: usize) -> Option<(I, Weak<T>)> {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.list.data. This applies to all code
+            //   that creates references to self.list.data.
+            // - Therefore, this is an exclusive reference to self.list.data.
+            // - The clone call only clones a Weak.
+            &mut *WeakListData::ptr(&self.list.data)
+        };
+        let member = data.members.get_by_index(slot)?;
+        Some((member.id, member.t.clone()))
+    }
+
+    /// Returns the target of the entry with the given id.
+    ///
+    /// Returns `None` if no entry with this id is attached to the list.
+    pub fn get(&self, id: I) -> Option<Weak<T>> {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.list.data. This applies to all code
+            //   that creates references to self.list.data.
+            // - Therefore, this is an exclusive reference to self.list.data.
+            // - The clone call only clones a Weak.
+            &mut *WeakListData::ptr(&self.list.data)
+        };
+        data.members.get(&id).map(|m| m.t.clone())
+    }
+
+    /// Compacts the list.
+    ///
+    /// Returns `false` and leaves the list unchanged if an iterator is active.
+    pub fn compact(&self) -> bool {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.list.data. This applies to all code
+            //   that creates references to self.list.data.
+            // - Therefore, this is an exclusive reference to self.list.data.
+            &mut *WeakListData::ptr(&self.list.data)
+        };
+        if data.active_iterators > 0 {
+            return false;
+        }
+        data.compact();
+        true
+    }
+
+    /// Returns whether the list compacts itself automatically.
+    ///
+    /// The default is `true`.
+    pub fn auto_compact(&self) -> bool {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.list.data. This applies to all code
+            //   that creates references to self.list.data.
+            // - Therefore, this is an exclusive reference to self.list.data.
+            &mut *WeakListData::ptr(&self.list.data)
+        };
+        data.auto_compact
+    }
+
+    /// Sets whether the list compacts itself automatically.
+    ///
+    /// If this is disabled, slots only change when [compact](Self::compact) is called,
+    /// when entries are attached or detached, and when the list is cleared.
+    pub fn set_auto_compact(&self, auto_compact: bool) {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.list.data. This applies to all code
+            //   that creates references to self.list.data.
+            // - Therefore, this is an exclusive reference to self.list.data.
+            &mut *WeakListData::ptr(&self.list.data)
+        };
+        data.auto_compact = auto_compact;
+    }
+}
+
+impl<T, I> Debug for RawWeakList<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RawWeakList")
+            .field("list", self.list)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, I> Iterator for IndexedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    type Item = (usize, Rc<T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next_with_index().map(|(idx, _, t)| (idx, t))
+    }
+}
+
+impl<T, I> Clone for IndexedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<T, I> Debug for IndexedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(&self.iter, f)
+    }
+}
+
+impl<T, I> FusedIterator for IndexedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+}
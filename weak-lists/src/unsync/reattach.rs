@@ -0,0 +1,152 @@
+use {
+    crate::{
+        id::EntryId,
+        unsync::{Member, WeakList, WeakListData, WeakListElement},
+    },
+    alloc::{
+        rc::{Rc, Weak},
+        vec::Vec,
+    },
+    core::{mem, ptr},
+};
+
+impl<T, I> WeakListElement<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Sets whether the element is reattached after its list has been cleared.
+    ///
+    /// If this is enabled and the list that the element is attached to is
+    /// [cleared](WeakList::clear), the element is marked as pending. A later call to
+    /// [reopen_pending](WeakList::reopen_pending) attaches it to the list again. Attaching
+    /// the element to another list, detaching it, or dropping it removes the mark.
+    ///
+    /// This has no effect if the list is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// elements[0].set_reattach_on_clear(true);
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// list.clear();
+    /// assert!(list.iter().next().is_none());
+    /// assert_eq!(list.reopen_pending(), 1);
+    /// assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [0]);
+    /// ```
+    pub fn set_reattach_on_clear(&self, reattach: bool) {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            &mut *self.data.get()
+        };
+        data.reattach = reattach;
+        if let Some(list) = data.owner.upgrade() {
+            let list_data = unsafe {
+                // SAFETY: See the previous safety comment.
+                &mut *WeakListData::ptr(&list)
+            };
+            if let Some(member) = list_data.members.get_mut(&data.id) {
+                if Rc::ptr_eq(&member.entry, &self.data) {
+                    member.reattach = reattach;
+                }
+            }
+        }
+    }
+}
+
+impl<T, I> WeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Reattaches the elements that were marked as pending when the list was cleared.
+    ///
+    /// See [set_reattach_on_clear](WeakListElement::set_reattach_on_clear). Elements that
+    /// have been detached, attached to another list, or dropped since the list was cleared
+    /// are ignored. The reattached elements keep their ids unless an id has been reused in
+    /// the meantime. If no other id is available, the element is detached instead.
+    ///
+    /// Returns the number of elements that were reattached.
+    pub fn reopen_pending(&self) -> usize {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            // - The same applies to the references to the entries below.
+            // - Dropping the stale members below never runs the drop impl of T.
+            &mut *WeakListData::ptr(&self.data)
+        };
+        let pending = mem::take(&mut data.pending);
+        let mut reopened = 0;
+        for mut member in pending {
+            let entry = unsafe {
+                // SAFETY: See the previous safety comment.
+                &mut *member.entry.get()
+            };
+            let is_pending = entry.reattach
+                && entry.id == member.id
+                && ptr::eq(entry.owner.as_ptr(), &*self.data);
+            let is_member = match data.members.get(&member.id) {
+                Some(m) => Rc::ptr_eq(&m.entry, &member.entry),
+                None => false,
+            };
+            if !is_pending || is_member {
+                continue;
+            }
+            if data.members.contains_key(&member.id) {
+                let Ok(id) = data.allocate_id() else {
+                    entry.owner = Weak::new();
+                    continue;
+                };
+                member.id = id;
+                entry.id = id;
+            }
+            member.dead_since = None;
+            member.reattach = true;
+            member.enabled = entry.enabled;
+            data.members.insert(member.id, member);
+            reopened += 1;
+        }
+        reopened
+    }
+}
+
+impl<T, I> WeakListData<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Removes all members. The members whose elements have requested to be reattached
+    /// are moved to the pending members. The other members are returned.
+    pub(super) fn clear_members(&mut self) -> Vec<Member<T, I>> {
+        let mut removed = Vec::new();
+        for idx in 0..self.members.index_len() {
+            let Some(id) = self.members.get_by_index(idx).map(|m| m.id) else {
+                continue;
+            };
+            if let Some(member) = self.remove_member(id) {
+                match member.reattach {
+                    true => self.pending.push(member),
+                    false => removed.push(member),
+                }
+            }
+        }
+        self.members.clear();
+        self.compaction_epoch += 1;
+        removed
+    }
+}
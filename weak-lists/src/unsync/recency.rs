@@ -0,0 +1,202 @@
+use {
+    crate::{
+        id::EntryId,
+        unsync::{RecencyIter, WeakList, WeakListData, WeakListElement},
+    },
+    alloc::{rc::Rc, vec::Vec},
+    core::{
+        cmp::Reverse,
+        fmt::{Debug, Formatter},
+        iter::FusedIterator,
+    },
+};
+
+impl<T, I> WeakListElement<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns the sequence number of the attachment of this element to its current list.
+    ///
+    /// Each list numbers the attachments of elements in the order in which they happen,
+    /// starting at 0. Replacing an entry with [replace](WeakList::replace) counts as an
+    /// attachment of the new element. Elements that are reattached by
+    /// [reopen_pending](WeakList::reopen_pending) keep their sequence numbers.
+    ///
+    /// Returns `None` if the element is not attached to a list.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entry = Rc::new(1);
+    /// let element = WeakListElement::new(Rc::downgrade(&entry));
+    /// assert_eq!(element.attach_seq(), None);
+    /// element.attach(&list);
+    /// assert_eq!(element.attach_seq(), Some(0));
+    /// element.attach(&list);
+    /// assert_eq!(element.attach_seq(), Some(1));
+    /// ```
+    pub fn attach_seq(&self) -> Option<u64> {
+        let data = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to self.data. This applies to all code that
+            //   creates references to self.data.
+            // - Therefore, this is an exclusive reference to self.data.
+            // - The same applies to the reference to the list below.
+            // - The list is kept alive by its owner while it is upgraded here. Therefore,
+            //   dropping the upgraded Rc never drops the list.
+            &mut *self.data.get()
+        };
+        let list = data.owner.upgrade()?;
+        let list_data = unsafe {
+            // SAFETY: See the previous safety comment.
+            &mut *WeakListData::ptr(&list)
+        };
+        let member = list_data.members.get(&data.id)?;
+        Rc::ptr_eq(&member.entry, &self.data).then_some(member.seq)
+    }
+}
+
+impl<T, I> WeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Creates an iterator that yields the most recently attached entries first.
+    ///
+    /// The entries are ordered by their [attach_seq](WeakListElement::attach_seq). The
+    /// order is determined when the iterator is created. Entries that are attached
+    /// afterwards are not yielded. Entries that are detached before they are yielded are
+    /// skipped. Otherwise, this iterator provides the same guarantees as
+    /// [iter](Self::iter).
+    ///
+    /// This function has to sort the entries and is intended for diagnostics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1), Rc::new(2)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// elements[0].attach(&list);
+    /// let recent: Vec<_> = list.iter_by_recency().map(|e| *e).collect();
+    /// assert_eq!(recent, [0, 2, 1]);
+    /// ```
+    pub fn iter_by_recency(&self) -> RecencyIter<'_, T, I> {
+        let iter = self.iter();
+        let mut entries: Vec<_> = {
+            let data = unsafe {
+                // SAFETY:
+                // - While we hold this reference, we do not call any functions that might
+                //   create additional references to self.data. This applies to all code
+                //   that creates references to self.data.
+                // - Therefore, this is an exclusive reference to self.data.
+                &mut *WeakListData::ptr(&self.data)
+            };
+            (0..data.members.index_len())
+                .filter_map(|idx| data.members.get_by_index(idx))
+                .filter(|member| member.is_visible_to(&iter.visibility))
+                .map(|member| (member.id, member.seq))
+                .collect()
+        };
+        drop(iter);
+        entries.sort_unstable_by_key(|&(_, seq)| Reverse(seq));
+        RecencyIter {
+            list: self,
+            entries: entries.into_iter(),
+        }
+    }
+}
+
+impl<T, I> WeakListData<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns the sequence number of a new attachment.
+    pub(super) fn allocate_seq(&mut self) -> u64 {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        seq
+    }
+}
+
+impl<T, I> Iterator for RecencyIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    type Item = Rc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (id, seq) in self.entries.by_ref() {
+            let t = {
+                let data = unsafe {
+                    // SAFETY:
+                    // - While we hold this reference, we do not call any functions that
+                    //   might create additional references to self.list.data. This
+                    //   applies to all code that creates references to self.list.data.
+                    // - Therefore, this is an exclusive reference to self.list.data.
+                    // - The upgrade call below only runs well-known code that does not
+                    //   depend on T.
+                    &mut *WeakListData::ptr(&self.list.data)
+                };
+                match data.members.get(&id) {
+                    Some(member) if member.seq == seq && member.enabled => member.upgrade(),
+                    _ => None,
+                }
+            };
+            if t.is_some() {
+                return t;
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.entries.len()))
+    }
+}
+
+impl<T, I> Clone for RecencyIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn clone(&self) -> Self {
+        Self {
+            list: self.list,
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+impl<T, I> Debug for RecencyIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RecencyIter")
+            .field("remaining", &self.entries.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, I> FusedIterator for RecencyIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+}
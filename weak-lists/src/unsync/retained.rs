@@ -0,0 +1,118 @@
+use {
+    crate::{
+        id::EntryId,
+        unsync::{RetainedIter, WeakList, WeakListData},
+    },
+    alloc::rc::Rc,
+    core::{
+        fmt::{Debug, Formatter},
+        iter::FusedIterator,
+    },
+};
+
+impl<T, I> WeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Creates an iterator that keeps the targets of the entries alive until they are
+    /// yielded.
+    ///
+    /// The iterator upgrades the targets of all live entries when it is created and holds
+    /// the strong references until it yields the entries. Therefore, every entry that is
+    /// alive when the iterator is created is still alive when it is yielded. This is
+    /// useful if the entries must not be dropped during a pass, for example, because
+    /// dropping one entry drops another.
+    ///
+    /// Entries that are detached before they are yielded are still skipped. Their targets
+    /// are released when the iterator is dropped. Otherwise, this iterator provides the
+    /// same guarantees as [iter](Self::iter).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{WeakList, WeakListElement};
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(0), Rc::new(1)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// let mut iter = list.iter_retained();
+    /// let [_, entry] = entries;
+    /// drop(entry);
+    /// assert_eq!(iter.next().map(|e| *e), Some(0));
+    /// assert_eq!(iter.next().map(|e| *e), Some(1));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn iter_retained(&self) -> RetainedIter<'_, T, I> {
+        let iter = self.iter();
+        let retained = {
+            let data = unsafe {
+                // SAFETY:
+                // - While we hold this reference, we do not call any functions that might
+                //   create additional references to self.data. This applies to all code
+                //   that creates references to self.data.
+                // - Therefore, this is an exclusive reference to self.data.
+                // - The upgrade calls below only run well-known code that does not depend
+                //   on T.
+                &mut *WeakListData::ptr(&self.data)
+            };
+            (0..data.members.index_len())
+                .filter_map(|idx| data.members.get_by_index(idx))
+                .filter(|member| member.is_visible_to(&iter.visibility))
+                .filter_map(|member| Some((member.id, member.upgrade()?)))
+                .collect()
+        };
+        RetainedIter { iter, retained }
+    }
+}
+
+impl<T, I> Iterator for RetainedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    type Item = Rc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (id, t) = self.iter.next_with_id()?;
+        self.retained.remove(&id);
+        Some(t)
+    }
+}
+
+impl<T, I> Clone for RetainedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            retained: self.retained.clone(),
+        }
+    }
+}
+
+impl<T, I> Debug for RetainedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RetainedIter")
+            .field("iter", &self.iter)
+            .field("retained", &self.retained.len())
+            .finish()
+    }
+}
+
+impl<T, I> FusedIterator for RetainedIter<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+}
@@ -0,0 +1,130 @@
+use {
+    crate::{
+        id::EntryId,
+        unsync::{Difference, Intersection, IterChain, Union, WeakList},
+    },
+    alloc::rc::Rc,
+    core::iter::FusedIterator,
+};
+
+impl<T, I> Iterator for Intersection<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    type Item = Rc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = &self.other;
+        self.iter.by_ref().find(|t| other.contains(t))
+    }
+}
+
+impl<T, I> FusedIterator for Intersection<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+}
+
+impl<T, I> Iterator for Difference<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    type Item = Rc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = &self.other;
+        self.iter.by_ref().find(|t| !other.contains(t))
+    }
+}
+
+impl<T, I> FusedIterator for Difference<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+}
+
+impl<T, I> Iterator for Union<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    type Item = Rc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let seen = &mut self.seen;
+        self.first
+            .by_ref()
+            .chain(self.second.by_ref())
+            .find(|t| seen.insert(t))
+    }
+}
+
+impl<T, I> FusedIterator for Union<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+}
+
+/// Creates an iterator over the entries of multiple lists.
+///
+/// The lists are visited in order. Entries are compared by the address of their targets
+/// and each target is returned at most once. Otherwise, the iterator provides the same
+/// guarantees as [iter](WeakList::iter) for each list.
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use weak_lists::{iter_chain, WeakList, WeakListElement};
+///
+/// let topics: [_; 3] = std::array::from_fn(|_| WeakList::default());
+/// let a = Rc::new(1);
+/// let b = Rc::new(2);
+/// let a1 = WeakListElement::new(Rc::downgrade(&a));
+/// let a2 = WeakListElement::new(Rc::downgrade(&a));
+/// let b1 = WeakListElement::new(Rc::downgrade(&b));
+/// a1.attach(&topics[0]);
+/// a2.attach(&topics[2]);
+/// b1.attach(&topics[1]);
+/// let subscribers: Vec<_> = iter_chain(&topics).collect();
+/// assert_eq!(subscribers, [a, b]);
+/// ```
+pub fn iter_chain<'a, T, I>(
+    lists: impl IntoIterator<Item = &'a WeakList<T, I>>,
+) -> IterChain<'a, T, I>
+where
+    T: ?Sized + 'a,
+    I: EntryId,
+{
+    IterChain {
+        iters: lists.into_iter().map(|l| l.iter()).collect(),
+        seen: Default::default(),
+    }
+}
+
+impl<T, I> Iterator for IterChain<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    type Item = Rc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let seen = &mut self.seen;
+        self.iters
+            .iter_mut()
+            .find_map(|iter| iter.find(|t| seen.insert(t)))
+    }
+}
+
+impl<T, I> FusedIterator for IterChain<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+}
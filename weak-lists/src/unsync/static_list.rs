@@ -0,0 +1,200 @@
+use {
+    crate::{
+        error::Error,
+        unsync::{StaticIter, StaticWeakList, StaticWeakListElement},
+    },
+    alloc::rc::{Rc, Weak},
+    core::{
+        cell::{Cell, UnsafeCell},
+        fmt::{Debug, Formatter},
+        iter::FusedIterator,
+        ptr,
+    },
+};
+
+impl<T, const N: usize> StaticWeakList<T, N>
+where
+    T: ?Sized,
+{
+    /// Creates a new, empty list.
+    pub const fn new() -> Self {
+        Self {
+            slots: UnsafeCell::new([const { None }; N]),
+        }
+    }
+
+    /// Creates an iterator over the entries of the list.
+    ///
+    /// The list can be mutated during the iteration. It is guaranteed that, if an element
+    /// was part of the list when this iterator was created, and if the element was not
+    /// removed during the iteration, then the element will be returned exactly once by
+    /// this iterator.
+    pub fn iter(&self) -> StaticIter<'_, T, N> {
+        StaticIter {
+            iter: 0..N,
+            list: self,
+        }
+    }
+}
+
+impl<'a, T, const N: usize> StaticWeakListElement<'a, T, N>
+where
+    T: ?Sized,
+{
+    /// Creates a new list element.
+    ///
+    /// This object holds a weak reference to the `T`. When this object is dropped, it
+    /// automatically detaches itself from the list it is currently attached to.
+    pub const fn new(t: Weak<T>) -> Self {
+        Self {
+            t,
+            slot: Cell::new(None),
+        }
+    }
+
+    /// Attaches the element to a list.
+    ///
+    /// If this element was previously attached to a list, it is automatically detached
+    /// from that list.
+    ///
+    /// Returns [Error::CapacityExceeded] if the list is full. In this case the element is
+    /// not attached to any list.
+    pub fn attach(&self, to: &'a StaticWeakList<T, N>) -> Result<(), Error> {
+        self.detach();
+        let slots = unsafe {
+            // SAFETY:
+            // - While we hold this reference, we do not call any functions that might
+            //   create additional references to the slots. This applies to all code that
+            //   creates references to the slots.
+            // - Therefore, this is an exclusive reference to the slots.
+            // - In particular, the clone call below clones a Weak and is therefore safe.
+            // - The replaced slot is empty, therefore no drop code runs.
+            &mut *to.slots.get()
+        };
+        let Some(idx) = slots.iter().position(|slot| slot.is_none()) else {
+            return Err(Error::CapacityExceeded);
+        };
+        slots[idx] = Some(self.t.clone());
+        self.slot.set(Some((to, idx)));
+        Ok(())
+    }
+
+    /// Detaches the element from its current list.
+    pub fn detach(&self) {
+        if let Some((list, idx)) = self.slot.take() {
+            let slots = unsafe {
+                // SAFETY:
+                // - While we hold this reference, we do not call any functions that might
+                //   create additional references to the slots. This applies to all code
+                //   that creates references to the slots.
+                // - Therefore, this is an exclusive reference to the slots.
+                // - In particular, dropping the Weak below will never run the drop impl
+                //   of T itself.
+                &mut *list.slots.get()
+            };
+            slots[idx] = None;
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for StaticWeakListElement<'_, T, N>
+where
+    T: ?Sized,
+{
+    fn drop(&mut self) {
+        self.detach();
+    }
+}
+
+impl<T, const N: usize> Debug for StaticWeakListElement<'_, T, N>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let list = self.slot.get().map(|(list, _)| ptr::from_ref(list));
+        f.debug_struct("StaticWeakListElement")
+            .field("list", &list)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, const N: usize> Default for StaticWeakList<T, N>
+where
+    T: ?Sized,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a StaticWeakList<T, N>
+where
+    T: ?Sized,
+{
+    type Item = Rc<T>;
+    type IntoIter = StaticIter<'a, T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T, const N: usize> Debug for StaticWeakList<T, N>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("StaticWeakList")
+            .field("id", &ptr::from_ref(self))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, const N: usize> Iterator for StaticIter<'_, T, N>
+where
+    T: ?Sized,
+{
+    type Item = Rc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for idx in &mut self.iter {
+            let slots = unsafe {
+                // SAFETY:
+                // - While we hold this reference, we do not call any functions that might
+                //   create additional references to the slots. This applies to all code
+                //   that creates references to the slots.
+                // - Therefore, this is an exclusive reference to the slots.
+                // - The upgrade call below only runs well-known code that does not depend
+                //   on T.
+                &mut *self.list.slots.get()
+            };
+            if let Some(t) = slots[idx].as_ref().and_then(Weak::upgrade) {
+                return Some(t);
+            }
+        }
+        None
+    }
+}
+
+impl<T, const N: usize> Clone for StaticIter<'_, T, N>
+where
+    T: ?Sized,
+{
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            list: self.list,
+        }
+    }
+}
+
+impl<T, const N: usize> Debug for StaticIter<'_, T, N>
+where
+    T: ?Sized + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<T, const N: usize> FusedIterator for StaticIter<'_, T, N> where T: ?Sized {}
@@ -1,7 +1,15 @@
 use {
-    crate::unsync::{WeakList, WeakListElement},
-    alloc::rc::Rc,
-    core::array,
+    crate::{
+        debug::DebugTargets,
+        error::Error,
+        policy::{LivenessPolicy, Upgrade},
+        unsync::{
+            iter_chain, PairedWeakListElement, PolicyWeakList, StaticWeakList,
+            StaticWeakListElement, WeakList, WeakListBuilder, WeakListElement,
+        },
+    },
+    alloc::{format, rc::Rc, vec::Vec},
+    core::{array, cell::Cell},
 };
 
 #[derive(Debug)]
@@ -35,6 +43,26 @@ fn clear() {
     assert!(list.iter().next().is_none());
 }
 
+#[test]
+fn clear_detaches() {
+    let list = WeakList::default();
+    let entries: [_; 2] = array::from_fn(Element::new);
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    entries[1].element.set_reattach_on_clear(true);
+    list.clear();
+    assert!(entries[0].element.id().is_none());
+    assert!(entries[1].element.id().is_some());
+    entries[0].element.detach();
+    entries[0].element.attach(&list);
+    assert_eq!(list.iter().map(|e| e.i).collect::<Vec<_>>(), [0]);
+    let mut cleared = Vec::new();
+    list.clear_notify(|e| cleared.push(e.i));
+    assert_eq!(cleared, [0]);
+    assert!(entries[0].element.id().is_none());
+}
+
 #[test]
 fn attach_detach() {
     let list = WeakList::<Element>::default();
@@ -89,6 +117,7 @@ fn attach_detach() {
 }
 
 #[test]
+#[allow(clippy::needless_range_loop)]
 fn no_compact_with_iter() {
     let list = WeakList::<Element>::default();
     let entries: [_; 16] = array::from_fn(|i| Element::new(1 << i));
@@ -154,3 +183,1829 @@ fn detach_on_drop() {
     drop(entry);
     assert_eq!(list.iter().map(|e| e.i).sum::<usize>(), 0);
 }
+
+#[test]
+fn intersect_iter() {
+    let list1 = WeakList::<Element>::default();
+    let list2 = WeakList::<Element>::default();
+    let entries: [_; 4] = array::from_fn(|i| Element::new(1 << i));
+    let elements: [_; 4] = array::from_fn(|i| WeakListElement::new(Rc::downgrade(&entries[i])));
+    for entry in &entries[..3] {
+        entry.element.attach(&list1);
+    }
+    for element in &elements[1..] {
+        element.attach(&list2);
+    }
+    assert_eq!(list1.intersect_iter(&list2).map(|e| e.i).sum::<usize>(), 6);
+    assert_eq!(list2.intersect_iter(&list1).map(|e| e.i).sum::<usize>(), 6);
+    elements[1].detach();
+    assert_eq!(list1.intersect_iter(&list2).map(|e| e.i).sum::<usize>(), 4);
+    let mut iter = list1.intersect_iter(&list2);
+    entries[2].element.detach();
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn difference_iter() {
+    let list1 = WeakList::<Element>::default();
+    let list2 = WeakList::<Element>::default();
+    let entries: [_; 4] = array::from_fn(|i| Element::new(1 << i));
+    let elements: [_; 4] = array::from_fn(|i| WeakListElement::new(Rc::downgrade(&entries[i])));
+    for entry in &entries[..3] {
+        entry.element.attach(&list1);
+    }
+    for element in &elements[1..] {
+        element.attach(&list2);
+    }
+    assert_eq!(list1.difference_iter(&list2).map(|e| e.i).sum::<usize>(), 1);
+    assert_eq!(list2.difference_iter(&list1).map(|e| e.i).sum::<usize>(), 8);
+    elements[1].detach();
+    assert_eq!(list1.difference_iter(&list2).map(|e| e.i).sum::<usize>(), 3);
+}
+
+#[test]
+fn union_iter() {
+    let list1 = WeakList::<Element>::default();
+    let list2 = WeakList::<Element>::default();
+    let entries: [_; 4] = array::from_fn(|i| Element::new(1 << i));
+    let elements: [_; 4] = array::from_fn(|i| WeakListElement::new(Rc::downgrade(&entries[i])));
+    for entry in &entries[..3] {
+        entry.element.attach(&list1);
+    }
+    for element in &elements[1..] {
+        element.attach(&list2);
+    }
+    assert_eq!(list1.union_iter(&list2).map(|e| e.i).sum::<usize>(), 15);
+    assert_eq!(list1.union_iter(&list1).map(|e| e.i).sum::<usize>(), 7);
+    let mut iter = list1.union_iter(&list2);
+    assert_eq!(iter.next().unwrap().i, 1);
+    entries[1].element.detach();
+    assert_eq!(iter.next().unwrap().i, 4);
+    assert_eq!(iter.next().unwrap().i, 2);
+    assert_eq!(iter.next().unwrap().i, 8);
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn will_yield() {
+    let list1 = WeakList::<Element>::default();
+    let list2 = WeakList::<Element>::default();
+    let entries: [_; 3] = array::from_fn(|i| Element::new(1 << i));
+    entries[0].element.attach(&list1);
+    entries[1].element.attach(&list1);
+    entries[2].element.attach(&list2);
+    let mut iter = list1.iter();
+    assert!(iter.will_yield(&entries[0].element));
+    assert!(iter.will_yield(&entries[1].element));
+    assert!(!iter.will_yield(&entries[2].element));
+    assert_eq!(iter.next().unwrap().i, 1);
+    assert!(!iter.will_yield(&entries[0].element));
+    entries[0].element.attach(&list1);
+    assert!(!iter.will_yield(&entries[0].element));
+    entries[1].element.detach();
+    assert!(!iter.will_yield(&entries[1].element));
+    entries[1].element.attach(&list1);
+    assert!(iter.will_yield(&entries[1].element));
+    assert_eq!(iter.next().unwrap().i, 2);
+}
+
+#[test]
+fn iter_owned() {
+    let list = WeakList::<Element>::default();
+    let entries: [_; 3] = array::from_fn(|i| Element::new(1 << i));
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    let mut iter1 = list.iter_owned();
+    assert_eq!(iter1.next().unwrap().i, 1);
+    let iter2 = iter1.clone();
+    entries[1].element.detach();
+    assert_eq!(iter1.next().unwrap().i, 4);
+    assert!(iter1.next().is_none());
+    let mut iter3 = list.iter_owned();
+    drop(list);
+    assert!(iter2.count() == 0);
+    assert!(iter3.next().is_none());
+    entries[0].element.detach();
+    let list = WeakList::default();
+    entries[2].element.attach(&list);
+    assert_eq!(list.iter().map(|e| e.i).sum::<usize>(), 4);
+}
+
+#[test]
+fn iter_owned_drop_list_mid_iteration() {
+    let list = WeakList::<Element>::default();
+    let entries: [_; 3] = array::from_fn(|i| Element::new(1 << i));
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    let mut iter = list.iter_owned();
+    let mut list = Some(list);
+    let mut sum = 0;
+    for e in iter.by_ref() {
+        sum += e.i;
+        if e.i == 2 {
+            list = None;
+            e.element.detach();
+        }
+    }
+    assert!(list.is_none());
+    assert_eq!(sum, 3);
+    assert!(iter.next().is_none());
+    for entry in &entries {
+        entry.element.detach();
+    }
+}
+
+#[cfg(feature = "unsize")]
+#[test]
+fn from_rc() {
+    trait Value {
+        fn value(&self) -> usize;
+    }
+    impl Value for usize {
+        fn value(&self) -> usize {
+            *self
+        }
+    }
+    fn element<U>(t: &Rc<U>) -> WeakListElement<dyn Value>
+    where
+        U: core::marker::Unsize<dyn Value>,
+    {
+        WeakListElement::from_rc(t)
+    }
+    let list = WeakList::<dyn Value>::default();
+    let entry = Rc::new(1usize);
+    let element = element(&entry);
+    element.attach(&list);
+    assert_eq!(list.iter().map(|e| e.value()).sum::<usize>(), 1);
+}
+
+trait Draw {
+    fn draw(&self) -> usize;
+}
+
+trait Input {
+    fn input(&self) -> usize;
+}
+
+struct Button(usize);
+
+impl Draw for Button {
+    fn draw(&self) -> usize {
+        self.0
+    }
+}
+
+impl Input for Button {
+    fn input(&self) -> usize {
+        self.0 << 1
+    }
+}
+
+#[test]
+fn multiple_trait_objects() {
+    let draw = WeakList::<dyn Draw>::default();
+    let input = WeakList::<dyn Input>::default();
+    let buttons: [_; 2] = array::from_fn(|i| Rc::new(Button(1 << i)));
+    let elements = buttons.each_ref().map(|button| {
+        let weak = Rc::downgrade(button);
+        let a = WeakListElement::<dyn Draw>::new(weak.clone());
+        let b = WeakListElement::<dyn Input>::new(weak);
+        a.attach(&draw);
+        b.attach(&input);
+        (a, b)
+    });
+    assert_eq!(draw.iter().map(|e| e.draw()).sum::<usize>(), 3);
+    assert_eq!(input.iter().map(|e| e.input()).sum::<usize>(), 6);
+    elements[0].1.detach();
+    assert_eq!(draw.iter().map(|e| e.draw()).sum::<usize>(), 3);
+    assert_eq!(input.iter().map(|e| e.input()).sum::<usize>(), 4);
+    let [_, button] = buttons;
+    drop(button);
+    assert_eq!(draw.iter().map(|e| e.draw()).sum::<usize>(), 1);
+    assert!(input.iter().next().is_none());
+}
+
+#[cfg(feature = "unsize")]
+#[test]
+fn from_rc_pair() {
+    let draw = WeakList::<dyn Draw>::default();
+    let input = WeakList::<dyn Input>::default();
+    let button = Rc::new(Button(1));
+    let (a, b) = WeakListElement::<dyn Draw>::from_rc_pair::<_, dyn Input>(&button);
+    a.attach(&draw);
+    b.attach(&input);
+    assert_eq!(draw.iter().map(|e| e.draw()).sum::<usize>(), 1);
+    assert_eq!(input.iter().map(|e| e.input()).sum::<usize>(), 2);
+    drop(button);
+    assert!(draw.iter().next().is_none());
+    assert!(input.iter().next().is_none());
+}
+
+#[test]
+fn for_each() {
+    let list = WeakList::<Element>::default();
+    let entries: [_; 3] = array::from_fn(|i| Element::new(1 << i));
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    let mut sum = 0;
+    list.for_each(|e| {
+        sum += e.i;
+        entries[2].element.detach();
+    });
+    assert_eq!(sum, 3);
+}
+
+#[test]
+fn for_each_skipped() {
+    let list = WeakList::<Element>::default();
+    let entries: [_; 3] = array::from_fn(|i| Element::new(1 << i));
+    let elements = entries
+        .each_ref()
+        .map(|e| WeakListElement::new(Rc::downgrade(e)));
+    for element in &elements {
+        element.attach(&list);
+    }
+    let [e0, e1, e2] = entries;
+    drop(e1);
+    assert!(elements[0].is_target_alive());
+    assert!(!elements[1].is_target_alive());
+    let mut sum = 0;
+    let skipped = list.for_each(|e| sum += e.i);
+    assert_eq!(sum, 5);
+    assert_eq!(skipped, 1);
+    drop((e0, e2));
+    assert_eq!(list.for_each(|_| ()), 3);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn for_each_catch_unwind() {
+    let list = WeakList::<Element>::default();
+    let entries: [_; 3] = array::from_fn(|i| Element::new(1 << i));
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    let mut sum = 0;
+    let panics = list.for_each_catch_unwind(|e| {
+        if e.i == 2 {
+            panic!("{}", e.i);
+        }
+        sum += e.i;
+    });
+    assert_eq!(sum, 5);
+    assert_eq!(panics.len(), 1);
+    assert_eq!(panics[0].member.i, 2);
+    assert_eq!(
+        panics[0]
+            .payload
+            .downcast_ref::<alloc::string::String>()
+            .unwrap(),
+        "2"
+    );
+}
+
+#[cfg(feature = "paranoid")]
+#[test]
+fn paranoid_reentrant_access() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let list = WeakList::default();
+    let entries: [_; 2] = array::from_fn(Element::new);
+    entries[0].element.attach(&list);
+    let set_borrowed = |borrowed| unsafe {
+        // SAFETY: No other reference to the list data exists.
+        (*list.data.get()).borrowed = borrowed;
+    };
+    // Simulates an access from within the section of the iterator that borrows the data.
+    set_borrowed(true);
+    let res = catch_unwind(AssertUnwindSafe(|| entries[1].element.attach(&list)));
+    assert!(res.is_err());
+    set_borrowed(false);
+    assert_eq!(entries[1].element.id(), None);
+    assert_eq!(list.iter().map(|e| e.i).collect::<Vec<_>>(), [0]);
+}
+
+#[test]
+fn split_off() {
+    let list = WeakList::<Element>::default();
+    let entries: [_; 4] = array::from_fn(|i| Element::new(1 << i));
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    let list2 = list.split_off(|e| {
+        if e.i == 1 {
+            entries[3].element.detach();
+        }
+        e.i != 2
+    });
+    assert_eq!(list.iter().map(|e| e.i).sum::<usize>(), 2);
+    assert_eq!(list2.iter().map(|e| e.i).sum::<usize>(), 5);
+    entries[0].element.detach();
+    assert_eq!(list.iter().map(|e| e.i).sum::<usize>(), 2);
+    assert_eq!(list2.iter().map(|e| e.i).sum::<usize>(), 4);
+    entries[2].element.attach(&list);
+    assert_eq!(list.iter().map(|e| e.i).sum::<usize>(), 6);
+    assert_eq!(list2.iter().map(|e| e.i).sum::<usize>(), 0);
+    let list3 = list.split_off(|_| true);
+    drop(list3);
+    assert_eq!(list.iter().map(|e| e.i).sum::<usize>(), 0);
+    entries[1].element.detach();
+}
+
+#[test]
+fn split_off_keeps_settings() {
+    let list = WeakListBuilder::default().max_entries_per_target(1).build();
+    list.set_max_concurrent_iterations(Some(1));
+    let target = Rc::new(0);
+    let elements: [_; 2] = array::from_fn(|_| WeakListElement::new(Rc::downgrade(&target)));
+    elements[0].attach(&list);
+    let idle = list.split_off(|_| true);
+    assert_eq!(
+        elements[1].try_attach(&idle),
+        Err(Error::TargetLimitReached)
+    );
+    let iter = idle.iter();
+    assert_eq!(idle.try_iter().err(), Some(Error::TooManyIterators));
+    drop(iter);
+}
+
+#[test]
+fn try_attach() {
+    let list1 = WeakList::<Element>::default();
+    let list2 = WeakList::<Element>::default();
+    let entry = Element::new(1);
+    assert_eq!(entry.element.try_attach(&list1), Ok(()));
+    assert_eq!(list1.iter().count(), 1);
+    let element = WeakListElement::new(Rc::downgrade(&entry));
+    element.attach(&list2);
+    drop(entry);
+    assert_eq!(element.try_attach(&list1), Err(Error::TargetDropped));
+    assert_eq!(list2.iter().count(), 0);
+    assert_eq!(list1.iter().count(), 0);
+}
+
+#[test]
+fn static_list() {
+    let list = StaticWeakList::<usize, 2>::new();
+    let entries: [_; 3] = array::from_fn(|i| Rc::new(1 << i));
+    let elements = entries
+        .each_ref()
+        .map(|e| StaticWeakListElement::new(Rc::downgrade(e)));
+    assert_eq!(elements[0].attach(&list), Ok(()));
+    assert_eq!(elements[1].attach(&list), Ok(()));
+    assert_eq!(elements[2].attach(&list), Err(Error::CapacityExceeded));
+    assert_eq!(list.iter().map(|e| *e).sum::<usize>(), 3);
+    let sum: usize = list
+        .iter()
+        .inspect(|e| {
+            if **e == 1 {
+                elements[1].detach();
+                assert_eq!(elements[2].attach(&list), Ok(()));
+            }
+        })
+        .map(|e| *e)
+        .sum();
+    assert_eq!(sum, 5);
+    assert_eq!(list.iter().map(|e| *e).sum::<usize>(), 5);
+    drop(elements);
+    assert_eq!(list.iter().count(), 0);
+}
+
+#[test]
+fn detach_group() {
+    let list1 = WeakList::<Element>::default();
+    let list2 = WeakList::<Element>::default();
+    let entries: [_; 4] = array::from_fn(|i| Element::new(1 << i));
+    entries[0].element.attach_with_group(&list1, 1);
+    entries[1].element.attach_with_group(&list1, 2);
+    entries[2].element.attach(&list1);
+    entries[3].element.attach_with_group(&list2, 1);
+    let sum: usize = list1
+        .iter()
+        .inspect(|e| {
+            if e.i == 1 {
+                list1.detach_group(2);
+            }
+        })
+        .map(|e| e.i)
+        .sum();
+    assert_eq!(sum, 5);
+    list1.detach_group(1);
+    assert_eq!(list1.iter().map(|e| e.i).sum::<usize>(), 4);
+    assert_eq!(list2.iter().map(|e| e.i).sum::<usize>(), 8);
+    entries[0].element.attach(&list1);
+    list1.detach_group(1);
+    assert_eq!(list1.iter().map(|e| e.i).sum::<usize>(), 5);
+}
+
+#[test]
+fn reset_iter() {
+    let list = WeakList::<Element>::default();
+    let entries: [_; 16] = array::from_fn(|i| Element::new(1 << i));
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    let mut iter = list.iter();
+    assert_eq!(iter.next().unwrap().i, 1);
+    for entry in &entries[..15] {
+        entry.element.detach();
+    }
+    let iter2 = iter.clone();
+    entries[0].element.attach(&list);
+    iter.reset();
+    assert_eq!(iter.by_ref().map(|e| e.i).sum::<usize>(), 1 + (1 << 15));
+    drop(iter2);
+    entries[1].element.attach(&list);
+    iter.reset();
+    assert_eq!(iter.map(|e| e.i).sum::<usize>(), 3 + (1 << 15));
+}
+
+#[test]
+fn peek() {
+    let list = WeakList::<Element>::default();
+    let entries: [_; 3] = array::from_fn(|i| Element::new(1 << i));
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    let mut iter = list.iter();
+    assert_eq!(iter.peek().unwrap().i, 1);
+    assert_eq!(iter.peek().unwrap().i, 1);
+    assert!(iter.will_yield(&entries[0].element));
+    assert_eq!(iter.next().unwrap().i, 1);
+    assert!(!iter.will_yield(&entries[0].element));
+    assert_eq!(iter.peek().unwrap().i, 2);
+    let mut iter2 = iter.clone();
+    entries[1].element.detach();
+    entries[2].element.detach();
+    assert_eq!(iter.next().unwrap().i, 2);
+    assert!(iter.peek().is_none());
+    assert_eq!(iter2.next().unwrap().i, 2);
+    iter2.reset();
+    assert!(iter2.next().is_some());
+    assert!(iter2.next().is_none());
+}
+
+#[test]
+fn drain() {
+    let list = WeakList::<Element>::default();
+    let entries: [_; 4] = array::from_fn(|i| Element::new(1 << i));
+    for entry in &entries[..3] {
+        entry.element.attach(&list);
+    }
+    let mut drain = list.drain();
+    assert_eq!(drain.next().unwrap().i, 1);
+    assert_eq!(list.iter().map(|e| e.i).sum::<usize>(), 6);
+    entries[3].element.attach(&list);
+    entries[0].element.attach(&list);
+    drop(drain);
+    assert_eq!(list.iter().map(|e| e.i).sum::<usize>(), 9);
+    assert_eq!(list.drain().map(|e| e.i).sum::<usize>(), 9);
+    assert!(list.iter().next().is_none());
+    entries[0].element.detach();
+}
+
+#[test]
+fn extract_if() {
+    let list = WeakList::<Element>::default();
+    let entries: [_; 4] = array::from_fn(|i| Element::new(1 << i));
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    let mut iter = list.extract_if(|e| {
+        if e.i == 1 {
+            entries[2].element.detach();
+        }
+        e.i != 2
+    });
+    assert_eq!(iter.next().unwrap().i, 1);
+    assert_eq!(list.iter().map(|e| e.i).sum::<usize>(), 10);
+    drop(iter);
+    assert_eq!(list.iter().map(|e| e.i).sum::<usize>(), 10);
+    assert_eq!(list.extract_if(|e| e.i == 8).map(|e| e.i).sum::<usize>(), 8);
+    assert_eq!(list.iter().map(|e| e.i).sum::<usize>(), 2);
+}
+
+#[test]
+fn pin_list() {
+    use {
+        crate::unsync::{PinWeakList, PinWeakListElement},
+        core::marker::PhantomPinned,
+    };
+
+    struct Pinned {
+        i: usize,
+        _pinned: PhantomPinned,
+    }
+
+    let list = PinWeakList::default();
+    let entries: [_; 2] = array::from_fn(|i| {
+        Rc::pin(Pinned {
+            i: 1 << i,
+            _pinned: PhantomPinned,
+        })
+    });
+    let elements = entries.each_ref().map(PinWeakListElement::new);
+    for element in &elements {
+        element.attach(&list);
+    }
+    assert_eq!(list.iter().map(|e| e.i).sum::<usize>(), 3);
+    elements[0].detach();
+    assert_eq!(list.iter().map(|e| e.i).sum::<usize>(), 2);
+    list.clear();
+    assert!(list.iter().next().is_none());
+}
+
+#[test]
+fn adopt_on_init() {
+    let list = WeakList::<Element>::default();
+    let entry = list.adopt_on_init(
+        |slf| {
+            let element = WeakListElement::new(slf.clone());
+            element.attach(&list);
+            assert_eq!(list.iter().count(), 0);
+            Element { i: 1, element }
+        },
+        |e| &e.element,
+    );
+    assert_eq!(list.iter().map(|e| e.i).sum::<usize>(), 1);
+    drop(entry);
+    assert!(list.iter().next().is_none());
+}
+
+#[test]
+fn attach_new() {
+    let list = WeakList::default();
+    let entries = [Rc::new(0), Rc::new(1)];
+    let elements = entries.each_ref().map(|e| list.attach_new(e));
+    assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [0, 1]);
+    drop(elements);
+    assert!(list.iter().next().is_none());
+}
+
+#[test]
+fn contains_id() {
+    let list = WeakList::default();
+    let entry = Rc::new(0);
+    let element = list.attach_new(&entry);
+    let id = element.id().unwrap();
+    assert!(list.contains_id(id));
+    drop(entry);
+    assert!(list.contains_id(id));
+    element.detach();
+    assert!(!list.contains_id(id));
+    element.attach(&list);
+    assert!(!list.contains_id(id));
+    assert!(list.contains_id(element.id().unwrap()));
+}
+
+#[test]
+fn max_concurrent_iterations() {
+    let list = WeakList::default();
+    let entry = Rc::new(0);
+    let _element = list.attach_new(&entry);
+    list.set_max_concurrent_iterations(Some(1));
+    let mut iter = list.iter();
+    assert_eq!(list.iter().next(), None);
+    assert!(list.try_iter().is_err());
+    assert_eq!(list.iter_owned().next(), None);
+    iter.reset();
+    assert_eq!(iter.next(), Some(entry.clone()));
+    drop(iter);
+    assert_eq!(list.try_iter().unwrap().next(), Some(entry.clone()));
+    list.set_max_concurrent_iterations(None);
+    let _iter = list.iter();
+    assert_eq!(list.iter().next(), Some(entry));
+}
+
+#[test]
+fn attach_once() {
+    let list = WeakList::default();
+    let entries = [Rc::new(0), Rc::new(1), Rc::new(2)];
+    let elements = entries
+        .each_ref()
+        .map(|e| WeakListElement::new(Rc::downgrade(e)));
+    for element in &elements {
+        element.attach_once(&list);
+    }
+    let mut iter1 = list.iter();
+    let mut iter2 = list.iter();
+    assert_eq!(iter1.next(), Some(entries[0].clone()));
+    assert_eq!(elements[0].id(), None);
+    assert_eq!(iter2.next(), Some(entries[1].clone()));
+    assert_eq!(iter1.next(), Some(entries[2].clone()));
+    assert_eq!(iter1.next(), None);
+    assert_eq!(iter2.next(), None);
+    drop((iter1, iter2));
+    assert!(elements.iter().all(|e| e.id().is_none()));
+    elements[0].attach_once(&list);
+    elements[1].attach(&list);
+    let mut seen = Vec::new();
+    list.for_each(|e| seen.push(*e));
+    list.for_each(|e| seen.push(*e));
+    assert_eq!(seen, [0, 1, 1]);
+}
+
+#[test]
+fn epochs() {
+    let list = WeakList::default();
+    let entries = [Rc::new(0), Rc::new(1), Rc::new(2), Rc::new(3)];
+    let elements = entries
+        .each_ref()
+        .map(|e| WeakListElement::new(Rc::downgrade(e)));
+    elements[0].attach(&list);
+    elements[1].attach_with_epoch(&list, 1);
+    elements[2].attach_with_epoch(&list, 2);
+    let collect = |iter: &mut dyn Iterator<Item = Rc<i32>>| iter.map(|e| *e).collect::<Vec<_>>();
+    assert_eq!(collect(&mut list.iter_epoch_at_most(0)), [0]);
+    assert_eq!(collect(&mut list.iter_epoch_at_most(1)), [0, 1]);
+    assert_eq!(collect(&mut list.iter()), [0, 1, 2]);
+    let mut iter = list.iter_epoch_at_most(1);
+    assert!(iter.will_yield(&elements[1]));
+    assert!(!iter.will_yield(&elements[2]));
+    elements[3].attach_visible_now(&list);
+    elements[0].detach();
+    elements[0].attach_with_epoch(&list, 3);
+    assert!(!iter.will_yield(&elements[0]));
+    assert!(iter.will_yield(&elements[3]));
+    assert_eq!(collect(&mut iter), [1, 3]);
+}
+
+#[test]
+fn is_iterating() {
+    let list = WeakList::<Element>::default();
+    let entry = Element::new(1);
+    entry.element.attach(&list);
+    assert!(!list.is_iterating());
+    list.for_each(|_| assert!(list.is_iterating()));
+    let iter = list.iter_owned();
+    assert!(list.is_iterating());
+    drop(iter);
+    assert!(!list.is_iterating());
+    list.drain().for_each(|_| assert!(list.is_iterating()));
+    assert!(!list.is_iterating());
+}
+
+#[test]
+fn dispatch_some() {
+    let list = WeakList::<Element>::default();
+    let entries: [_; 8] = array::from_fn(|i| Element::new(1 << i));
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    let mut sum = 0;
+    assert_eq!(list.dispatch_some(6, |e| sum += e.i), 6);
+    assert_eq!(sum, 63);
+    for entry in &entries[..5] {
+        entry.element.detach();
+    }
+    sum = 0;
+    assert_eq!(list.dispatch_some(1, |e| sum += e.i), 1);
+    assert_eq!(sum, 64);
+    sum = 0;
+    assert_eq!(list.dispatch_some(4, |e| sum += e.i), 3);
+    assert_eq!(sum, 128 + 32 + 64);
+    list.clear();
+    assert_eq!(list.dispatch_some(4, |_| ()), 0);
+}
+
+#[test]
+fn members_equal() {
+    let list = WeakList::<Element>::default();
+    let entries: [_; 3] = array::from_fn(|i| Element::new(1 << i));
+    assert!(list.members_equal(&[]));
+    entries[0].element.attach(&list);
+    entries[1].element.attach(&list);
+    assert!(list.members_equal(&[entries[1].clone(), entries[0].clone()]));
+    assert!(!list.members_equal(&entries[..1]));
+    assert!(!list.members_equal(&entries));
+    let element = WeakListElement::new(Rc::downgrade(&entries[0]));
+    element.attach(&list);
+    assert!(!list.members_equal(&entries[..2]));
+    assert!(list.members_equal(&[entries[0].clone(), entries[1].clone(), entries[0].clone()]));
+}
+
+#[test]
+fn replace() {
+    let list1 = WeakList::<Element>::default();
+    let list2 = WeakList::<Element>::default();
+    let entries: [_; 4] = array::from_fn(|i| Element::new(1 << i));
+    entries[0].element.attach(&list1);
+    entries[1].element.attach(&list1);
+    entries[2].element.attach(&list2);
+    let id = entries[0].element.id().unwrap();
+    assert_eq!(list1.replace(id, &entries[0].element), Ok(()));
+    assert_eq!(entries[0].element.id(), Some(id));
+    let mut iter = list1.iter();
+    assert_eq!(list1.replace(id, &entries[2].element), Ok(()));
+    assert_eq!(iter.next().unwrap().i, 4);
+    assert_eq!(iter.next().unwrap().i, 2);
+    assert!(iter.next().is_none());
+    assert_eq!(entries[0].element.id(), None);
+    assert_eq!(entries[2].element.id(), Some(id));
+    assert!(list2.iter().next().is_none());
+    entries[0].element.detach();
+    assert_eq!(list1.iter().map(|e| e.i).sum::<usize>(), 6);
+    entries[3].element.attach(&list2);
+    assert_eq!(
+        list1.replace(id + 100, &entries[3].element),
+        Err(Error::NotFound)
+    );
+    assert_eq!(entries[3].element.id(), None);
+    entries[2].element.detach();
+    assert_eq!(list1.iter().map(|e| e.i).sum::<usize>(), 2);
+}
+
+#[test]
+fn raw() {
+    let list = WeakList::<Element>::default();
+    let entries: [_; 4] = array::from_fn(|i| Element::new(1 << i));
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    let raw = list.raw();
+    assert!(raw.auto_compact());
+    raw.set_auto_compact(false);
+    entries[0].element.detach();
+    entries[1].element.detach();
+    entries[2].element.detach();
+    let iter = list.iter();
+    assert_eq!(raw.slots(), 4);
+    assert!(raw.slot(2).is_none());
+    assert!(!raw.compact());
+    drop(iter);
+    assert_eq!(raw.slots(), 4);
+    let id = entries[3].element.id().unwrap();
+    assert_eq!(raw.slot(3).unwrap().0, id);
+    assert!(raw.compact());
+    assert_eq!(raw.slots(), 1);
+    assert_eq!(raw.slot(0).unwrap().0, id);
+    assert_eq!(raw.get(id).unwrap().upgrade().unwrap().i, 8);
+    assert!(raw.get(id + 100).is_none());
+}
+
+#[test]
+fn paired() {
+    let global = WeakList::<Element>::default();
+    let parent1 = WeakList::<Element>::default();
+    let parent2 = WeakList::<Element>::default();
+    let entries: [_; 2] = array::from_fn(|i| Element::new(1 << i));
+    let paired = entries
+        .each_ref()
+        .map(|e| PairedWeakListElement::new(Rc::downgrade(e)));
+    paired[0].attach(&global, &parent1);
+    paired[1].attach(&global, &parent2);
+    assert_eq!(global.iter().map(|e| e.i).sum::<usize>(), 3);
+    assert_eq!(parent1.iter().map(|e| e.i).sum::<usize>(), 1);
+    assert_eq!(parent2.iter().map(|e| e.i).sum::<usize>(), 2);
+    paired[0].attach(&parent2, &parent2);
+    assert_eq!(global.iter().map(|e| e.i).sum::<usize>(), 2);
+    assert!(parent1.iter().next().is_none());
+    assert_eq!(parent2.iter().map(|e| e.i).sum::<usize>(), 4);
+    paired[0].detach();
+    assert_eq!(parent2.iter().map(|e| e.i).sum::<usize>(), 2);
+    drop(parent2);
+    paired[1].detach();
+    assert!(global.iter().next().is_none());
+    paired[0].attach(&global, &parent1);
+    drop(paired);
+    assert!(global.iter().next().is_none());
+    assert!(parent1.iter().next().is_none());
+}
+
+#[test]
+fn paired_target_limit() {
+    let global = WeakList::default();
+    let limited = WeakListBuilder::default().max_entries_per_target(1).build();
+    let entry = Rc::new(1);
+    let paired = [(); 2].map(|_| PairedWeakListElement::new(Rc::downgrade(&entry)));
+    let res = paired[0].try_attach(&limited, &limited);
+    assert_eq!(res, Err(Error::TargetLimitReached));
+    assert!(limited.iter().next().is_none());
+    assert_eq!(paired[0].try_attach(&global, &limited), Ok(()));
+    paired[1].attach(&global, &limited);
+    assert_eq!(global.iter().count(), 1);
+    let res = paired[1].try_attach(&limited, &global);
+    assert_eq!(res, Err(Error::TargetLimitReached));
+    assert_eq!(global.iter().count(), 1);
+    paired[0].detach();
+    assert_eq!(paired[1].try_attach(&limited, &global), Ok(()));
+    assert_eq!(global.iter().count(), 1);
+    assert_eq!(limited.iter().count(), 1);
+}
+
+#[test]
+fn cascade() {
+    let entries: [_; 3] = array::from_fn(|i| Element::new(1 << i));
+    let lists: [_; 3] = array::from_fn(|_| WeakList::<Element>::default());
+    for (entry, list) in entries.iter().zip(&lists) {
+        entry.element.attach(list);
+    }
+    lists[0].add_child(&lists[1]);
+    lists[1].add_child(&lists[2]);
+    lists[2].add_child(&lists[0]);
+    lists[0].clear();
+    for list in &lists {
+        assert!(list.iter().next().is_none());
+    }
+    for (entry, list) in entries.iter().zip(&lists) {
+        entry.element.attach(list);
+    }
+    lists[1].remove_child(&lists[2]);
+    lists[2].clear();
+    for list in &lists {
+        assert!(list.iter().next().is_none());
+    }
+    for (entry, list) in entries.iter().zip(&lists) {
+        entry.element.attach(list);
+    }
+    let [list0, list1, list2] = lists;
+    drop(list0);
+    assert!(list1.iter().next().is_none());
+    assert_eq!(list2.iter().next().unwrap().i, 4);
+}
+
+#[test]
+fn iter_chain_dedup() {
+    let lists: [_; 3] = array::from_fn(|_| WeakList::<Element>::default());
+    let entries: [_; 3] = array::from_fn(|i| Element::new(1 << i));
+    entries[0].element.attach(&lists[0]);
+    entries[1].element.attach(&lists[1]);
+    entries[2].element.attach(&lists[2]);
+    let extra = WeakListElement::new(Rc::downgrade(&entries[0]));
+    extra.attach(&lists[2]);
+    let mut iter = iter_chain(&lists);
+    assert_eq!(iter.next().unwrap().i, 1);
+    entries[1].element.detach();
+    assert_eq!(iter.next().unwrap().i, 4);
+    assert!(iter.next().is_none());
+    assert!(iter_chain(&lists[..0]).next().is_none());
+}
+
+#[test]
+fn dead_age_stats() {
+    let list = WeakList::<Element>::default();
+    let entries: [_; 3] = array::from_fn(|i| Element::new(1 << i));
+    let elements = entries
+        .each_ref()
+        .map(|e| WeakListElement::new(Rc::downgrade(e)));
+    for element in &elements {
+        element.attach(&list);
+    }
+    let [e0, e1, _] = entries;
+    drop(e0);
+    assert_eq!(list.iter().count(), 2);
+    drop(e1);
+    list.iter().for_each(drop);
+    list.iter().for_each(drop);
+    let stats = list.dead_age_stats();
+    assert_eq!(stats.dead, 2);
+    assert_eq!(stats.unobserved, 0);
+    assert_eq!(stats.max_age, 2);
+    assert_eq!(stats.total_age, 3);
+    let other = list.split_off(|_| true);
+    elements[0].detach();
+    assert_eq!(list.dead_age_stats().dead, 1);
+    assert_eq!(other.dead_age_stats().dead, 0);
+}
+
+#[test]
+fn clock() {
+    let list = WeakList::default();
+    let time = Rc::new(Cell::new(10));
+    list.set_clock({
+        let time = time.clone();
+        move || time.get()
+    });
+    let entries = [Rc::new(0), Rc::new(1)];
+    let elements = entries
+        .each_ref()
+        .map(|e| WeakListElement::new(Rc::downgrade(e)));
+    for element in &elements {
+        element.attach(&list);
+    }
+    let [e0, e1] = entries;
+    drop(e0);
+    let mut iter = list.iter();
+    time.set(20);
+    iter.reset();
+    drop(e1);
+    assert_eq!(iter.next(), None);
+    time.set(25);
+    let stats = list.dead_age_stats();
+    assert_eq!(stats.unobserved, 0);
+    assert_eq!(stats.max_age, 5);
+    assert_eq!(stats.total_age, 10);
+}
+
+#[test]
+fn id_wrap_around() {
+    let list: WeakList<Element, u16> = WeakListBuilder::default().build_with_id_type();
+    let entries: [_; 2] = array::from_fn(|i| Element::new(1 << i));
+    let first = WeakListElement::new_with_id_type(Rc::downgrade(&entries[0]));
+    let second = WeakListElement::new_with_id_type(Rc::downgrade(&entries[1]));
+    first.attach(&list);
+    assert_eq!(first.id(), Some(0));
+    for _ in 0..u16::MAX {
+        second.attach(&list);
+    }
+    assert_eq!(second.id(), Some(u16::MAX));
+    second.attach(&list);
+    assert_eq!(first.id(), Some(0));
+    assert_eq!(second.id(), Some(1));
+    assert_eq!(list.iter().map(|e| e.i).sum::<usize>(), 3);
+}
+
+#[test]
+fn ids_exhausted() {
+    let list: WeakList<u32, u16> = WeakListBuilder::default().build_with_id_type();
+    let target = Rc::new(0);
+    let elements: Vec<_> = (0..=u16::MAX).map(|_| list.attach_new(&target)).collect();
+    assert!(elements.iter().all(|e| e.id().is_some()));
+    let element = WeakListElement::new_with_id_type(Rc::downgrade(&target));
+    assert_eq!(element.try_attach(&list), Err(Error::IdsExhausted));
+    assert_eq!(element.id(), None);
+    element.attach(&list);
+    assert_eq!(element.id(), None);
+    let id = elements[7].id();
+    elements[7].detach();
+    assert_eq!(element.try_attach(&list), Ok(()));
+    assert_eq!(element.id(), id);
+}
+
+#[test]
+fn export_weak() {
+    let list = WeakList::<Element>::default();
+    let entries: [_; 3] = array::from_fn(|i| Element::new(1 << i));
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    entries[1].element.detach();
+    let mut out = Vec::new();
+    list.export_weak(&mut out);
+    assert_eq!(out.len(), 2);
+    for entry in &entries {
+        assert_eq!(Rc::strong_count(entry), 1);
+    }
+    let [e0, _, _] = entries;
+    drop(e0);
+    assert_eq!(
+        out.iter()
+            .filter_map(|t| t.upgrade())
+            .map(|e| e.i)
+            .sum::<usize>(),
+        4
+    );
+}
+
+#[test]
+#[cfg(feature = "deterministic")]
+fn deterministic() {
+    let list: WeakList<Element> = WeakListBuilder::default().first_id(10).build();
+    let entries: [_; 3] = array::from_fn(|i| Element::new(1 << i));
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    assert_eq!(entries[2].element.id(), Some(12));
+    entries[0].element.attach(&list);
+    entries[1].element.attach(&list);
+    let order: Vec<_> = list.targets_by_id().iter().map(|e| e.i).collect();
+    assert_eq!(order, [4, 1, 2]);
+}
+
+#[test]
+fn memory_usage() {
+    let empty = WeakList::<Element>::default();
+    let list: WeakList<Element> = WeakListBuilder::default().capacity(64).build();
+    assert!(list.memory_usage() > empty.memory_usage());
+    let usage = list.memory_usage();
+    let entries: [_; 8] = array::from_fn(Element::new);
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    assert_eq!(list.memory_usage(), usage);
+    list.notify_coalesced(0, |_| ());
+    assert!(list.memory_usage() > usage);
+    let usage = list.memory_usage();
+    entries[0].element.attach_with_group(&list, 1);
+    assert!(list.memory_usage() > usage);
+}
+
+#[test]
+fn keyed() {
+    let list = WeakList::default();
+    let entries: [_; 4] = array::from_fn(Element::new);
+    assert_eq!(entries[0].element.attach_keyed(&list, 1), Ok(()));
+    entries[1].element.attach(&list);
+    entries[2].element.attach(&list);
+    assert_eq!(entries[1].element.id(), Some(0));
+    assert_eq!(entries[2].element.id(), Some(2));
+    assert_eq!(
+        entries[3].element.attach_keyed(&list, 2),
+        Err(Error::IdInUse)
+    );
+    assert_eq!(entries[3].element.id(), None);
+    assert_eq!(list.get_key(1).map(|e| e.i), Some(0));
+    assert_eq!(entries[0].element.attach_keyed(&list, 1), Ok(()));
+    assert_eq!(list.detach_key(2), Ok(()));
+    assert_eq!(entries[3].element.attach_keyed(&list, 2), Ok(()));
+    assert_eq!(list.get_key(2).map(|e| e.i), Some(3));
+    assert_eq!(entries[2].element.id(), None);
+    assert_eq!(list.iter().count(), 3);
+}
+
+#[test]
+fn duplicate() {
+    let list = WeakList::default();
+    let entries: [_; 4] = array::from_fn(Element::new);
+    entries[0].element.attach_with_group(&list, 1);
+    entries[1].element.attach(&list);
+    entries[2].element.attach_with_group(&list, 1);
+    let copy = list.duplicate();
+    entries[3].element.attach(&copy);
+    assert_eq!(entries[3].element.id(), Some(3));
+    copy.detach_group(1);
+    assert_eq!(list.iter().count(), 3);
+    let ids: Vec<_> = copy.iter().map(|e| e.i).collect();
+    assert_eq!(ids, [1, 3]);
+    assert_eq!(entries[1].element.id(), Some(1));
+    copy.clear();
+    assert_eq!(list.iter().count(), 3);
+}
+
+#[test]
+fn keepalive() {
+    let list = WeakList::default();
+    let targets = [Rc::new(0), Rc::new(1)];
+    let session = Rc::new(());
+    let elements = [
+        WeakListElement::with_keepalive(Rc::downgrade(&targets[0]), Rc::downgrade(&session)),
+        WeakListElement::new(Rc::downgrade(&targets[1])),
+    ];
+    for element in &elements {
+        element.attach(&list);
+    }
+    assert_eq!(list.iter().count(), 2);
+    drop(session);
+    assert_eq!(list.iter().map(|e| *e).collect::<Vec<_>>(), [1]);
+    assert!(list.members_equal(&targets[1..]));
+    assert_eq!(list.get_key(elements[0].id().unwrap()), None);
+    assert_eq!(list.dead_age_stats().dead, 1);
+    assert!(elements[0].is_target_alive());
+}
+
+#[test]
+fn keepalive_with_id_type() {
+    let list: WeakList<i32, u16> = WeakListBuilder::default().build_with_id_type();
+    let target = Rc::new(0);
+    let session = Rc::new(());
+    let element = WeakListElement::with_keepalive(Rc::downgrade(&target), Rc::downgrade(&session));
+    element.attach(&list);
+    assert_eq!(element.id(), Some(0u16));
+    assert_eq!(list.iter().count(), 1);
+    drop(session);
+    assert_eq!(list.iter().count(), 0);
+}
+
+#[test]
+fn compaction_preserves_order() {
+    let list = WeakList::default();
+    let entries: [_; 16] = array::from_fn(Element::new);
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    let raw = list.raw();
+    raw.set_auto_compact(false);
+    for entry in entries.iter().filter(|e| e.i % 3 != 1) {
+        entry.element.detach();
+    }
+    let order = |list: &WeakList<Element>| list.iter().map(|e| e.i).collect::<Vec<_>>();
+    assert_eq!(order(&list), [1, 4, 7, 10, 13]);
+    entries[0].element.attach(&list);
+    entries[2].element.attach(&list);
+    assert_eq!(order(&list), [0, 1, 2, 4, 7, 10, 13]);
+    entries[0].element.detach();
+    assert!(raw.compact());
+    assert_eq!(raw.slots(), 6);
+    assert_eq!(order(&list), [1, 2, 4, 7, 10, 13]);
+}
+
+#[test]
+fn reopen_pending() {
+    let list = WeakList::default();
+    let entries: [_; 4] = array::from_fn(Element::new);
+    for entry in &entries {
+        entry.element.set_reattach_on_clear(entry.i != 3);
+        entry.element.attach(&list);
+    }
+    list.clear();
+    assert!(list.iter().next().is_none());
+    // Detached and reattached elements are no longer pending.
+    entries[1].element.detach();
+    let other = WeakList::default();
+    entries[2].element.attach(&other);
+    // The id of the pending element is reused by another element.
+    let id = entries[0].element.id().unwrap();
+    assert_eq!(entries[3].element.attach_keyed(&list, id), Ok(()));
+    assert_eq!(list.reopen_pending(), 1);
+    assert_ne!(entries[0].element.id(), Some(id));
+    let mut order: Vec<_> = list.iter().map(|e| e.i).collect();
+    order.sort_unstable();
+    assert_eq!(order, [0, 3]);
+    assert_eq!(other.iter().map(|e| e.i).collect::<Vec<_>>(), [2]);
+    assert_eq!(list.reopen_pending(), 0);
+    entries[0].element.set_reattach_on_clear(false);
+    list.clear();
+    assert_eq!(list.reopen_pending(), 0);
+    assert!(list.iter().next().is_none());
+}
+
+#[test]
+fn invocation_stats() {
+    let list = WeakListBuilder::default().track_invocations(true).build();
+    let entries: [_; 3] = array::from_fn(Element::new);
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    let ids = entries.each_ref().map(|e| e.element.id().unwrap());
+    list.for_each(|e| {
+        if e.i == 0 {
+            e.element.detach();
+        }
+    });
+    assert_eq!(list.invocation_stats(ids[0]), None);
+    let stats = list.invocation_stats(ids[1]).unwrap();
+    assert_eq!(stats.count, 1);
+    assert_eq!(stats.last_iteration, Some(1));
+    assert_eq!(list.dispatch_some(1, |_| ()), 1);
+    assert_eq!(list.invocation_stats(ids[1]).unwrap().count, 2);
+    assert_eq!(list.invocation_stats(ids[2]).unwrap().count, 1);
+    let other = WeakList::default();
+    entries[1].element.attach(&other);
+    other.for_each(|_| ());
+    assert_eq!(
+        other.invocation_stats(entries[1].element.id().unwrap()),
+        None
+    );
+}
+
+#[test]
+fn partition() {
+    let list = WeakList::default();
+    let entries: [_; 5] = array::from_fn(Element::new);
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    let [e0, e1, e2, e3, e4] = entries;
+    let dead = Rc::downgrade(&e2);
+    let element = WeakListElement::new(dead.clone());
+    element.attach(&list);
+    drop(e2);
+    let (even, odd) = list.partition(|e| e.i % 2 == 0);
+    assert_eq!(even.iter().map(|e| e.i).collect::<Vec<_>>(), [0, 4]);
+    assert_eq!(odd.iter().map(|e| e.i).collect::<Vec<_>>(), [1, 3]);
+    drop((e0, e1, e3, e4, even, odd));
+    let (all, none) = list.partition(|_| true);
+    assert!(all.is_empty() && none.is_empty());
+}
+
+#[test]
+fn attach_visibility() {
+    let list = WeakList::default();
+    let entries: [_; 5] = array::from_fn(Element::new);
+    entries[0].element.attach(&list);
+    entries[1].element.attach(&list);
+    let mut iter = list.iter();
+    let mut other = list.iter();
+    assert_eq!(iter.next().unwrap().i, 0);
+    // The entry might reuse the storage of entry 0 which the iterator has already passed.
+    entries[0].element.detach();
+    entries[2].element.attach_visible_now(&list);
+    entries[3].element.attach_visible_now(&list);
+    entries[4].element.attach_visible_next(&list);
+    assert!(iter.will_yield(&entries[2].element));
+    assert!(iter.will_yield(&entries[3].element));
+    assert!(!iter.will_yield(&entries[4].element));
+    assert_eq!(iter.by_ref().map(|e| e.i).collect::<Vec<_>>(), [1, 2, 3]);
+    // Iterators that have returned None are no longer running.
+    entries[0].element.attach_visible_now(&list);
+    assert!(iter.next().is_none());
+    let mut seen: Vec<_> = other.by_ref().map(|e| e.i).collect();
+    seen.sort_unstable();
+    assert_eq!(seen, [0, 1, 2, 3]);
+    other.reset();
+    let mut seen: Vec<_> = other.map(|e| e.i).collect();
+    seen.sort_unstable();
+    assert_eq!(seen, [0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn dispatch_exactly_once() {
+    let list = WeakList::default();
+    let entries: [_; 3] = array::from_fn(Element::new);
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    let mut invoked = Vec::new();
+    let n = list.dispatch_exactly_once(|e| {
+        invoked.push(e.i);
+        for entry in &entries {
+            entry.element.detach();
+            entry.element.attach(&list);
+        }
+    });
+    assert_eq!(n, invoked.len());
+    invoked.sort();
+    assert_eq!(invoked, [0, 1, 2]);
+}
+
+#[test]
+fn debug_iter() {
+    let list = WeakList::default();
+    let entries: [_; 3] = array::from_fn(Element::new);
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    let mut iter = list.iter();
+    assert_eq!(iter.next().unwrap().i, 0);
+    assert_eq!(
+        format!("{:?}", iter),
+        "[Entry { id: 1, alive: true }, Entry { id: 2, alive: true }]",
+    );
+    assert!(format!("{:?}", DebugTargets::new(&iter)).starts_with("[Element { i: 1,"));
+}
+
+#[test]
+fn iter_indexed() {
+    let list = WeakList::default();
+    let entries: [_; 40] = array::from_fn(Element::new);
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    let epoch = list.compaction_epoch();
+    for entry in &entries[..30] {
+        entry.element.detach();
+    }
+    assert_eq!(list.compaction_epoch(), epoch);
+    let mut iter = list.iter_indexed();
+    let compacted = list.compaction_epoch();
+    assert_ne!(compacted, epoch);
+    assert_eq!(iter.next().map(|(slot, e)| (slot, e.i)), Some((0, 30)));
+    entries[30].element.detach();
+    entries[31].element.detach();
+    let slots: Vec<_> = iter.map(|(slot, e)| (slot, e.i)).collect();
+    assert_eq!(slots, (2..10).map(|i| (i, i + 30)).collect::<Vec<_>>());
+    assert_eq!(list.compaction_epoch(), compacted);
+}
+
+#[test]
+fn iter_limited() {
+    let list = WeakList::default();
+    let entries: [_; 5] = array::from_fn(Element::new);
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    let collect = |max| list.iter_limited(max).map(|e| e.i).collect::<Vec<_>>();
+    assert_eq!(collect(2), [0, 1]);
+    assert_eq!(collect(2), [2, 3]);
+    entries[0].element.detach();
+    assert_eq!(collect(2), [4, 1]);
+    assert_eq!(collect(10), [2, 3, 4, 1]);
+    assert_eq!(collect(0), []);
+}
+
+#[test]
+fn max_entries_per_target() {
+    let list = WeakListBuilder::default().max_entries_per_target(2).build();
+    let target = Rc::new(0);
+    let other = Rc::new(1);
+    let elements: [_; 3] = array::from_fn(|_| WeakListElement::new(Rc::downgrade(&target)));
+    let element = WeakListElement::new(Rc::downgrade(&other));
+    assert_eq!(elements[0].try_attach(&list), Ok(()));
+    assert_eq!(elements[1].try_attach(&list), Ok(()));
+    assert_eq!(elements[1].try_attach(&list), Ok(()));
+    assert_eq!(
+        elements[2].try_attach(&list),
+        Err(Error::TargetLimitReached)
+    );
+    assert_eq!(element.try_attach(&list), Ok(()));
+    elements[0].detach();
+    assert_eq!(elements[2].try_attach(&list), Ok(()));
+    assert_eq!(list.iter().filter(|e| Rc::ptr_eq(e, &target)).count(), 2);
+}
+
+#[cfg(feature = "debug-registry")]
+#[test]
+fn debug_registry() {
+    use alloc::string::String;
+
+    let dump = || {
+        let mut out = String::new();
+        crate::dump_all(&mut out).unwrap();
+        out
+    };
+    let list = WeakListBuilder::default().debug_name("first").build();
+    let other = WeakList::<Element>::default();
+    let entries = [Rc::new(0), Rc::new(1)];
+    let elements = entries
+        .each_ref()
+        .map(|e| WeakListElement::new(Rc::downgrade(e)));
+    for element in &elements {
+        element.attach(&list);
+    }
+    let [_, entry] = entries;
+    drop(entry);
+    list.iter().for_each(drop);
+    let out = dump();
+    // The thread-safe lists of the other tests are also printed.
+    let lines: Vec<_> = out.lines().filter(|l| l.starts_with("WeakList ")).collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("WeakList \"first\" at "));
+    assert!(lines[0].ends_with(": 2 entries, 1 alive, 0 active iterators, 1 iterations"));
+    assert!(lines[1].starts_with("WeakList <unnamed> at "));
+    drop(list);
+    drop(other);
+    assert!(!dump().lines().any(|l| l.starts_with("WeakList ")));
+}
+
+#[cfg(feature = "debug-registry")]
+#[test]
+fn debug_registry_writer_creates_lists() {
+    use {alloc::string::String, core::fmt::Write};
+
+    struct Writer(String);
+
+    impl Write for Writer {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            drop(WeakList::<u32>::default());
+            #[cfg(feature = "sync")]
+            drop(crate::SyncWeakList::<u32>::default());
+            self.0.write_str(s)
+        }
+    }
+
+    let _list = WeakListBuilder::default()
+        .debug_name("writer-creates-lists")
+        .build::<u32>();
+    let mut out = Writer(String::new());
+    crate::dump_all(&mut out).unwrap();
+    assert!(out.0.contains("\"writer-creates-lists\""));
+}
+
+#[test]
+fn defer_attach() {
+    let list = WeakListBuilder::default().max_entries_per_target(1).build();
+    let entry = Element::new(0);
+    let element = WeakListElement::new(Rc::downgrade(&entry));
+    assert_eq!(list.adopt_pending(&entry.element), Err(Error::NotDeferred));
+    entry.element.defer_attach();
+    element.defer_attach();
+    assert_eq!(list.adopt_pending(&entry.element), Ok(()));
+    assert_eq!(list.adopt_pending(&entry.element), Err(Error::NotDeferred));
+    assert_eq!(list.adopt_pending(&element), Err(Error::TargetLimitReached));
+    assert!(element.is_attach_deferred());
+    entry.element.defer_attach();
+    assert!(list.iter().next().is_none());
+    assert_eq!(list.adopt_pending(&element), Ok(()));
+    entry.element.detach();
+    assert!(!entry.element.is_attach_deferred());
+    assert_eq!(list.adopt_pending(&entry.element), Err(Error::NotDeferred));
+    assert_eq!(list.iter().count(), 1);
+}
+
+#[test]
+fn map_collect() {
+    let list = WeakList::default();
+    let entries: [_; 3] = array::from_fn(Element::new);
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    let [_, entry, _] = entries;
+    drop(entry);
+    let out = list.map_collect(|e| {
+        assert!(list.iter().any(|o| Rc::ptr_eq(&o, &e)));
+        e.i
+    });
+    assert_eq!(out, [0, 2]);
+    assert_eq!(out.capacity(), 2);
+}
+
+#[test]
+fn set_enabled() {
+    let list1 = WeakList::default();
+    let list2 = WeakList::default();
+    let entries: [_; 3] = array::from_fn(Element::new);
+    for entry in &entries {
+        entry.element.attach(&list1);
+    }
+    entries[1].element.set_enabled(false);
+    assert!(!entries[1].element.is_enabled());
+    assert_eq!(list1.iter().map(|e| e.i).collect::<Vec<_>>(), [0, 2]);
+    let mut iter = list1.iter();
+    assert_eq!(iter.next().unwrap().i, 0);
+    entries[1].element.set_enabled(true);
+    assert_eq!(iter.next().unwrap().i, 1);
+    entries[2].element.set_enabled(false);
+    assert!(iter.next().is_none());
+    assert_eq!(iter.skipped(), 0);
+    entries[2].element.attach(&list2);
+    assert!(list2.iter().next().is_none());
+    entries[2].element.set_enabled(true);
+    assert_eq!(list2.iter().next().unwrap().i, 2);
+}
+
+#[test]
+fn partition_disabled() {
+    let list = WeakList::default();
+    let entries: [_; 3] = array::from_fn(Element::new);
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    entries[0].element.set_enabled(false);
+    let (even, odd) = list.partition(|e| e.i % 2 == 0);
+    assert_eq!(even.iter().map(|e| e.i).collect::<Vec<_>>(), [2]);
+    assert_eq!(odd.iter().map(|e| e.i).collect::<Vec<_>>(), [1]);
+}
+
+#[test]
+fn map_collect_disabled() {
+    let list = WeakList::default();
+    let entries: [_; 2] = array::from_fn(Element::new);
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    entries[0].element.set_enabled(false);
+    assert_eq!(list.iter().count(), 1);
+    assert_eq!(list.map_collect(|e| e.i), [1]);
+}
+
+#[test]
+#[cfg(feature = "deterministic")]
+fn targets_by_id_disabled() {
+    let list = WeakList::default();
+    let entries: [_; 3] = array::from_fn(Element::new);
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    entries[1].element.set_enabled(false);
+    let order: Vec<_> = list.targets_by_id().iter().map(|e| e.i).collect();
+    assert_eq!(order, [0, 2]);
+}
+
+#[test]
+fn iter_retained() {
+    let list = WeakList::default();
+    let entries: [_; 3] = array::from_fn(Element::new);
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    let weak = entries.each_ref().map(Rc::downgrade);
+    let mut iter = list.iter_retained();
+    drop(entries);
+    assert!(weak.iter().all(|w| w.strong_count() == 1));
+    let first = iter.next().unwrap();
+    assert_eq!(first.i, 0);
+    first.element.detach();
+    drop(first);
+    assert_eq!(weak[0].strong_count(), 0);
+    iter.clone().for_each(|e| e.element.detach());
+    assert_eq!(weak[2].strong_count(), 1);
+    drop(iter);
+    assert!(weak.iter().all(|w| w.strong_count() == 0));
+    assert!(list.iter().next().is_none());
+}
+
+#[test]
+fn ensure_attached() {
+    let list1 = WeakList::default();
+    let list2 = WeakList::default();
+    let entries: [_; 3] = array::from_fn(Element::new);
+    for entry in &entries {
+        entry.element.attach(&list1);
+    }
+    let id = entries[0].element.id();
+    assert_eq!(entries[0].element.ensure_attached(&list1), Ok(false));
+    assert_eq!(entries[0].element.id(), id);
+    assert_eq!(list1.iter().map(|e| e.i).collect::<Vec<_>>(), [0, 1, 2]);
+    assert_eq!(entries[0].element.ensure_attached(&list2), Ok(true));
+    assert_eq!(list1.iter().map(|e| e.i).collect::<Vec<_>>(), [1, 2]);
+    assert_eq!(list2.iter().map(|e| e.i).collect::<Vec<_>>(), [0]);
+    list1.clear();
+    assert_eq!(entries[1].element.ensure_attached(&list1), Ok(true));
+    assert_eq!(list1.iter().map(|e| e.i).collect::<Vec<_>>(), [1]);
+}
+
+#[test]
+fn live_len_hint() {
+    let list = WeakList::default();
+    let targets: [_; 4] = array::from_fn(Rc::new);
+    let elements = targets
+        .each_ref()
+        .map(|t| WeakListElement::new(Rc::downgrade(t)));
+    for element in &elements[..3] {
+        element.attach(&list);
+    }
+    assert_eq!(list.live_len_hint(), 3);
+    let [t0, t1, t2, _] = targets;
+    drop((t0, t1, t2));
+    assert_eq!(list.live_len_hint(), 3);
+    list.iter().for_each(drop);
+    assert_eq!(list.live_len_hint(), 0);
+    list.iter().for_each(drop);
+    assert_eq!(list.live_len_hint(), 0);
+    elements[0].detach();
+    assert_eq!(list.live_len_hint(), 0);
+    let id = elements[1].id().unwrap();
+    assert_eq!(list.replace(id, &elements[3]), Ok(()));
+    assert_eq!(list.live_len_hint(), 1);
+    elements[0].attach(&list);
+    assert_eq!(list.live_len_hint(), 2);
+    list.clear();
+    assert_eq!(list.live_len_hint(), 0);
+    assert_eq!(list.iter().count(), 0);
+}
+
+#[test]
+fn for_each_mut() {
+    use {crate::unsync::BorrowPolicy, core::cell::RefCell};
+
+    let list = WeakList::default();
+    let targets: [_; 3] = array::from_fn(|i| Rc::new(RefCell::new(i)));
+    let elements = targets
+        .each_ref()
+        .map(|t| WeakListElement::new(Rc::downgrade(t)));
+    for element in &elements {
+        element.attach(&list);
+    }
+    let mut nested = Vec::new();
+    let skipped = list.for_each_mut(BorrowPolicy::Skip, |outer| {
+        let mut sum = 0;
+        nested.push(list.for_each_mut(BorrowPolicy::Skip, |inner| sum += *inner));
+        *outer += sum;
+    });
+    assert_eq!(skipped, 0);
+    assert_eq!(nested, [1, 1, 1]);
+    assert_eq!(targets.each_ref().map(|t| *t.borrow()), [3, 6, 11]);
+}
+
+#[test]
+#[should_panic]
+fn for_each_mut_panic() {
+    use {crate::unsync::BorrowPolicy, core::cell::RefCell};
+
+    let list = WeakList::default();
+    let target = Rc::new(RefCell::new(0));
+    let element = WeakListElement::new(Rc::downgrade(&target));
+    element.attach(&list);
+    let _borrow = target.borrow();
+    list.for_each_mut(BorrowPolicy::Panic, |t| *t += 1);
+}
+
+#[test]
+fn multi_element() {
+    use crate::unsync::MultiWeakListElement;
+
+    let lists: [_; 3] = array::from_fn(|_| WeakList::default());
+    let target = Rc::new(1);
+    let element = MultiWeakListElement::new(Rc::downgrade(&target));
+    let ids = lists.each_ref().map(|l| element.attach(l));
+    let twice = element.attach(&lists[0]);
+    assert_eq!(lists[0].iter().count(), 2);
+    assert!(element.detach(twice));
+    assert!(!element.detach(twice));
+    assert!(!element.is_attached(twice));
+    assert_eq!(lists.each_ref().map(|l| l.iter().count()), [1, 1, 1]);
+    lists[1].clear();
+    assert!(!element.is_attached(ids[1]));
+    assert!(element.is_attached(ids[2]));
+    let id = element.attach(&lists[1]);
+    assert!(ids.iter().all(|i| *i != id));
+    assert!(!element.detach(ids[1]));
+    assert_eq!(lists.each_ref().map(|l| l.iter().count()), [1, 1, 1]);
+    element.detach_all();
+    assert_eq!(lists.each_ref().map(|l| l.iter().count()), [0, 0, 0]);
+    element.attach(&lists[2]);
+    drop(element);
+    assert!(lists[2].iter().next().is_none());
+}
+
+#[test]
+fn iteration_order() {
+    let list = WeakList::default();
+    let entries: [_; 16] = array::from_fn(Element::new);
+    let mut state = 1u64;
+    let mut random = move || {
+        // xorshift64
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let mut prev: Vec<usize> = Vec::new();
+    let mut detached = Vec::new();
+    for _ in 0..4096 {
+        let r = random();
+        let entry = &entries[r as usize % entries.len()];
+        match (r >> 32) % 4 {
+            0 | 1 => {
+                if entry.element.id().is_some() {
+                    detached.push(entry.i);
+                }
+                entry.element.attach(&list);
+            }
+            2 => {
+                if entry.element.id().is_some() {
+                    detached.push(entry.i);
+                }
+                entry.element.detach();
+            }
+            _ => {
+                let cur: Vec<_> = list.iter().map(|e| e.i).collect();
+                // The relative order of the remaining entries never changes.
+                let remains = |i: &usize| prev.contains(i) && !detached.contains(i);
+                let kept: Vec<_> = prev.iter().filter(|i| remains(i)).collect();
+                let old: Vec<_> = cur.iter().filter(|i| remains(i)).collect();
+                assert_eq!(kept, old);
+                prev = cur;
+                detached.clear();
+            }
+        }
+    }
+}
+
+#[test]
+fn view() {
+    let list = WeakList::default();
+    let entries: [_; 3] = array::from_fn(Element::new);
+    for entry in &entries {
+        entry.element.attach(&list);
+    }
+    let view = list.view();
+    let copy = view;
+    let mut sum = 0;
+    for entry in view {
+        sum += entry.i;
+        entries[2].element.detach();
+    }
+    assert_eq!(sum, 1);
+    assert_eq!(copy.live_len_hint(), 2);
+    assert_eq!(copy.for_each(|e| sum += e.i), 0);
+    assert_eq!(sum, 2);
+    assert_eq!(copy.dead_age_stats().dead, 0);
+}
+
+#[test]
+fn detach_handle() {
+    let lists = [(); 2].map(|_| WeakList::default());
+    let entry = Rc::new(1);
+    let element = WeakListElement::new(Rc::downgrade(&entry));
+    element.attach(&lists[0]);
+    let handle = element.detach_handle().clone();
+    element.attach(&lists[1]);
+    handle.detach();
+    assert!(lists[1].iter().next().is_none());
+    assert!(element.id().is_none());
+    handle.detach();
+    element.attach(&lists[0]);
+    drop(element);
+    handle.detach();
+    assert!(lists[0].iter().next().is_none());
+    assert!(format!("{handle:?}").starts_with("DetachHandle"));
+}
+
+#[test]
+fn iter_by_recency() {
+    let list = WeakList::default();
+    let entries: [_; 4] = array::from_fn(Rc::new);
+    let elements = entries
+        .each_ref()
+        .map(|e| WeakListElement::new(Rc::downgrade(e)));
+    for element in &elements {
+        element.attach(&list);
+    }
+    elements[1].attach(&list);
+    assert_eq!(elements[1].attach_seq(), Some(4));
+    elements[3].detach();
+    assert_eq!(elements[3].attach_seq(), None);
+    assert!(list
+        .replace(elements[0].id().unwrap(), &elements[3])
+        .is_ok());
+    assert_eq!(elements[3].attach_seq(), Some(5));
+    let mut iter = list.iter_by_recency();
+    assert_eq!(iter.next().map(|e| *e), Some(3));
+    elements[1].detach();
+    elements[0].attach(&list);
+    assert_eq!(iter.map(|e| *e).collect::<Vec<_>>(), [2]);
+    let recent: Vec<_> = list.iter_by_recency().map(|e| *e).collect();
+    assert_eq!(recent, [0, 3, 2]);
+}
+
+#[test]
+fn element_size() {
+    use {
+        super::{EntryData, Member},
+        core::mem::size_of,
+    };
+
+    assert_eq!(size_of::<WeakListElement<u8>>(), 4 * size_of::<usize>());
+    assert!(size_of::<EntryData<u8, u32>>() < size_of::<EntryData<u8, u64>>());
+    assert!(size_of::<Member<u8, u64>>() <= 64);
+}
+
+#[test]
+fn skip_target() {
+    let list = WeakList::default();
+    let entries: [_; 3] = array::from_fn(Rc::new);
+    let elements = entries
+        .each_ref()
+        .map(|e| WeakListElement::new(Rc::downgrade(e)));
+    for element in &elements {
+        element.attach(&list);
+    }
+    let twice = WeakListElement::new(Rc::downgrade(&entries[1]));
+    twice.attach(&list);
+    let mut iter = list.iter();
+    assert_eq!(iter.peek(), Some(&entries[0]));
+    let mut iter = iter.skip_target(&entries[0]).skip_target(&entries[1]);
+    assert_eq!(iter.next().map(|e| *e), Some(2));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.skipped(), 0);
+    iter.reset();
+    assert_eq!(iter.clone().map(|e| *e).collect::<Vec<_>>(), [0, 2]);
+}
+
+#[test]
+fn policy() {
+    struct Positive;
+    impl LivenessPolicy<Cell<u32>> for Positive {
+        fn is_live(target: &Cell<u32>) -> bool {
+            target.get() > 0
+        }
+    }
+    let list = PolicyWeakList::<_, Positive>::default();
+    let entries: [_; 3] = array::from_fn(|i| Rc::new(Cell::new(i as u32)));
+    let elements = entries
+        .each_ref()
+        .map(|e| WeakListElement::new(Rc::downgrade(e)));
+    for element in &elements {
+        element.attach(list.list());
+    }
+    let values = || list.iter().map(|e| e.get()).collect::<Vec<_>>();
+    assert_eq!(values(), [1, 2]);
+    entries[0].set(3);
+    entries[2].set(0);
+    assert_eq!(values(), [3, 1]);
+    assert_eq!(list.list().iter().count(), 3);
+    let list = PolicyWeakList::<_, Upgrade>::from_list(list.into_list());
+    assert_eq!(list.iter().count(), 3);
+}
+
+#[test]
+fn notify_coalesced() {
+    let list = WeakList::default();
+    let entries: [_; 3] = array::from_fn(Rc::new);
+    let elements = entries
+        .each_ref()
+        .map(|e| WeakListElement::new(Rc::downgrade(e)));
+    for element in &elements {
+        element.attach(&list);
+    }
+    let [_first, _second, third] = entries;
+    drop(third);
+    let calls = core::cell::Cell::new(0);
+    let res = list.notify_coalesced(0, |_| {
+        calls.set(calls.get() + 1);
+        assert_eq!(list.notify_coalesced(0, |_| unreachable!()), None);
+        list.notify_coalesced(1, |_| calls.set(calls.get() + 10));
+    });
+    assert_eq!(res, Some(1));
+    assert_eq!(calls.get(), 22);
+    assert_eq!(list.notify_coalesced(1, |_| calls.set(0)), Some(1));
+    assert_eq!(calls.get(), 0);
+}
+
+#[test]
+fn replace_resets_entry() {
+    let list = WeakListBuilder::default().track_invocations(true).build();
+    let entries: [_; 6] = array::from_fn(Rc::new);
+    let elements = entries
+        .each_ref()
+        .map(|e| WeakListElement::new(Rc::downgrade(e)));
+    elements[1].attach_with_group(&list, 1);
+    elements[2].attach_with_epoch(&list, 5);
+    list.for_each(drop);
+    elements[0].attach_once(&list);
+    let ids = [0, 1, 2].map(|i| elements[i].id().unwrap());
+    for (id, element) in ids.into_iter().zip(&elements[3..]) {
+        assert_eq!(list.replace(id, element), Ok(()));
+    }
+    for id in ids {
+        assert_eq!(list.invocation_stats(id).unwrap().count, 0);
+    }
+    list.detach_group(1);
+    assert_eq!(list.iter_epoch_at_most(0).count(), 3);
+    assert_eq!(list.iter().map(|e| *e).sum::<usize>(), 12);
+    assert_eq!(list.iter().map(|e| *e).sum::<usize>(), 12);
+}
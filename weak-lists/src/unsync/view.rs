@@ -0,0 +1,115 @@
+use {
+    crate::{
+        id::EntryId,
+        unsync::{DeadAgeStats, Iter, WeakList, WeakListView},
+    },
+    alloc::rc::Rc,
+    core::fmt::{Debug, Formatter},
+};
+
+impl<T, I> WeakList<T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Returns a read-only view of the list.
+    ///
+    /// The view can be handed to code that should be able to iterate over the list but
+    /// not to attach elements to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use weak_lists::{unsync::WeakListView, WeakList, WeakListElement};
+    ///
+    /// fn dispatch(view: WeakListView<'_, u32>) -> u32 {
+    ///     view.iter().map(|e| *e).sum()
+    /// }
+    ///
+    /// let list = WeakList::default();
+    /// let entries = [Rc::new(1), Rc::new(2)];
+    /// let elements = entries.each_ref().map(|e| WeakListElement::new(Rc::downgrade(e)));
+    /// for element in &elements {
+    ///     element.attach(&list);
+    /// }
+    /// assert_eq!(dispatch(list.view()), 3);
+    /// ```
+    pub fn view(&self) -> WeakListView<'_, T, I> {
+        WeakListView { list: self }
+    }
+}
+
+impl<'a, T, I> WeakListView<'a, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    /// Creates an iterator over the entries of the list.
+    ///
+    /// See [WeakList::iter].
+    pub fn iter(&self) -> Iter<'a, T, I> {
+        self.list.iter()
+    }
+
+    /// Invokes the callback for each entry of the list.
+    ///
+    /// See [WeakList::for_each].
+    pub fn for_each(&self, f: impl FnMut(Rc<T>)) -> usize {
+        self.list.for_each(f)
+    }
+
+    /// Returns an estimate of the number of live entries in the list.
+    ///
+    /// See [WeakList::live_len_hint].
+    pub fn live_len_hint(&self) -> usize {
+        self.list.live_len_hint()
+    }
+
+    /// Returns statistics about the entries whose targets have been dropped.
+    ///
+    /// See [WeakList::dead_age_stats].
+    pub fn dead_age_stats(&self) -> DeadAgeStats {
+        self.list.dead_age_stats()
+    }
+}
+
+impl<T, I> Clone for WeakListView<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, I> Copy for WeakListView<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+}
+
+impl<T, I> Debug for WeakListView<'_, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("WeakListView").field(self.list).finish()
+    }
+}
+
+impl<'a, T, I> IntoIterator for WeakListView<'a, T, I>
+where
+    T: ?Sized,
+    I: EntryId,
+{
+    type Item = Rc<T>;
+    type IntoIter = Iter<'a, T, I>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -0,0 +1,212 @@
+//! A list of wakers owned by weakly held objects.
+//!
+//! This is a building block for asynchronous primitives. Each element holds a weak
+//! reference to its owner, usually the future that registered the waker, and the most
+//! recently registered waker. [WakerList::wake_all] wakes the wakers of all elements
+//! whose owner is still alive.
+
+#[cfg(test)]
+mod tests;
+
+use {
+    crate::handle::{HandleList, HandleListElement, WeakHandle},
+    alloc::rc::Weak,
+    core::{
+        cell::{Cell, RefCell},
+        fmt::{Debug, Formatter},
+        task::Waker,
+    },
+};
+
+/// A list of wakers.
+///
+/// # Examples
+///
+/// ```
+/// use {
+///     std::{rc::Rc, task::Waker},
+///     weak_lists::waker::{WakerList, WakerListElement},
+/// };
+///
+/// let list = WakerList::default();
+/// let owner = Rc::new(());
+/// let element = WakerListElement::new(Rc::downgrade(&owner));
+/// element.register(Waker::noop());
+/// element.attach(&list);
+/// assert_eq!(list.wake_all(), 1);
+/// drop(owner);
+/// assert_eq!(list.wake_all(), 0);
+/// ```
+pub struct WakerList<T>
+where
+    T: ?Sized,
+{
+    list: HandleList<WakerHandle<T>>,
+}
+
+/// An element of a [WakerList].
+///
+/// When this object is dropped, it detaches itself from its current list.
+pub struct WakerListElement<T>
+where
+    T: ?Sized,
+{
+    element: HandleListElement<WakerHandle<T>>,
+}
+
+struct WakerHandle<T>
+where
+    T: ?Sized,
+{
+    owner: Weak<T>,
+    waker: RefCell<Option<Waker>>,
+    remove_on_wake: Cell<bool>,
+}
+
+impl<T> WeakHandle for WakerHandle<T>
+where
+    T: ?Sized,
+{
+    type Strong = Waker;
+
+    fn upgrade(&self) -> Option<Self::Strong> {
+        if self.owner.strong_count() == 0 {
+            return None;
+        }
+        // This function is only called by wake_all.
+        if self.remove_on_wake.get() {
+            return self.waker.borrow_mut().take();
+        }
+        self.waker.borrow().clone()
+    }
+}
+
+impl<T> WakerList<T>
+where
+    T: ?Sized,
+{
+    /// Wakes the wakers of all elements whose owner is still alive.
+    ///
+    /// Returns the number of wakers that were woken. Elements that have not registered a
+    /// waker are skipped.
+    pub fn wake_all(&self) -> usize {
+        let mut woken = 0;
+        for waker in self.list.iter() {
+            waker.wake();
+            woken += 1;
+        }
+        woken
+    }
+
+    /// Removes all elements from the list.
+    pub fn clear(&self) {
+        self.list.clear();
+    }
+}
+
+impl<T> WakerListElement<T>
+where
+    T: ?Sized,
+{
+    /// Creates a new element owned by `owner`.
+    pub fn new(owner: Weak<T>) -> Self {
+        Self {
+            element: HandleListElement::new(WakerHandle {
+                owner,
+                waker: Default::default(),
+                remove_on_wake: Cell::new(false),
+            }),
+        }
+    }
+
+    /// Registers the waker that is woken by [WakerList::wake_all].
+    ///
+    /// This replaces the previously registered waker unless both wake the same task.
+    pub fn register(&self, waker: &Waker) {
+        let mut slot = self.element.handle().waker.borrow_mut();
+        if slot.as_ref().is_some_and(|w| w.will_wake(waker)) {
+            return;
+        }
+        let prev = slot.replace(waker.clone());
+        drop(slot);
+        drop(prev);
+    }
+
+    /// Sets whether the registered waker is removed when it is woken.
+    ///
+    /// If this is enabled, [WakerList::wake_all] wakes the registered waker at most once.
+    /// Afterwards, the element is skipped until a new waker is [registered](Self::register).
+    /// This is useful for primitives whose futures register their waker each time they
+    /// are polled.
+    ///
+    /// The default is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use {
+    ///     std::{rc::Rc, task::Waker},
+    ///     weak_lists::waker::{WakerList, WakerListElement},
+    /// };
+    ///
+    /// let list = WakerList::default();
+    /// let owner = Rc::new(());
+    /// let element = WakerListElement::new(Rc::downgrade(&owner));
+    /// element.set_remove_on_wake(true);
+    /// element.register(Waker::noop());
+    /// element.attach(&list);
+    /// assert_eq!(list.wake_all(), 1);
+    /// assert_eq!(list.wake_all(), 0);
+    /// element.register(Waker::noop());
+    /// assert_eq!(list.wake_all(), 1);
+    /// ```
+    pub fn set_remove_on_wake(&self, remove: bool) {
+        self.element.handle().remove_on_wake.set(remove);
+    }
+
+    /// Attaches the element to a list.
+    ///
+    /// If this element was previously attached to a list, it is automatically detached
+    /// from that list.
+    pub fn attach(&self, to: &WakerList<T>) {
+        self.element.attach(&to.list);
+    }
+
+    /// Detaches the element from its current list.
+    pub fn detach(&self) {
+        self.element.detach();
+    }
+}
+
+impl<T> Default for WakerList<T>
+where
+    T: ?Sized,
+{
+    fn default() -> Self {
+        Self {
+            list: Default::default(),
+        }
+    }
+}
+
+impl<T> Debug for WakerList<T>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("WakerList")
+            .field("list", &self.list)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Debug for WakerListElement<T>
+where
+    T: ?Sized,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("WakerListElement")
+            .field("element", &self.element)
+            .finish_non_exhaustive()
+    }
+}
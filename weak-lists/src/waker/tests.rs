@@ -0,0 +1,83 @@
+use {
+    crate::waker::{WakerList, WakerListElement},
+    alloc::{rc::Rc, sync::Arc},
+    core::{
+        sync::atomic::{AtomicUsize, Ordering::Relaxed},
+        task::{RawWaker, RawWakerVTable, Waker},
+    },
+};
+
+fn counting_waker(count: &Arc<AtomicUsize>) -> Waker {
+    static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake_by_ref, drop);
+
+    unsafe fn clone(data: *const ()) -> RawWaker {
+        unsafe { Arc::increment_strong_count(data.cast::<AtomicUsize>()) };
+        RawWaker::new(data, &VTABLE)
+    }
+
+    unsafe fn wake(data: *const ()) {
+        unsafe {
+            wake_by_ref(data);
+            drop(data);
+        }
+    }
+
+    unsafe fn wake_by_ref(data: *const ()) {
+        unsafe { (*data.cast::<AtomicUsize>()).fetch_add(1, Relaxed) };
+    }
+
+    unsafe fn drop(data: *const ()) {
+        unsafe { Arc::decrement_strong_count(data.cast::<AtomicUsize>()) };
+    }
+
+    let data = Arc::into_raw(count.clone()).cast::<()>();
+    unsafe { Waker::from_raw(RawWaker::new(data, &VTABLE)) }
+}
+
+#[test]
+fn wake_all() {
+    let list = WakerList::default();
+    let counts: [_; 3] = core::array::from_fn(|_| Arc::new(AtomicUsize::new(0)));
+    let owners: [_; 3] = core::array::from_fn(|_| Rc::new(()));
+    let elements = owners
+        .each_ref()
+        .map(|o| WakerListElement::new(Rc::downgrade(o)));
+    for element in &elements {
+        element.attach(&list);
+    }
+    elements[0].register(&counting_waker(&counts[0]));
+    elements[1].register(&counting_waker(&counts[1]));
+    assert_eq!(list.wake_all(), 2);
+    let [_, owner, _] = owners;
+    drop(owner);
+    elements[2].register(&counting_waker(&counts[2]));
+    elements[0].register(&counting_waker(&counts[1]));
+    assert_eq!(list.wake_all(), 2);
+    assert_eq!(counts.each_ref().map(|c| c.load(Relaxed)), [1, 2, 1]);
+    elements[2].detach();
+    assert_eq!(list.wake_all(), 1);
+    drop(elements);
+    for count in &counts {
+        assert_eq!(Arc::strong_count(count), 1);
+    }
+}
+
+#[test]
+fn remove_on_wake() {
+    let list = WakerList::default();
+    let count = Arc::new(AtomicUsize::new(0));
+    let owner = Rc::new(());
+    let element = WakerListElement::new(Rc::downgrade(&owner));
+    element.set_remove_on_wake(true);
+    element.attach(&list);
+    element.register(&counting_waker(&count));
+    assert_eq!(list.wake_all(), 1);
+    assert_eq!(list.wake_all(), 0);
+    assert_eq!(count.load(Relaxed), 1);
+    assert_eq!(Arc::strong_count(&count), 1);
+    element.register(&counting_waker(&count));
+    element.set_remove_on_wake(false);
+    assert_eq!(list.wake_all(), 1);
+    assert_eq!(list.wake_all(), 1);
+    assert_eq!(count.load(Relaxed), 3);
+}